@@ -6,14 +6,49 @@ import assert from 'node:assert/strict'
 import {
   Instruction,
   InstrCategory,
+  InstrId,
+  InstrIdType,
   Config,
   Abi,
   GprO32,
   Utils,
+  CodeSection,
+  RspSection,
+  Function,
+  XrefKind,
+  AccessType,
+  ValueKind,
+  BaseKind,
+  Compiler,
+  LintSeverity,
+  lint,
+  assemble,
+  assembleBlock,
+  RelocationType,
+  relocate,
+  RelocateIssueKind,
+  PatchBuilder,
+  diffSections,
+  DiffKind,
+  matchFingerprints,
+  SignatureDatabase,
+  identifyFunctions,
+  search,
+  statistics,
   getVersion,
   getVersionInfo,
 } from '../index.js'
 
+// The words `assembleBlock` encodes `source` to at `vram`
+const wordsOf = (source, vram) => {
+  const data = assembleBlock(source, { vram }).data
+  const words = []
+  for (let i = 0; i < data.length; i += 4) {
+    words.push(data.readUInt32BE(i))
+  }
+  return words
+}
+
 test('Instruction creation', (t) => {
   const instr = new Instruction(0x8d4a7e18, 0x80000000)
   assert.equal(instr.word, 0x8d4a7e18)
@@ -25,6 +60,9 @@ test('Instruction disassembly', (t) => {
   const instr = new Instruction(0x24010001)
   const disassembly = instr.disassemble()
   assert(disassembly.includes('addiu'))
+
+  const jal = new Instruction(0x0c000004)
+  assert(jal.disassemble(undefined, 0x90000000).includes('func_90000010'))
 })
 
 test('Instruction bit fields', (t) => {
@@ -146,3 +184,1461 @@ test('Version functions', (t) => {
   assert.equal(info.version, '1.14.3')
   assert.equal(info.name, 'rabbitizer')
 })
+
+// A small function calling a leaf, with two data words in between
+//   func_80000000: addiu $sp, -0x18; sw $ra; jal func_80000024; nop
+//                  lw $ra; jr $ra; addiu $sp, 0x18
+//   .word 0x3F800000, 0xFFFFFFFF
+//   func_80000024: jr $ra; nop
+const sampleWords = [
+  0x27bdffe8, 0xafbf0014, 0x0c000009, 0x00000000, 0x8fbf0014, 0x03e00008,
+  0x27bd0018, 0x3f800000, 0xffffffff, 0x03e00008, 0x00000000,
+]
+
+function wordsToBuffer(words, littleEndian = false) {
+  const buffer = Buffer.alloc(words.length * 4)
+  words.forEach((word, i) => {
+    if (littleEndian) {
+      buffer.writeUInt32LE(word, i * 4)
+    } else {
+      buffer.writeUInt32BE(word, i * 4)
+    }
+  })
+  return buffer
+}
+
+test('CodeSection linear sweep', (t) => {
+  const section = new CodeSection(wordsToBuffer(sampleWords), 0x80000000)
+  assert.equal(section.vram, 0x80000000)
+  assert.equal(section.size, sampleWords.length * 4)
+  assert.equal(section.category, 'cpu')
+
+  const instructions = section.instructions()
+  assert.equal(instructions.length, sampleWords.length)
+  assert.equal(instructions[2].vram, 0x80000008)
+  assert(instructions[2].isFunctionCall())
+  assert.equal(section.instructionAt(0x80000014).word, 0x03e00008)
+  assert.equal(section.instructionAt(0x80000100), null)
+})
+
+test('CodeSection endianness', (t) => {
+  const big = new CodeSection(wordsToBuffer(sampleWords), 0x80000000)
+  const little = new CodeSection(
+    wordsToBuffer(sampleWords, true),
+    0x80000000,
+    'cpu',
+    'little',
+  )
+  assert.equal(
+    little.instructionAt(0x80000000).word,
+    big.instructionAt(0x80000000).word,
+  )
+
+  assert.throws(() => new CodeSection(Buffer.alloc(6), 0))
+  assert.throws(() => new CodeSection(Buffer.alloc(4), 0, 'cpu', 'middle'))
+})
+
+test('Recursive descent separates code and data', (t) => {
+  const section = CodeSection.fromWordList(sampleWords, 0x80000000)
+  const result = section.recursiveDescent([0x80000000])
+
+  assert.deepEqual(result.functions, [0x80000000, 0x80000024])
+  assert.deepEqual(result.code, [
+    { start: 0x80000000, end: 0x8000001c },
+    { start: 0x80000024, end: 0x8000002c },
+  ])
+  assert.deepEqual(result.data, [{ start: 0x8000001c, end: 0x80000024 }])
+
+  // The last range ends past the top of the address space and wraps to 0
+  const top = CodeSection.fromWordList([0x03e00008, 0, 1, 2], 0xfffffff0)
+  const topResult = top.recursiveDescent([0xfffffff0])
+  assert.deepEqual(topResult.code, [{ start: 0xfffffff0, end: 0xfffffff8 }])
+  assert.deepEqual(topResult.data, [{ start: 0xfffffff8, end: 0 }])
+})
+
+// func_80000000 calls func_80000020 through $t9 and an external function
+//   lui $t9, 0x8000; addiu $t9, $t9, 0x20; jalr $t9; nop
+//   jal 0x80300000; nop; jr $ra; nop
+// func_80000020: jr $ra; nop
+const callWords = [
+  0x3c198000, 0x27390020, 0x0320f809, 0x00000000, 0x0c0c0000, 0x00000000,
+  0x03e00008, 0x00000000, 0x03e00008, 0x00000000,
+]
+
+test('Call graph direct and indirect calls', (t) => {
+  const section = CodeSection.fromWordList(callWords, 0x80000000)
+  const graph = section.callGraph([0x80000000, 0x80000020])
+
+  assert.deepEqual(graph.functions(), [0x80000000, 0x80000020])
+  assert.deepEqual(graph.callees(0x80000000), [
+    {
+      caller: 0x80000000,
+      vram: 0x80000008,
+      target: 0x80000020,
+      indirect: true,
+    },
+    {
+      caller: 0x80000000,
+      vram: 0x80000010,
+      target: 0x80300000,
+      indirect: false,
+    },
+  ])
+  assert.deepEqual(
+    graph.callers(0x80300000).map((call) => call.vram),
+    [0x80000010],
+  )
+  assert.deepEqual(graph.callees(0x80000020), [])
+})
+
+test('Call graph export', (t) => {
+  const section = CodeSection.fromWordList(callWords, 0x80000000)
+  const graph = section.callGraph([0x80000000, 0x80000020])
+
+  const dot = graph.toDot()
+  assert(dot.startsWith('digraph calls {'))
+  assert(dot.includes('func_80000000 -> func_80300000;'))
+  assert(dot.includes('func_80000000 -> func_80000020 [style=dashed];'))
+
+  const json = JSON.parse(graph.toJson())
+  assert.deepEqual(json.functions, [0x80000000, 0x80000020])
+  assert.equal(json.calls.length, 2)
+  assert.equal(json.calls[1].target, 0x80300000)
+})
+
+// lui $at, 0x8010; lw $t6, 0x20($at); sw $t6, 0x24($at)
+// lui $a0, 0x8010; addiu $a0, $a0, 0x30; jal func_8000003C; nop
+// bnez $a0, .L80000028; nop; nop
+// .L80000028: lui $at, 0x8010; addu $at, $at, $t7; lw $t7, 0x100($at)
+// jr $ra; nop
+// func_8000003C: jr $ra; nop
+const xrefWords = [
+  0x3c018010, 0x8c2e0020, 0xac2e0024, 0x3c048010, 0x24840030, 0x0c00000f,
+  0x00000000, 0x14800002, 0x00000000, 0x00000000, 0x3c018010, 0x002f0821,
+  0x8c2f0100, 0x03e00008, 0x00000000, 0x03e00008, 0x00000000,
+]
+
+test('Cross-reference index', (t) => {
+  const section = CodeSection.fromWordList(xrefWords, 0x80000000)
+  const xrefs = section.xrefs([0x80000000])
+
+  assert.deepEqual(xrefs.targets(), [
+    0x80000028, 0x8000003c, 0x80100020, 0x80100024, 0x80100030, 0x80100100,
+  ])
+  assert.deepEqual(xrefs.referencesTo(0x80100020), [
+    {
+      from: 0x80000004,
+      target: 0x80100020,
+      kind: XrefKind.Read,
+      accessType: AccessType.Word,
+    },
+  ])
+  assert.equal(xrefs.referencesTo(0x80100024)[0].kind, XrefKind.Write)
+  assert.equal(xrefs.referencesTo(0x80100030)[0].kind, XrefKind.AddressOf)
+  assert.equal(xrefs.referencesTo(0x8000003c)[0].kind, XrefKind.Call)
+  assert.equal(xrefs.referencesTo(0x80000028)[0].kind, XrefKind.Branch)
+  // Jump table style indexed load
+  assert.equal(xrefs.referencesTo(0x80100100)[0].from, 0x80000030)
+})
+
+// sll $t6, $a0, 2; lui $at, 0x8000; addu $at, $at, $t6; lw $t6, 0x28($at)
+// jr $t6; nop
+// .L80000018: jr $ra; li $v0, 1
+// .L80000020: jr $ra; li $v0, 2
+// .word .L80000018, .L80000020
+const switchWords = [
+  0x00047080, 0x3c018000, 0x002e0821, 0x8c2e0028, 0x01c00008, 0x00000000,
+  0x03e00008, 0x24020001, 0x03e00008, 0x24020002, 0x80000018, 0x80000020,
+]
+
+test('Jump tables are followed and indexed', (t) => {
+  const section = CodeSection.fromWordList(switchWords, 0x80000000)
+  const result = section.recursiveDescent([0x80000000])
+  assert.deepEqual(result.code, [{ start: 0x80000000, end: 0x80000028 }])
+  assert.deepEqual(result.data, [{ start: 0x80000028, end: 0x80000030 }])
+
+  const xrefs = section.xrefs([0x80000000])
+  assert.deepEqual(xrefs.referencesFrom(0x80000010), [
+    {
+      from: 0x80000010,
+      target: 0x80000018,
+      kind: XrefKind.JumpTable,
+      accessType: AccessType.Invalid,
+    },
+    {
+      from: 0x80000010,
+      target: 0x80000020,
+      kind: XrefKind.JumpTable,
+      accessType: AccessType.Invalid,
+    },
+  ])
+  assert.equal(xrefs.referencesTo(0x80000028)[0].kind, XrefKind.Read)
+})
+
+test('Cross-reference queries', (t) => {
+  const section = CodeSection.fromWordList(xrefWords, 0x80000000)
+  const xrefs = section.xrefs([0x80000000])
+
+  assert.deepEqual(
+    xrefs.referencesInRange(0x80100000, 0x80100030).map((x) => x.from),
+    [0x80000004, 0x80000008],
+  )
+  assert.deepEqual(
+    xrefs.referencesFrom(0x80000014).map((x) => x.target),
+    [0x8000003c],
+  )
+  assert.deepEqual(xrefs.referencesTo(0x12345678), [])
+})
+
+test('CodeSection functions', (t) => {
+  const section = CodeSection.fromWordList(sampleWords, 0x80000000)
+  const functions = section.functions([0x80000000])
+
+  assert.deepEqual(
+    functions.map((func) => [func.vram, func.size]),
+    [
+      [0x80000000, 0x1c],
+      [0x80000024, 0x8],
+    ],
+  )
+  assert.equal(functions[1].instructions()[0].word, 0x03e00008)
+})
+
+test('Stack frame analysis (O32)', (t) => {
+  const func = Function.fromWordList(
+    [
+      0x27bdffd8, 0xafbf0024, 0xafb10020, 0xafb0001c, 0xf7b40010, 0x00808025,
+      0xafa40028, 0xafa00018, 0x0c000400, 0x87a5001a, 0x8fbf0024, 0x8fb10020,
+      0x8fb0001c, 0xd7b40010, 0x03e00008, 0x27bd0028,
+    ],
+    0x80000000,
+  )
+  const frame = func.stackFrame()
+
+  assert.equal(frame.size, 0x28)
+  assert.deepEqual(
+    frame.savedGprs.map((slot) => [slot.register, slot.offset, slot.size]),
+    [
+      [31, 0x24, 4],
+      [17, 0x20, 4],
+      [16, 0x1c, 4],
+    ],
+  )
+  assert.deepEqual(
+    frame.savedFprs.map((slot) => [slot.register, slot.offset, slot.size]),
+    [[20, 0x10, 8]],
+  )
+  assert.deepEqual(frame.locals, [{ start: 0x18, end: 0x1c }])
+  assert.equal(frame.usesFramePointer, false)
+  assert.equal(frame.allocation, 0x80000000)
+  assert.deepEqual(frame.deallocations, [0x8000003c])
+})
+
+test('Stack frame analysis (N64 with frame pointer)', (t) => {
+  const func = Function.fromWordList(
+    [
+      0x67bdffe0, 0xffbf0018, 0xffbe0010, 0xffbc0008, 0x03a0f02d, 0xafc00004,
+      0x03c0e82d, 0xdfbf0018, 0xdfbe0010, 0xdfbc0008, 0x03e00008, 0x67bd0020,
+    ],
+    0x80000000,
+  )
+
+  const n64 = func.stackFrame(Abi.N64)
+  assert.equal(n64.size, 0x20)
+  assert.deepEqual(
+    n64.savedGprs.map((slot) => [slot.register, slot.offset, slot.size]),
+    [
+      [31, 0x18, 8],
+      [30, 0x10, 8],
+      [28, 0x8, 8],
+    ],
+  )
+  assert(n64.usesFramePointer)
+  assert.deepEqual(n64.locals, [{ start: 4, end: 8 }])
+
+  const o32 = func.stackFrame(Abi.O32)
+  assert.deepEqual(o32.savedGprs.map((slot) => slot.register), [31, 30])
+})
+
+const branchyWords = [
+  0x10800003, 0x00a01021, 0x00851021, 0x24420001, 0x03e00008, 0x00000000,
+]
+
+test('Function basic blocks', (t) => {
+  const func = Function.fromWordList(branchyWords, 0x80000000)
+
+  assert.deepEqual(func.basicBlocks(), [
+    {
+      start: 0x80000000,
+      end: 0x80000008,
+      successors: [0x80000010, 0x80000008],
+      predecessors: [],
+    },
+    {
+      start: 0x80000008,
+      end: 0x80000010,
+      successors: [0x80000010],
+      predecessors: [0x80000000],
+    },
+    {
+      start: 0x80000010,
+      end: 0x80000018,
+      successors: [],
+      predecessors: [0x80000000, 0x80000008],
+    },
+  ])
+})
+
+test('Register liveness', (t) => {
+  const func = Function.fromWordList(branchyWords, 0x80000000)
+
+  assert.deepEqual(func.liveIn(0x80000000), ['$a0', '$a1', '$ra'])
+  assert.deepEqual(func.liveIn(0x80000008), ['$a0', '$a1', '$ra'])
+  assert.deepEqual(func.liveIn(0x80000010), ['$v0', '$ra'])
+  assert.throws(() => func.liveIn(0x80000004))
+})
+
+test('Def-use chains', (t) => {
+  const func = Function.fromWordList(branchyWords, 0x80000000)
+
+  assert.deepEqual(func.definitionsReaching(0x80000010, '$v0'), {
+    definitions: [0x80000004, 0x8000000c],
+    fromEntry: false,
+  })
+  assert.deepEqual(func.definitionsReaching(0x80000008, 'a0'), {
+    definitions: [],
+    fromEntry: true,
+  })
+  assert.deepEqual(func.usesOf(0x80000004, '$v0'), [0x80000010])
+  assert.deepEqual(func.usesOf(0x80000008, '$v0'), [0x8000000c])
+  assert.throws(() => func.usesOf(0x80000008, '$a0'))
+  assert.throws(() => func.usesOf(0x80000008, '$bogus'))
+})
+
+test('Def-use chains across calls', (t) => {
+  const func = Function.fromWordList(
+    [
+      0x27bdffe8, 0xafbf0010, 0x0c000400, 0x02002021, 0x8fbf0010, 0x03e00008,
+      0x27bd0018,
+    ],
+    0x80000000,
+  )
+
+  // The delay slot sets up the argument the callee reads
+  assert.deepEqual(func.usesOf(0x8000000c, '$a0'), [0x80000008])
+  assert.deepEqual(func.usesOf(0x80000008, '$v0'), [0x80000014])
+  assert.deepEqual(func.definitionsReaching(0x80000010, '$a0'), {
+    definitions: [0x80000008],
+    fromEntry: false,
+  })
+  const live = func.liveIn(0x80000000)
+  assert(live.includes('$s0'))
+  assert(live.includes('$sp'))
+  assert(!live.includes('$a0'))
+})
+
+test('Signature inference', (t) => {
+  const func = Function.fromWordList(branchyWords, 0x80000000)
+  assert.deepEqual(func.signature(), {
+    vram: 0x80000000,
+    parameters: [
+      { register: '$a0', kind: ValueKind.Int },
+      { register: '$a1', kind: ValueKind.Int },
+    ],
+    returnRegisters: ['$v0'],
+    returnKind: ValueKind.Int,
+  })
+
+  // Arguments passed on to a callee and its return value don't count
+  const wrapper = Function.fromWordList(
+    [
+      0x27bdffe8, 0xafbf0010, 0x0c000400, 0x02002021, 0x8fbf0010, 0x03e00008,
+      0x27bd0018,
+    ],
+    0x80000000,
+  )
+  const signature = wrapper.signature()
+  assert.deepEqual(signature.parameters, [])
+  assert.deepEqual(signature.returnRegisters, [])
+  assert.equal(signature.returnKind, undefined)
+})
+
+test('Signature inference with floats', (t) => {
+  // mul.s $f0, $f12, $f14
+  const single = Function.fromWordList(
+    [0x460e6002, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.deepEqual(single.signature(Abi.O32).parameters, [
+    { register: '$f12', kind: ValueKind.Float },
+    { register: '$f14', kind: ValueKind.Float },
+  ])
+  assert.equal(single.signature().returnKind, ValueKind.Float)
+  assert.deepEqual(single.signature().returnRegisters, ['$f0'])
+
+  // mul.d $f0, $f12, $f14: under N64 $f14 is the third argument
+  const double = Function.fromWordList(
+    [0x462e6002, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.deepEqual(double.signature(Abi.N64).parameters, [
+    { register: '$f12', kind: ValueKind.Double },
+    { register: '$a1', kind: ValueKind.Int },
+    { register: '$f14', kind: ValueKind.Double },
+  ])
+  assert.equal(double.signature(Abi.N64).returnKind, ValueKind.Double)
+})
+
+test('Signature inference names N32 and N64 registers', (t) => {
+  // addu $v0, $a0, $8: $8 is the fifth argument, $a4, under N32 and N64
+  const func = Function.fromWordList(
+    [0x00881021, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.deepEqual(
+    func.signature(Abi.N64).parameters.map((p) => p.register),
+    ['$a0', '$a1', '$a2', '$a3', '$a4'],
+  )
+  assert.deepEqual(
+    func.signature(Abi.O32).parameters.map((p) => p.register),
+    ['$a0'],
+  )
+  assert.deepEqual(func.liveIn(0x80000000, Abi.N32), ['$a0', '$a4', '$ra'])
+
+  // The names given back are accepted under the same ABI
+  for (const abi of [Abi.N32, Abi.N64]) {
+    for (const reg of func.liveIn(0x80000000, abi)) {
+      const reaching = func.definitionsReaching(0x80000000, reg, abi)
+      assert.deepEqual(reaching, { definitions: [], fromEntry: true }, reg)
+    }
+  }
+  assert.deepEqual(func.usesOf(0x80000000, '$v0', Abi.N64), [0x80000004])
+  assert.throws(() => func.definitionsReaching(0x80000000, '$a4'), /Unknown/)
+})
+
+test('CodeSection signatures', (t) => {
+  const section = CodeSection.fromWordList(branchyWords, 0x80000000)
+  const signatures = section.signatures([0x80000000])
+
+  assert.equal(signatures.length, 1)
+  assert.equal(signatures[0].parameters.length, 2)
+  assert.equal(signatures[0].returnKind, ValueKind.Int)
+})
+
+test('Constant propagation', (t) => {
+  const func = Function.fromWordList(
+    [
+      0x3c088012, 0x35083456, 0x0008483c, 0x27bdffe0, 0xafa80010, 0x24040010,
+      0x0c000400, 0x27b00018, 0x8faa0010, 0x27bd0020, 0x03e00008, 0x00000000,
+    ],
+    0x80000000,
+  )
+
+  const t0 = func.valueAt(0x80000008, '$t0')
+  assert.equal(t0.word, 0x80123456)
+  assert.equal(t0.value, BigInt.asIntN(64, 0xffffffff80123456n))
+  assert.equal(t0.stackOffset, undefined)
+
+  // dsll32 builds a 64-bit value
+  const t1 = func.valueAt(0x8000000c, '$t1')
+  assert.equal(t1.value, BigInt.asIntN(64, 0x8012345600000000n))
+  assert.equal(t1.word, 0)
+
+  assert.equal(func.valueAt(0x80000010, '$sp').stackOffset, -0x20)
+  assert.equal(func.valueAt(0x80000018, '$a0').value, 16n)
+  assert.equal(func.valueAt(0x8000001c, '$ra').word, 0x80000020)
+  assert.equal(func.valueAt(0x80000020, '$s0').stackOffset, -8)
+
+  // Clobbered by the call, but reloaded from its stack slot
+  assert.equal(func.valueAt(0x80000020, '$t0'), null)
+  assert.equal(func.valueAt(0x80000024, '$t2').word, 0x80123456)
+
+  assert.throws(() => func.valueAt(0x80000000, '$f0'))
+  assert.throws(() => func.valueAt(0x90000000, '$t0'))
+})
+
+test('Constant propagation across branches', (t) => {
+  const func = Function.fromWordList(
+    [0x10800002, 0x24020001, 0x24030002, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+
+  assert.equal(func.valueAt(0x8000000c, '$v0').value, 1n)
+  assert.equal(func.valueAt(0x8000000c, '$v1'), null)
+  assert.equal(func.valueAt(0x8000000c, '$zero').value, 0n)
+})
+
+test('Float constants from mtc1', (t) => {
+  // lui $at, 0x3F80; mtc1 $at, $f4; add.s $f0, $f12, $f4
+  const single = Function.fromWordList(
+    [0x3c013f80, 0x44812000, 0x46046000, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.deepEqual(single.floatConstants(), [
+    { vram: 0x80000004, register: '$f4', value: 1, isDouble: false },
+  ])
+
+  // lui $at, 0x3FF0; mtc1 $at, $f5; mtc1 $zero, $f4; add.d $f0, $f12, $f4
+  const double = Function.fromWordList(
+    [0x3c013ff0, 0x44812800, 0x44802000, 0x46246000, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.deepEqual(double.floatConstants(), [
+    { vram: 0x80000008, register: '$f4', value: 1, isDouble: true },
+  ])
+
+  // lui $at, 0x4049; ori $at, $at, 0xFDB; mtc1 $at, $f12
+  const pi = Function.fromWordList(
+    [0x3c014049, 0x34210fdb, 0x44816000, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.equal(pi.floatConstants()[0].value, Math.fround(Math.PI))
+
+  // li $t0, 5; mtc1 $t0, $f4; cvt.s.w $f0, $f4 moves an integer
+  const integer = Function.fromWordList(
+    [0x24080005, 0x44882000, 0x46802020, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.deepEqual(integer.floatConstants(), [])
+})
+
+test('Float constants from rodata', (t) => {
+  const func = Function.fromWordList(
+    [0x3c018001, 0xc4240010, 0xd4260018, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  const rodata = wordsToBuffer([
+    0, 0, 0, 0, 0x40490fdb, 0, 0x400921fb, 0x54442d18,
+  ])
+  const options = { rodata, rodataVram: 0x80010000 }
+
+  const constants = func.floatConstants(options)
+  assert.equal(constants.length, 2)
+  assert.equal(constants[0].value, Math.fround(Math.PI))
+  assert.equal(constants[0].address, 0x80010010)
+  assert.equal(constants[1].value, Math.PI)
+  assert(constants[1].isDouble)
+
+  // Without rodata the loads can't be resolved
+  assert.deepEqual(func.floatConstants(), [])
+
+  const lines = func.disassemble(true, options).split('\n')
+  assert.equal(lines.length, 5)
+  assert(lines[1].startsWith('/* 80000004 C4240010 */  lwc1'))
+  assert(lines[1].endsWith('/* 3.1415927f */'))
+  assert(lines[2].endsWith('/* 3.141592653589793 */'))
+  assert(!func.disassemble().includes('3.14'))
+})
+
+test('Pseudo-C loops and calls', (t) => {
+  const loop = Function.fromWordList(
+    [
+      0x00001021, 0x18a00006, 0x00000000, 0x8c8e0000, 0x24a5ffff, 0x24840004,
+      0x14a0fffc, 0x004e1021, 0x03e00008, 0x00000000,
+    ],
+    0x80000000,
+  )
+  assert.equal(
+    loop.toPseudoC(),
+    [
+      's32 func_80000000(s32 a0, s32 a1) {',
+      '    v0 = 0;',
+      '    if (a1 > 0) {',
+      '        do {',
+      '            t6 = *(s32*)a0;',
+      '            a1 = a1 - 1;',
+      '            a0 = a0 + 4;',
+      '            v0 = v0 + t6;',
+      '        } while (a1 != 0);',
+      '    }',
+      '    return v0;',
+      '}',
+      '',
+    ].join('\n'),
+  )
+
+  const wrapper = Function.fromWordList(
+    [
+      0x27bdffe8, 0xafbf0010, 0x0c000400, 0x02002021, 0x8fbf0010, 0x03e00008,
+      0x27bd0018,
+    ],
+    0x80000000,
+  )
+  const lines = wrapper.toPseudoC().split('\n')
+  assert.equal(lines[0], 'void func_80000000(void) {')
+  assert(lines.includes('    *(s32*)(sp + 0x10) = ra;'))
+  // The delay slot sets up the argument, so it comes first
+  assert.equal(
+    lines.indexOf('    a0 = s0;') + 1,
+    lines.indexOf('    v0 = func_80001000(a0);'),
+  )
+})
+
+test('Pseudo-C conditionals', (t) => {
+  const ifElse = Function.fromWordList(
+    [
+      0x10800003, 0x00000000, 0x10000003, 0x24020001, 0x24020002, 0x00000000,
+      0x03e00008, 0x00000000,
+    ],
+    0x80000000,
+  )
+  assert.equal(
+    ifElse.toPseudoC(),
+    [
+      's32 func_80000000(s32 a0) {',
+      '    if (a0 != 0) {',
+      '        v0 = 1;',
+      '    } else {',
+      '        v0 = 2;',
+      '    }',
+      '    return v0;',
+      '}',
+      '',
+    ].join('\n'),
+  )
+
+  // Branch-likely delay slots only run when taken
+  const likely = Function.fromWordList(
+    [0x50800002, 0x24020001, 0x24020002, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.equal(
+    likely.toPseudoC(),
+    [
+      's32 func_80000000(s32 a0) {',
+      '    if (a0 == 0) {',
+      '        v0 = 1;',
+      '        goto label_8000000C;',
+      '    }',
+      '    v0 = 2;',
+      'label_8000000C:',
+      '    return v0;',
+      '}',
+      '',
+    ].join('\n'),
+  )
+})
+
+test('Struct field accesses', (t) => {
+  const func = Function.fromWordList(
+    [
+      0x27bdffe8, // addiu $sp, $sp, -0x18
+      0xafbf0014, // sw $ra, 0x14($sp)
+      0x8c8e0004, // lw $t6, 0x4($a0)
+      0x948f0008, // lhu $t7, 0x8($a0)
+      0x8dd80000, // lw $t8, 0x0($t6)
+      0x3c018010, // lui $at, 0x8010
+      0xac2f0120, // sw $t7, 0x120($at)
+      0xc484000c, // lwc1 $f4, 0xC($a0)
+      0xe7a40010, // swc1 $f4, 0x10($sp)
+      0x8fbf0014, // lw $ra, 0x14($sp)
+      0x03e00008, // jr $ra
+      0x27bd0018, // addiu $sp, $sp, 0x18
+    ],
+    0x80000000,
+  )
+  const [arg, frame, global, loaded] = func.structAccesses()
+
+  assert.equal(arg.kind, BaseKind.Argument)
+  assert.equal(arg.register, '$a0')
+  assert.deepEqual(
+    arg.fields.map((f) => [f.offset, f.size, f.isUnsigned, f.isFloat]),
+    [
+      [4, 4, false, false],
+      [8, 2, true, false],
+      [12, 4, undefined, true],
+    ],
+  )
+  assert.equal(
+    arg.definition,
+    [
+      'struct Arg_a0 {',
+      '    /* 0x00 */ char pad0[0x4];',
+      '    /* 0x04 */ s32 unk4;',
+      '    /* 0x08 */ u16 unk8;',
+      '    /* 0x0A */ char padA[0x2];',
+      '    /* 0x0C */ f32 unkC;',
+      '}; /* size >= 0x10 */',
+      '',
+    ].join('\n'),
+  )
+
+  // Offsets are relative to the allocated frame
+  assert.equal(frame.kind, BaseKind.Stack)
+  assert.deepEqual(
+    frame.fields.map((f) => [f.offset, f.read, f.written]),
+    [
+      [0x10, false, true],
+      [0x14, true, true],
+    ],
+  )
+
+  assert.equal(global.kind, BaseKind.Global)
+  assert.equal(global.address, 0x80100120)
+  assert.deepEqual(global.fields[0].vrams, [0x80000018])
+
+  assert.equal(loaded.kind, BaseKind.Loaded)
+  assert.equal(loaded.vram, 0x80000008)
+  assert.equal(loaded.fields[0].offset, 0)
+})
+
+test('Compiler guess', (t) => {
+  // lw $t6, 0($a0); div $zero, $t6, $a1; bnez $a1, 1f; nop; break 7; 1: mflo
+  const ido = Function.fromWordList(
+    [
+      0x8c8e0000, 0x01c5001a, 0x14a00002, 0x00000000, 0x0007000d, 0x00001012,
+      0x03e00008, 0x00000000,
+    ],
+    0x80000000,
+  ).guessCompiler()
+  assert.equal(ido[0].compiler, Compiler.IDO)
+  assert.ok(ido[0].reasons.includes('zero check after the div'))
+
+  // bnez $a1, 1f; div $zero, $a0, $a1; break 0, 7; 1: mflo $v0
+  const sn64 = Function.fromWordList(
+    [0x14a00002, 0x0085001a, 0x000001cd, 0x00001012, 0x03e00008, 0x00000000],
+    0x80000000,
+  ).guessCompiler()
+  assert.equal(sn64[0].compiler, Compiler.SN64)
+  assert.ok(sn64[0].confidence > 0.5)
+
+  // lw $v0, 0($a0); nop; addiu $v0, $v0, 1
+  const psyq = Function.fromWordList(
+    [0x8c820000, 0x00000000, 0x24420001, 0x03e00008, 0x00000000],
+    0x80000000,
+  ).guessCompiler()
+  assert.equal(psyq[0].compiler, Compiler.PSYQ)
+
+  const total = psyq.reduce((sum, guess) => sum + guess.confidence, 0)
+  assert.ok(Math.abs(total - 1) < 1e-9)
+})
+
+test('Handwritten score', (t) => {
+  // addiu $sp, $sp, -0x18; sw $ra, 0x14($sp); lw $ra, 0x14($sp); jr $ra; ...
+  const compiled = Function.fromWordList(
+    [0x27bdffe8, 0xafbf0014, 0x8fbf0014, 0x03e00008, 0x27bd0018],
+    0x80000000,
+  ).handwrittenScore()
+  assert.equal(compiled.score, 0)
+  assert.deepEqual(compiled.reasons, [])
+
+  // mfc0 $k0, $12; lui $k1, 0x8000; addiu $k1, $k1, 0x180; jr $k1; eret
+  const handler = Function.fromWordList(
+    [0x401a6000, 0x3c1b8000, 0x277b0180, 0x03600008, 0x42000018],
+    0x80000000,
+  ).handwrittenScore()
+  assert.ok(handler.score > 0.9)
+  assert.deepEqual(handler.reasons, [
+    'COP0 access at 0x80000000',
+    '$k0/$k1 used at 0x80000000',
+    'eret at 0x80000010',
+  ])
+
+  // nop; addiu $sp, $sp, -0x1C; ...
+  const prologue = Function.fromWordList(
+    [0x00000000, 0x27bdffe4, 0x03e00008, 0x27bd001c],
+    0x80000000,
+  ).handwrittenScore()
+  assert.ok(prologue.score >= 0.5)
+  assert.equal(prologue.reasons.length, 2)
+
+  // GCC with a frame pointer: move $fp, $sp; ...; move $sp, $fp
+  const framePointer = Function.fromWordList(
+    [
+      0x27bdffe8, 0xafbe0014, 0x03a0f025, 0x00801025, 0x03c0e825, 0x8fbe0014,
+      0x27bd0018, 0x03e00008, 0x00000000,
+    ],
+    0x80000000,
+  ).handwrittenScore()
+  assert.equal(framePointer.score, 0)
+
+  // blt $a0, $a1, 1f expands to slt $at, $a0, $a1; bnez $at, 1f
+  const blt = Function.fromWordList(
+    [0x0085082a, 0x14200002, 0x00000000, 0x03e00008, 0x00000000],
+    0x80000000,
+  ).handwrittenScore()
+  assert.equal(blt.score, 0)
+})
+
+test('Lint hazards', (t) => {
+  const section = CodeSection.fromWordList(
+    [
+      0x8c820000, // lw $v0, 0($a0)
+      0x24420001, // addiu $v0, $v0, 1
+      0x00001012, // mflo $v0
+      0x00850018, // mult $a0, $a1
+      0x40846000, // mtc0 $a0, $12
+      0x42000002, // tlbwi
+      0x10000002, // b
+      0x10000001, // b
+      0x03e00008, // jr $ra
+    ],
+    0x80000000,
+  )
+  const rules = (issues) => issues.map((issue) => [issue.vram, issue.rule])
+
+  assert.deepEqual(rules(lint(section)), [
+    [0x8000000c, 'hilo'],
+    [0x80000014, 'cop0'],
+    [0x8000001c, 'branch-in-delay-slot'],
+    [0x80000020, 'branch-in-delay-slot'],
+    [0x80000020, 'missing-delay-slot'],
+  ])
+  assert.deepEqual(rules(lint(section, 'r3000gte')).slice(0, 2), [
+    [0x80000004, 'load-delay'],
+    [0x8000000c, 'hilo'],
+  ])
+  assert.deepEqual(
+    lint(section, 'r5900').map((issue) => issue.severity),
+    [LintSeverity.Error, LintSeverity.Error, LintSeverity.Error],
+  )
+
+  const [load] = lint(section, 'r3000gte')
+  assert.equal(load.severity, LintSeverity.Warning)
+  assert.equal(
+    load.message,
+    '$v0 is read in the load delay slot of lw at 0x80000000',
+  )
+})
+
+test('Cycle estimates', (t) => {
+  const words = [
+    0x8c820000, // lw $v0, 0($a0)
+    0x24420001, // addiu $v0, $v0, 1
+    0x00850018, // mult $a0, $a1
+    0x00001812, // mflo $v1
+    0x03e00008, // jr $ra
+    0x00000000, // nop
+  ]
+
+  // Load-use stall, then mflo waits for the multiplier
+  const n64 = Function.fromWordList(words, 0x80000000).estimateCycles(
+    0x80000000,
+  )
+  assert.equal(n64.min, 11)
+  assert.equal(n64.expected, 11)
+  assert.deepEqual(
+    n64.instructions.map((instr) => instr.stall),
+    [0, 1, 0, 4, 0, 0],
+  )
+
+  // No load interlock, and a multiplier whose speed depends on the operands
+  const psx = Function.fromWordList(
+    words,
+    0x80000000,
+    'r3000gte',
+  ).estimateCycles(0x80000000)
+  assert.equal(psx.min, 11)
+  assert.equal(psx.expected, 14)
+
+  // beql $a0, $zero, ...: falls through half of the time
+  const likely = Function.fromWordList(
+    [0x50800002, 0x24420001, 0x24420002, 0x03e00008, 0x00000000],
+    0x80000000,
+  )
+  assert.equal(likely.estimateCycles(0x80000000).expected, 1.5)
+  assert.throws(() => likely.estimateCycles(0x80000010))
+})
+
+test('RSP section disassembly', (t) => {
+  const section = RspSection.fromWordList([
+    0x8c0802c0, // lw $8, 0x2C0($zero)
+    0x20090100, // addi $9, $zero, 0x100
+    0xc9212001, // lqv $v1[0], 0x10($9)
+    0x09000800, // j 0x04002000, which wraps to the start of IMEM
+    0xad280008, // sw $8, 0x8($9)
+  ])
+  assert.equal(section.vram, 0x04001000)
+  assert.equal(section.branchTarget(0x0400100c), 0x04001000)
+
+  assert.deepEqual(
+    section.dmemAccesses().map((access) => [access.offset, access.isWrite]),
+    [
+      [0x2c0, false],
+      [0x110, false],
+      [0x108, true],
+    ],
+  )
+
+  const symbols = [
+    'OSTask_flags = 0x040002C0; // size:0x4',
+    'table = 0x100; // type:data size:0x40',
+    '',
+    'entry = 0x04001000;',
+  ].join('\n')
+  assert.equal(
+    section.disassemble(symbols),
+    [
+      'entry:',
+      '/* 04001000 8C0802C0 */  lw          $8, OSTask_flags($zero)',
+      '/* 04001004 20090100 */  addi        $9, $zero, 0x100',
+      '/* 04001008 C9212001 */  lqv         $v1[0], 0x10($9)  /* table + 0x10 */',
+      '/* 0400100C 09000800 */  j           entry',
+      '/* 04001010 AD280008 */  sw          $8, 0x8($9)  /* table + 0x8 */',
+    ].join('\n'),
+  )
+  assert.ok(section.disassemble().startsWith('.L04001000:\n'))
+  assert.throws(() => section.disassemble('not a symbol'))
+
+  // rabbitizer only names COP0 registers below 16
+  const cop0 = RspSection.fromWordList([0x40088000])
+  assert.equal(cop0.disassemble(), '/* 04001000 40088000 */  .word 0x40088000')
+})
+
+test('assemble', (t) => {
+  assert.deepEqual(assemble('addiu $sp, $sp, -0x18'), [0x27bdffe8])
+  assert.deepEqual(assemble('lw $a0, 16($sp)  # comment'), [0x8fa40010])
+  assert.deepEqual(assemble('li $a0, 0x12345678'), [0x3c041234, 0x34845678])
+  assert.deepEqual(assemble('la $a0, D_80018000'), [0x3c048002, 0x24848000])
+  assert.deepEqual(assemble('move $a0, $a1'), [0x00a02025])
+  assert.deepEqual(assemble('beqz $v0, .L80000020', { vram: 0x80000000 }), [
+    0x10400007,
+  ])
+  assert.deepEqual(assemble('b 0x80000010', { vram: 0x80000000 }), [
+    0x10000003,
+  ])
+  assert.deepEqual(assemble('addiu $a4, $zero, 1', { abi: Abi.N32 }), [
+    0x24080001,
+  ])
+  assert.deepEqual(assemble('lqv $v1[0], 0x10($zero)', { category: 'rsp' }), [
+    0xc8012001,
+  ])
+
+  assert.throws(() => assemble('foo $a0'), /^Error: column 1: unknown/)
+  assert.throws(() => assemble('addiu $sp, $q0, 1'), /column 12: invalid/)
+  assert.throws(() => assemble('addu $a0, $a1'), /column 14: missing/)
+  assert.throws(() => assemble('addiu $a0, $a0, 0x18000'), /column 17: value/)
+  assert.throws(() => assemble('j 0x90000000', { vram: 0x80000000 }))
+  // Jumps reach the region of their delay slot, the first one at vram 0
+  assert.deepEqual(assemble('j 0x100'), [0x08000040])
+  assert.throws(() => assemble('j 0x80000100'), /not reachable/)
+
+  // The code of syscall, break and traps may be left out
+  assert.deepEqual(assemble('syscall'), [0x0000000c])
+  assert.deepEqual(assemble('break'), [0x0000000d])
+  assert.deepEqual(assemble('teq $a0, $a1'), [0x00850034])
+
+  // Every template of every category, and each word one bit away from it
+  // that decodes to the same instruction, assembles back to its own word.
+  // Only bits rabbitizer does not print, like the unused fields of GTE
+  // commands, may come back different
+  const categories = {
+    Rsp: 'rsp',
+    R3000gte: 'r3000gte',
+    R4000allegrex: 'r4000allegrex',
+    R5900: 'r5900',
+  }
+  const vram = 0x80000000
+  const roundTrip = (word, category, unprinted = 0) => {
+    const text = new Instruction(word, vram, category).disassemble()
+    const [assembled] = assemble(text, { category, vram })
+    const again = new Instruction(assembled, vram, category)
+    assert.equal(again.disassemble(), text, `${category}: ${text}`)
+    assert.equal((assembled ^ word) & ~unprinted, 0, `${category}: ${text}`)
+  }
+  for (const [name, id] of Object.entries(InstrId)) {
+    let template
+    try {
+      template = Instruction.build(id)
+    } catch {
+      continue
+    }
+    const prefix = Object.keys(categories).find((p) => name.startsWith(p))
+    const category = categories[prefix] ?? 'cpu'
+    const text = new Instruction(template.raw(), vram, category).disassemble()
+    const words = [template.raw()]
+    let unprinted = 0
+    for (let bit = 0; bit < 32; bit++) {
+      const word = (template.raw() ^ (1 << bit)) >>> 0
+      const instr = new Instruction(word, vram, category)
+      // rabbitizer aborts on RSP coprocessor 0 registers past $c15
+      const rspC0 = category === 'rsp' && /c0$/.test(instr.opcodeName())
+      if (rspC0 && ((word >> 11) & 0x1f) >= 16) {
+        continue
+      }
+      if (!instr.isValid()) {
+        continue
+      }
+      // Another opcode may print the same, like the two `c.lt.s` of R5900
+      if (instr.disassemble() === text) {
+        unprinted |= 1 << bit
+      }
+      if (instr.sameOpcode(template)) {
+        words.push(word)
+      }
+    }
+    for (const word of words) {
+      roundTrip(word, category, unprinted)
+    }
+  }
+
+  // Allegrex condition, prefix and half float operands. Denormal halves, in
+  // the low 10 bits of `vfim`, all print as 0
+  for (const word of [0x6c4d4e05, 0xdc076483, 0xdd05ca47]) {
+    roundTrip(word, 'r4000allegrex')
+  }
+  roundTrip(0xdfbf8233, 'r4000allegrex', 0x3ff)
+})
+
+test('assembleBlock', (t) => {
+  const source = `
+    /* copy loop */
+    .set noreorder
+    loop:   lbu $t0, 0($a0)
+            addiu $a0, $a0, 1
+            bnez $t0, loop
+             sb $t0, -1($a0)
+            lui $t1, %hi(table)
+            lw $t1, %lo(table + 4)($t1)
+            jal extern_func
+             nop
+    .align 3
+    table:  .word 1, table, other + 8
+            .half 0x1234
+            .byte 0xff
+  `
+  const block = assembleBlock(source, { vram: 0x80100000 })
+  const words = []
+  for (let i = 0; i < 8; i++) {
+    words.push(block.data.readUInt32BE(i * 4))
+  }
+  assert.deepEqual(words, [
+    0x90880000, 0x24840001, 0x1500fffd, 0xa088ffff, 0x3c098010, 0x8d290024,
+    0x0c000000, 0x00000000,
+  ])
+  assert.equal(block.data.readUInt32BE(0x24), 0x80100020)
+  assert.equal(block.data.readUInt16BE(0x2c), 0x1234)
+  assert.equal(block.data.length, 0x2f)
+  assert.deepEqual(
+    block.relocations.map((r) => [r.offset, r.kind, r.symbol, r.addend]),
+    [
+      [0x18, RelocationType.Mips26, 'extern_func', 0],
+      [0x28, RelocationType.Mips32, 'other', 8],
+    ],
+  )
+
+  // Delay slots are filled in reorder mode, and symbols resolve names
+  const reorder = assembleBlock('jal func\nla $a0, data', {
+    vram: 0x80000000,
+    symbols: 'func = 0x80000400;\ndata = 0x80018010;',
+    endianness: 'little',
+  })
+  assert.equal(reorder.data.toString('hex'), '0001000c000000000280043c10808424')
+
+  // Names ending in hex digits are names like any other
+  const named = assembleBlock('jal some_handler_deadbeef\nla $t0, func_80001234')
+  assert.deepEqual(
+    named.relocations.map((r) => [r.offset, r.kind, r.symbol]),
+    [
+      [0x0, RelocationType.Mips26, 'some_handler_deadbeef'],
+      [0x8, RelocationType.MipsHi16, 'func_80001234'],
+      [0xc, RelocationType.MipsLo16, 'func_80001234'],
+    ],
+  )
+  assert.throws(() => assembleBlock('b func_80000010'), /func_80000010/)
+  assert.throws(() => assembleBlock('beqz $a0, some_deadbeef'), /some_deadbeef/)
+  assert.deepEqual(assemble('b func_80000010', { vram: 0x80000000 }), [
+    0x10000003,
+  ])
+
+  assert.throws(() => assembleBlock('nop\n.foo'), /^Error: line 2, column 1/)
+  assert.throws(() => assembleBlock('a: nop\na: nop'), /already defined/)
+})
+
+test('Instruction builder', (t) => {
+  const addiu = Instruction.build(InstrId.CpuAddiu, { rt: 4, rs: 29, imm: -24 })
+  assert.equal(addiu.word, 0x27a4ffe8)
+  const jal = Instruction.build(
+    InstrId.CpuJal,
+    { target: 0x80001234 },
+    0x80000000,
+  )
+  assert.equal(jal.word, 0x0c00048d)
+  const beq = Instruction.build(
+    InstrId.CpuBeq,
+    { rs: 4, rt: 5, target: 0x80000010 },
+    0x80000000,
+  )
+  assert.equal(beq.word, 0x10850003)
+  assert.equal(Instruction.build(InstrId.RspVadd).category, 'rsp')
+
+  const edited = addiu.withRs(5).withRt(6).withImmediate(0x10)
+  assert.equal(edited.word, 0x24a60010)
+  assert.equal(addiu.word, 0x27a4ffe8)
+  const b = new Instruction(0x10000000, 0x80000000)
+  assert.equal(b.withBranchTarget(0x80000100).word, 0x1000003f)
+
+  assert.throws(() => addiu.withRt(32), /does not fit/)
+  assert.throws(() => addiu.withImmediate(0x10000), /does not fit/)
+  assert.throws(() => Instruction.build(InstrId.CpuAddiu, { rd: 1 }), /no rd/)
+  assert.throws(() => jal.withTarget(0x90000000), /not reachable/)
+  const jump = new Instruction(0x08000000, 0)
+  assert.equal(jump.withTarget(0x00000100).word, 0x08000040)
+  assert.throws(() => jump.withTarget(0x80000100), /not reachable/)
+  assert.throws(() => b.withBranchTarget(0x80040000), /not reachable/)
+  assert.throws(() => b.withTarget(0x80000100), /not a jump/)
+})
+
+test('relocate', (t) => {
+  const source = `
+    .set noreorder
+    func:   jal inner
+             nop
+            bnez $a0, 0x800f0000
+             nop
+            lui $at, %hi(table)
+            lw $t0, %lo(table)($at)
+    inner:  jr $ra
+             nop
+    table:  .word 0
+  `
+  const words = wordsOf(source, 0x80100000)
+  const section = CodeSection.fromWordList(words, 0x80100000)
+
+  const moved = relocate(section, 0x80100000, 0x80104000)
+  assert.deepEqual(moved.words.slice(0, 6), [
+    0x0c041006, 0x00000000, 0x1480affd, 0x00000000, 0x3c018010, 0x8c284020,
+  ])
+  assert.deepEqual(moved.issues, [])
+
+  const far = relocate(section, 0x80100000, 0x80200000, 'ext = 0x800f0000;')
+  assert.equal(far.words[2], words[2])
+  assert.equal(far.issues.length, 1)
+  assert.equal(far.issues[0].kind, RelocateIssueKind.BranchOutOfRange)
+  assert.equal(far.issues[0].vram, 0x80200008)
+  assert.equal(far.issues[0].symbol, 'ext')
+})
+
+test('PatchBuilder', (t) => {
+  const rom = Buffer.alloc(0x40)
+  rom.writeUInt32BE(0x27bdffe8, 0x10)
+  rom.writeUInt32BE(0x03e00008, 0x20)
+
+  const builder = new PatchBuilder(rom, { vram: 0x80000400, romOffset: 0x10 })
+  builder.editAtVram(
+    0x80000400,
+    'addiu $sp, $sp, -0x20',
+    'addiu $sp, $sp, -0x18',
+  )
+  builder.editAtOffset(0x24, 'li $t0, 0x12345678')
+  builder.editAtOffset(0x0, Instruction.build(InstrId.CpuNop), 0)
+  assert.equal(builder.editCount, 4)
+
+  const patched = builder.apply()
+  assert.equal(patched.readUInt32BE(0x10), 0x27bdffe0)
+  assert.equal(patched.readUInt32BE(0x24), 0x3c081234)
+  assert.equal(patched.readUInt32BE(0x28), 0x35085678)
+  assert.equal(rom.readUInt32BE(0x10), 0x27bdffe8)
+
+  assert.equal(
+    builder.ips().toString('hex'),
+    '5041544348' + '0000130001e0' + '00002400083c08123435085678' + '454f46',
+  )
+  const bps = builder.bps()
+  assert.equal(bps.subarray(0, 4).toString(), 'BPS1')
+  assert.equal(bps.readUInt32LE(bps.length - 4), 0xebb1f94a)
+
+  assert.throws(() => builder.editAtOffset(0x20, 'nop', 'nop'), /found 'jr/)
+  assert.throws(() => builder.editAtOffset(0x3c, 'li $t0, 0x12345678'), /ROM/)
+  assert.throws(() => builder.apply(true), /N64 ROM/)
+
+  // Every word of an expected `li` is checked
+  const li = Buffer.alloc(0xc)
+  li.writeUInt32BE(0x3c081234, 0x0)
+  li.writeUInt32BE(0x35080000, 0x4)
+  li.writeUInt32BE(0x3c081234, 0x8)
+  const liBuilder = new PatchBuilder(li)
+  assert.throws(
+    () => liBuilder.editAtOffset(0x0, 'nop', 'li $t0, 0x12345678'),
+    /at 0x00000004, found 'ori/,
+  )
+  liBuilder.editAtOffset(0x0, 'nop', 'li $t0, 0x12340000')
+  assert.throws(
+    () => liBuilder.editAtOffset(0x8, 'nop', 'li $t0, 0x12345678'),
+    /found the end of the ROM/,
+  )
+
+  const cop0 = Buffer.alloc(0x4)
+  cop0.writeUInt32BE(0x40088000)
+  const rsp = new PatchBuilder(cop0, { category: 'rsp' })
+  assert.throws(
+    () => rsp.editAtOffset(0x0, 'nop', 'nop'),
+    /found '\.word 0x40088000'/,
+  )
+})
+
+test('PatchBuilder fixes N64 checksums', (t) => {
+  // A 1 MiB ROM whose boot code ends in the 4 bytes that give it the CRC32
+  // of a known CIC. The checksums come from n64crc
+  const n64Rom = (forged) => {
+    const rom = Buffer.alloc(0x101000)
+    rom.writeUInt32BE(0x80371240, 0)
+    for (let i = 0x40; i < 0xffc; i++) {
+      rom[i] = (i * 7) & 0xff
+    }
+    rom.writeUInt32BE(forged, 0xffc)
+    for (let i = 0x1000; i < rom.length; i += 4) {
+      rom.writeUInt32BE(Math.imul(i, 0x9e3779b9) >>> 0, i)
+    }
+    return rom
+  }
+  for (const [cic, forged, crc1, crc2] of [
+    [6102, 0x1b946746, 0x90a44dde, 0x8e65ca23],
+    [6105, 0x1d54409c, 0xaaf0f438, 0xad77c087],
+  ]) {
+    const rom = n64Rom(forged)
+    const patched = new PatchBuilder(rom, { vram: 0x80000400 }).apply(true)
+    assert.equal(patched.readUInt32BE(0x10), crc1, `CIC ${cic}`)
+    assert.equal(patched.readUInt32BE(0x14), crc2, `CIC ${cic}`)
+    assert.ok(patched.subarray(0x18).equals(rom.subarray(0x18)))
+  }
+  assert.throws(
+    () => new PatchBuilder(n64Rom(0), { vram: 0x80000400 }).apply(true),
+    /Unknown CIC/,
+  )
+})
+
+test('diffSections', (t) => {
+  const section = (source) =>
+    CodeSection.fromWordList(wordsOf(source, 0x80000000), 0x80000000)
+  const a = section(`
+    .set noreorder
+    addiu $sp, $sp, -0x18
+    jal 0x80001000
+    lui $a0, 0x8010
+    addiu $a0, $a0, 0x20
+    or $v0, $a0, $zero
+    jr $ra
+    addiu $sp, $sp, 0x18
+  `)
+  const b = section(`
+    .set noreorder
+    addiu $sp, $sp, -0x20
+    jal 0x80002000
+    lui $a0, 0x8011
+    addiu $a0, $a0, 0x20
+    or $v1, $a0, $zero
+    nop
+    jr $ra
+    addiu $sp, $sp, 0x18
+  `)
+
+  const rows = diffSections(a, b)
+  assert.deepEqual(
+    rows.map((row) => row.kind),
+    [
+      DiffKind.ImmediateDiffers,
+      DiffKind.RelocationOnly,
+      DiffKind.RelocationOnly,
+      DiffKind.Equal,
+      DiffKind.RegisterRenamed,
+      DiffKind.Inserted,
+      DiffKind.Equal,
+      DiffKind.Equal,
+    ],
+  )
+  assert.equal(rows[5].aVram, undefined)
+  assert.equal(rows[5].bVram, 0x80000014)
+  assert.equal(rows[6].aVram, 0x80000014)
+
+  assert.equal(diffSections(b, a)[5].kind, DiffKind.Deleted)
+  assert.equal(diffSections(a, b, { context: 0 }).length, 5)
+  assert.deepEqual(diffSections(a, a, { context: 1 }), [])
+
+  // A register and an immediate changing together count as a renaming
+  const both = diffSections(
+    section('addiu $a1, $a0, 0x10'),
+    section('addiu $a2, $a0, 0x14'),
+  )
+  assert.deepEqual(both.map((row) => row.kind), [DiffKind.RegisterRenamed])
+
+  // Words rabbitizer cannot print, like RSP COP0 registers past 15
+  const cop0 = [0x40088000, 0x03e00008, 0x00000000]
+  const rsp = CodeSection.fromWordList(cop0, 0x04001000, 'rsp')
+  assert.equal(diffSections(rsp, rsp)[0].aText, '.word 0x40088000')
+  const func = Function.fromWordList(cop0, 0x04001000, 'rsp')
+  assert.ok(func.disassemble().includes('.word 0x40088000'))
+  assert.ok(func.toPseudoC().includes('/* .word 0x40088000 */'))
+})
+
+test('Function.fingerprint and matchFingerprints', (t) => {
+  const source = (target, hi, reg) => `
+    .set noreorder
+    addiu $sp, $sp, -0x18
+    sw $ra, 0x14($sp)
+    jal ${target}
+    lui $a0, ${hi}
+    addiu $a0, $a0, 0x20
+    beqz $a0, end
+    or ${reg}, $a0, $zero
+  end:
+    lw $ra, 0x14($sp)
+    jr $ra
+    addiu $sp, $sp, 0x18
+  `
+  const original = Function.fromWordList(
+    wordsOf(source('0x80001000', '0x8010', '$v0'), 0x80000000),
+    0x80000000,
+  )
+  const moved = Function.fromWordList(
+    wordsOf(source('0x80204000', '0x8022', '$v0'), 0x80200000),
+    0x80200000,
+  )
+  const renamed = Function.fromWordList(
+    wordsOf(source('0x80204000', '0x8022', '$v1'), 0x80200000),
+    0x80200000,
+  )
+
+  assert.match(original.fingerprint(), /^[0-9a-f]{16}$/)
+  assert.equal(original.fingerprint(), moved.fingerprint())
+  assert.notEqual(
+    original.fingerprint({ ignoreRelocs: false }),
+    moved.fingerprint({ ignoreRelocs: false }),
+  )
+  assert.notEqual(original.fingerprint(), renamed.fingerprint())
+  const options = { ignoreRegisters: true }
+  assert.equal(original.fingerprint(options), renamed.fingerprint(options))
+
+  const rom = Buffer.alloc(0x80)
+  rom.writeUInt32BE(0x03e00008, 0x0)
+  renamed.instructions().forEach((instr, i) => {
+    rom.writeUInt32BE(instr.word, 0x10 + i * 4)
+  })
+  const database = [
+    '// fingerprint size name',
+    `${original.fingerprint(options)} 0x${original.size.toString(16)} func`,
+  ].join('\n')
+  assert.deepEqual(
+    matchFingerprints(rom, database, { vram: 0x80000000, ...options }),
+    [{ name: 'func', vram: 0x80000010, offset: 0x10, size: 0x28 }],
+  )
+  assert.deepEqual(matchFingerprints(rom, database), [])
+  assert.throws(() => matchFingerprints(rom, 'abc 4 func'), /line 1/)
+})
+
+test('SignatureDatabase and identifyFunctions', (t) => {
+  const source = (last) => `
+    .set noreorder
+    addiu $sp, $sp, -0x18
+    sw $ra, 0x14($sp)
+    jal 0x80001000
+    lui $a0, 0x8010
+    addiu $a0, $a0, 0x20
+    or $v0, $a0, $zero
+    lw $ra, 0x14($sp)
+    ${last}
+    jr $ra
+    addiu $sp, $sp, 0x18
+  `
+  const func = (last) =>
+    Function.fromWordList(wordsOf(source(last), 0x80000000), 0x80000000)
+
+  const built = new SignatureDatabase()
+  built.add('osFoo', 'libultra 2.0I', func('nop'))
+  built.add('osFoo', 'libultra 2.0L', func('nop'))
+  built.add('osBar', 'libultra 2.0L', func('addiu $v0, $v0, 1'))
+  const db = SignatureDatabase.fromBuffer(built.toBuffer())
+  assert.equal(db.length, 3)
+  assert.deepEqual(db.sdks(), ['libultra 2.0I', 'libultra 2.0L'])
+
+  const section = CodeSection.fromWordList(
+    [
+      0x03e00008,
+      0x00000000,
+      ...wordsOf(source('addiu $v0, $v0, 2'), 0x80200008),
+      ...wordsOf(source('nop'), 0x80200030),
+    ],
+    0x80200000,
+  )
+  assert.deepEqual(identifyFunctions(section, db), [
+    {
+      name: 'osFoo',
+      sdks: ['libultra 2.0I', 'libultra 2.0L'],
+      vram: 0x80200030,
+      size: 0x28,
+      confidence: 1,
+    },
+  ])
+  const near = identifyFunctions(section, db, { sdk: 'libultra 2.0I' })
+  assert.equal(near.length, 2)
+  assert.equal(near[0].vram, 0x80200008)
+  assert.equal(near[0].confidence, 0.9)
+
+  assert.throws(() => SignatureDatabase.fromBuffer(Buffer.from('nope!')), /Not/)
+})
+
+test('search', (t) => {
+  const source = `
+    .set noreorder
+    lui $at, 0x8010
+    lw $t0, 0x20($at)
+    jal 0x80001234
+    nop
+    lui $at, 0x8011
+    lw $s0, -0x8($at)
+    beqz $v0, 0x80000000
+    addiu $sp, $sp, -0x18
+  `
+  const data = assembleBlock(source, { vram: 0x80000000 }).data
+  const find = (pattern) => search(data, pattern, { vram: 0x80000000 })
+
+  assert.deepEqual(find('lui $at, ?; lw $t?, ?($at)'), [0x80000000])
+  assert.deepEqual(find('lui $at, ?\nlw ?, ?($at)'), [0x80000000, 0x80000010])
+  assert.deepEqual(find('jal 0x80001234'), [0x80000008])
+  assert.deepEqual(find('b*'), [0x80000018])
+  assert.deepEqual(find('lw ?, -0x10..0($at)'), [0x80000014])
+  assert.deepEqual(find('0x3C01????; ?'), [0x80000000, 0x80000010])
+  assert.deepEqual(find('0x3C010000/0xFFE00000'), [0x80000000, 0x80000010])
+  assert.deepEqual(find('addiu $sp, $sp, 0x18'), [])
+  assert.deepEqual(find('addiu $sp, $sp, -24'), [0x8000001c])
+  assert.deepEqual(find('addiu $s?, ?, ?'), [])
+  assert.deepEqual(find('lw $s?, -8($1)'), [0x80000014])
+  assert.deepEqual(find('lw $t*, 32($at)'), [0x80000004])
+  assert.throws(() => find('lw ?, a..b'), /Invalid pattern/)
+})
+
+test('statistics', (t) => {
+  const section = CodeSection.fromWordList(
+    [
+      0x27bdffe8, // addiu $sp, $sp, -0x18
+      0xafbf0014, // sw $ra, 0x14($sp)
+      0x00801025, // move $v0, $a0
+      0x00000001, // invalid
+      0x8fbf0014, // lw $ra, 0x14($sp)
+      0x03e00008, // jr $ra
+      0x27bd0018, // addiu $sp, $sp, 0x18
+      0x54400001, // bnel $v0, $zero, . + 8
+      0x46002000, // add.s $f0, $f4, $f0
+    ],
+    0x80000000,
+  )
+  const stats = statistics(section)
+  assert.equal(stats.words, 9)
+  assert.equal(stats.invalid, 1)
+  assert.equal(stats.pseudos, 1)
+  assert.deepEqual(stats.byId[0], {
+    id: InstrId.CpuAddiu,
+    name: 'addiu',
+    count: 2,
+  })
+  assert.equal(stats.byId.length, 7)
+  assert.deepEqual(stats.byIdType, [
+    { idType: InstrIdType.CpuNormal, count: 5 },
+    { idType: InstrIdType.CpuSpecial, count: 2 },
+    { idType: InstrIdType.CpuCop1Fpus, count: 1 },
+  ])
+  assert.deepEqual(stats.byAccessType, [
+    { accessType: AccessType.Word, count: 2 },
+  ])
+
+  // paddw $v0, $a0, $a1
+  const mmi = statistics(
+    CodeSection.fromWordList([0x70851008], 0x80000000, 'r5900'),
+  )
+  assert.deepEqual(mmi.byId, [
+    { id: InstrId.R5900Paddw, name: 'paddw', count: 1 },
+  ])
+  assert.deepEqual(mmi.byIdType, [
+    { idType: InstrIdType.R5900Mmi0, count: 1 },
+  ])
+})
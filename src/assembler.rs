@@ -0,0 +1,1154 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! Assembly text back to instruction words
+//!
+//! rabbitizer only decodes, so the encodings are learnt from its decoder: a
+//! search over instruction words finds one word per `InstrId`, and flipping
+//! each of its bits tells which bits make up which operand. Assembling a line
+//! then fills the operand bits until rabbitizer prints each operand the way it
+//! was written.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
+
+use napi_derive::napi;
+use rabbitizer::Instruction as RustInstruction;
+use rabbitizer::{InstrCategory, InstrId, OperandType};
+
+use crate::enums::Abi;
+use crate::fields;
+use crate::instruction::parse_category;
+use crate::registers;
+
+/// Operands of at most this many bits are found by trying every value
+const BRUTE_FORCE_BITS: u32 = 12;
+/// Limit for operands that are neither numbers nor small enough, like
+/// Allegrex half floats
+const BRUTE_FORCE_MAX_BITS: u32 = 16;
+
+/// Options for `assemble`
+#[napi(object)]
+pub struct AssembleOptions {
+    /// Address of the first instruction, used for branch and jump targets
+    /// (defaults to 0)
+    pub vram: Option<u32>,
+    /// "cpu", "rsp", "r3000gte", "r4000allegrex" or "r5900" (defaults to "cpu")
+    pub category: Option<String>,
+    /// Names accepted for GPRs besides `$4` (defaults to O32)
+    pub abi: Option<Abi>,
+}
+
+/// Where and how a line failed to assemble
+pub(crate) struct AsmError {
+    /// 1-based column in the line
+    pub column: usize,
+    pub message: String,
+}
+
+impl AsmError {
+    pub fn new(column: usize, message: impl Into<String>) -> Self {
+        AsmError {
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<AsmError> for napi::Error {
+    fn from(error: AsmError) -> Self {
+        napi::Error::new(
+            napi::Status::InvalidArg,
+            format!("column {}: {}", error.column, error.message),
+        )
+    }
+}
+
+type Result<T> = std::result::Result<T, AsmError>;
+
+/// What a line is assembled for
+#[derive(Clone, Copy)]
+pub(crate) struct Context {
+    pub vram: u32,
+    pub category: InstrCategory,
+    pub abi: Abi,
+    /// Whether a label ending in an address, like `func_80001234`, stands for
+    /// that address
+    pub address_labels: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Kind {
+    /// A register, `$4` for GPRs whatever name they were written with
+    Register(String),
+    Number(i64),
+    /// Any other word, lowercased: mnemonics, COP0 register names, labels
+    Word(String),
+    Punct(char),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Token {
+    pub kind: Kind,
+    /// Byte range in the line
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn column(&self) -> usize {
+        self.start + 1
+    }
+
+    fn is_value(&self) -> bool {
+        !matches!(self.kind, Kind::Punct(c) if c != ')')
+    }
+}
+
+fn same_kinds(a: &[Token], b: &[Token]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.kind == b.kind)
+}
+
+pub(crate) fn parse_number(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let value = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+    Some(if negative { -value } else { value })
+}
+
+/// Splits operand text into tokens, starting at byte `offset` of the line
+///
+/// Numbers are parsed, so `0x10` and `16` compare equal, and GPRs are
+/// renamed to their number using the names of `abi`.
+fn tokenize(text: &str, offset: usize, abi: Abi) -> Result<Vec<Token>> {
+    let bytes = text.as_bytes();
+    let word_end = |from: usize| {
+        let mut end = from;
+        while end < bytes.len()
+            && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_' || bytes[end] == b'.')
+        {
+            end += 1;
+        }
+        end
+    };
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let negative = c == b'-'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && !tokens.last().is_some_and(Token::is_value);
+        let (kind, end) = if c == b'$' {
+            let end = word_end(i + 1);
+            let name = text[i + 1..end].to_ascii_lowercase();
+            let name = match registers::gpr_index(&name, abi) {
+                Some(index) => format!("${}", index),
+                None => format!("${}", name),
+            };
+            (Kind::Register(name), end)
+        } else if c.is_ascii_digit() || negative {
+            let end = word_end(i + 1);
+            let literal = &text[i..end];
+            // Also RSP elements like `0q` and Allegrex floats like `1.5`.
+            // `-0` is kept apart from `0`, as Allegrex prints negated zeros
+            let kind = match (parse_number(literal), literal.parse::<f64>()) {
+                (Some(0), _) if negative => Kind::Word(literal.to_string()),
+                (Some(value), _) => Kind::Number(value),
+                (None, Ok(value)) => Kind::Word(value.to_string()),
+                (None, Err(_)) => Kind::Word(literal.to_ascii_lowercase()),
+            };
+            (kind, end)
+        } else if c.is_ascii_alphabetic() || c == b'_' || c == b'.' {
+            let end = word_end(i + 1);
+            (Kind::Word(text[i..end].to_ascii_lowercase()), end)
+        } else {
+            let end = i + text[i..].chars().next().map_or(1, char::len_utf8);
+            (Kind::Punct(text[i..end].chars().next().unwrap_or(' ')), end)
+        };
+        tokens.push(Token {
+            kind,
+            start: offset + i,
+            end: offset + end,
+        });
+        i = end;
+    }
+    Ok(tokens)
+}
+
+/// Index of the first comma at `from` or after it outside of parentheses
+fn operand_end(tokens: &[Token], from: usize) -> usize {
+    let mut depth = 0i32;
+    for (i, token) in tokens.iter().enumerate().skip(from) {
+        match token.kind {
+            Kind::Punct('(') | Kind::Punct('[') => depth += 1,
+            Kind::Punct(')') | Kind::Punct(']') => depth -= 1,
+            Kind::Punct(',') if depth <= 0 => return i,
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// Spreads the low bits of `value` over the set bits of `mask`
+fn deposit(word: u32, mask: u32, value: u32) -> u32 {
+    let mut out = word & !mask;
+    let mut value = value;
+    for bit in 0..32 {
+        if mask & (1 << bit) != 0 {
+            if value & 1 != 0 {
+                out |= 1 << bit;
+            }
+            value >>= 1;
+        }
+    }
+    out
+}
+
+/// The bits of an operand inside a template word
+struct Operand {
+    kind: OperandType,
+    mask: u32,
+    /// Bits printed inside the trailing parentheses, like the base register
+    /// of `0x10($sp)`
+    inner: u32,
+}
+
+/// A word for one `InstrId`, with the bits of each of its operands
+struct Template {
+    word: u32,
+    operands: Vec<Operand>,
+    /// Bits only printed as a suffix of the mnemonic, like the `.xyzw` of
+    /// R5900 vector instructions
+    suffix: u32,
+}
+
+/// Templates of a category, by mnemonic
+struct Table {
+    templates: HashMap<&'static str, Vec<Template>>,
+}
+
+/// Checks if rabbitizer can print an operand
+///
+/// rabbitizer only names the 16 COP0 registers of the RSP and aborts on the
+/// others.
+fn printable(kind: OperandType, instr: &RustInstruction) -> bool {
+    kind != OperandType::rsp_cop0d || fields::rd(instr) < 16
+}
+
+pub(crate) fn print_operand(kind: OperandType, instr: &RustInstruction) -> Option<String> {
+    printable(kind, instr).then(|| kind.disassemble(instr, None))
+}
+
+fn operands_printable(instr: &RustInstruction) -> bool {
+    instr
+        .get_operands_slice()
+        .iter()
+        .all(|&kind| printable(kind, instr))
+}
+
+/// Disassembles an instruction, or prints it as `.word 0x...` when rabbitizer
+/// would abort on one of its operands
+pub(crate) fn disassemble(instr: &RustInstruction, immediate_override: Option<&str>) -> String {
+    if operands_printable(instr) {
+        instr.disassemble(immediate_override, 0)
+    } else {
+        format!(".word 0x{:08X}", instr.raw())
+    }
+}
+
+fn is_valid(instr: &RustInstruction) -> bool {
+    instr.is_valid() && instr.opcode_name() != "INVALID" && operands_printable(instr)
+}
+
+/// Finds one word for each instruction the decoder knows
+///
+/// Starts from words covering every major opcode with the common
+/// sub-opcode fields, then flips single bits of every new instruction found,
+/// going through up to two invalid words in between.
+fn discover(category: InstrCategory) -> Vec<u32> {
+    let mut found: HashMap<InstrId, u32> = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    for opcode in 0..64u32 {
+        for field in 0..32u32 {
+            for function in 0..64u32 {
+                let base = opcode << 26;
+                queue.push_back((base | (field << 21) | function, 2u8));
+                queue.push_back((base | (field << 6) | function, 2));
+                queue.push_back((base | (field << 16) | ((function & 0x1F) << 21), 2));
+            }
+        }
+    }
+
+    while let Some((word, depth)) = queue.pop_front() {
+        if !seen.insert(word) {
+            continue;
+        }
+        let instr = RustInstruction::new(word, 0, category);
+        if is_valid(&instr) {
+            if found.contains_key(&instr.unique_id) {
+                continue;
+            }
+            found.insert(instr.unique_id, word);
+            queue.extend((0..32).map(|bit| (word ^ (1 << bit), 0)));
+        } else if depth < 2 {
+            queue.extend((0..32).map(|bit| (word ^ (1 << bit), depth + 1)));
+        }
+    }
+
+    let mut words: Vec<(InstrId, u32)> = found.into_iter().collect();
+    words.sort_by_key(|&(id, _)| id as u32);
+    words.into_iter().map(|(_, word)| word).collect()
+}
+
+/// Finds the bits of each operand by flipping the bits of `word` one by one
+///
+/// A flip can turn the instruction into another one only for some operand
+/// values (`beq $zero, $1` into `b`), so the flips are repeated from a word
+/// with every operand bit found so far inverted.
+fn layout(word: u32, category: InstrCategory) -> Template {
+    let instr = RustInstruction::new(word, 0, category);
+    let mut template = Template {
+        word,
+        operands: instr
+            .get_operands_slice()
+            .iter()
+            .map(|&kind| Operand {
+                kind,
+                mask: 0,
+                inner: 0,
+            })
+            .collect(),
+        suffix: 0,
+    };
+
+    flip_bits(&mut template, word, category, instr.unique_id);
+    let inverted = template
+        .operands
+        .iter()
+        .fold(word, |word, operand| word ^ operand.mask);
+    if is_valid(&RustInstruction::new(inverted, 0, category)) {
+        flip_bits(&mut template, inverted, category, instr.unique_id);
+    }
+    template
+}
+
+/// The mnemonic as printed, with any suffix
+fn mnemonic(instr: &RustInstruction) -> Option<String> {
+    is_valid(instr).then(|| {
+        let text = instr.disassemble(None, 0);
+        text.split_whitespace().next().unwrap_or("").to_string()
+    })
+}
+
+fn flip_bits(template: &mut Template, word: u32, category: InstrCategory, id: InstrId) {
+    let instr = RustInstruction::new(word, 0, category);
+    if instr.unique_id != id {
+        return;
+    }
+    let name = mnemonic(&instr);
+    let texts: Vec<String> = template
+        .operands
+        .iter()
+        .map(|operand| print_operand(operand.kind, &instr).unwrap_or_default())
+        .collect();
+
+    for bit in 0..32 {
+        let flipped = RustInstruction::new(word ^ (1 << bit), 0, category);
+        // `or $a0, $a1, $zero` decodes as `move`, but the bits are still rt
+        let same =
+            flipped.unique_id == instr.unique_id || (flipped.is_pseudo() && !instr.is_pseudo());
+        if !same {
+            continue;
+        }
+        let renamed = mnemonic(&flipped).is_some_and(|flipped| Some(flipped) != name);
+        if flipped.unique_id == instr.unique_id && renamed {
+            template.suffix |= 1 << bit;
+        }
+        for (operand, text) in template.operands.iter_mut().zip(&texts) {
+            let changed = print_operand(operand.kind, &flipped);
+            if changed.as_ref() == Some(text) {
+                continue;
+            }
+            operand.mask |= 1 << bit;
+            let changed = changed.unwrap_or_default();
+            if let (Some(a), Some(b)) = (text.rfind('('), changed.rfind('(')) {
+                if text[..a] == changed[..b] {
+                    operand.inner |= 1 << bit;
+                }
+            }
+        }
+    }
+}
+
+fn build_table(category: InstrCategory) -> Table {
+    let mut templates: HashMap<&'static str, Vec<Template>> = HashMap::new();
+    for word in discover(category) {
+        let name = RustInstruction::new(word, 0, category).opcode_name();
+        templates
+            .entry(name)
+            .or_default()
+            .push(layout(word, category));
+    }
+    Table { templates }
+}
+
+/// The templates of a category, built on first use
+///
+/// Walking the decoder takes 0.1-0.2 s per category in a release build, and
+/// about a second in a debug one.
+fn table(category: InstrCategory) -> &'static Table {
+    static TABLES: [OnceLock<Table>; 5] = [
+        OnceLock::new(),
+        OnceLock::new(),
+        OnceLock::new(),
+        OnceLock::new(),
+        OnceLock::new(),
+    ];
+    let index = match category {
+        InstrCategory::RSP => 1,
+        InstrCategory::R3000GTE => 2,
+        InstrCategory::R4000ALLEGREX => 3,
+        InstrCategory::R5900 => 4,
+        InstrCategory::CPU | InstrCategory::MAX => 0,
+    };
+    TABLES[index].get_or_init(|| build_table(category))
+}
+
+/// A word of the instruction `id`, with its operand bits cleared where that
+/// stays the same instruction
+pub(crate) fn template_word(category: InstrCategory, id: u32) -> Option<u32> {
+    let decode = |word| RustInstruction::new(word, 0, category);
+    let template = table(category)
+        .templates
+        .values()
+        .flatten()
+        .find(|template| decode(template.word).unique_id as u32 == id)?;
+    let operands = template
+        .operands
+        .iter()
+        .fold(template.suffix, |mask, operand| mask | operand.mask);
+    let cleared = template.word & !operands;
+    let instr = decode(cleared);
+    Some(if is_valid(&instr) && instr.unique_id as u32 == id {
+        cleared
+    } else {
+        template.word
+    })
+}
+
+/// A branch or jump target as written
+enum Target {
+    /// `. + 4 + (0x3 << 2)`, counted in instructions after the delay slot
+    Relative(i64),
+    Absolute(u32),
+}
+
+/// Parses `. + 4 + (N << 2)`, an address, or, with `address_labels`, a label
+/// ending in one like `.L80001234` and `func_80001234`
+fn parse_target(tokens: &[Token], address_labels: bool) -> Option<Target> {
+    use Kind::{Number, Punct, Word};
+
+    match tokens
+        .iter()
+        .map(|t| &t.kind)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [Word(dot), Punct('+'), Number(4), Punct('+'), Punct('('), Number(offset), Punct('<'), Punct('<'), Number(2), Punct(')')]
+            if dot == "." =>
+        {
+            Some(Target::Relative(*offset))
+        }
+        [Number(address)] => u32::try_from(*address).ok().map(Target::Absolute),
+        [Word(label)] if address_labels && label.len() > 8 => {
+            let address = &label[label.len() - 8..];
+            u32::from_str_radix(address, 16)
+                .ok()
+                .filter(|_| address.bytes().all(|b| b.is_ascii_hexdigit()))
+                .map(Target::Absolute)
+        }
+        _ => None,
+    }
+}
+
+/// Splits tokens at the commas outside of parentheses
+fn split_commas(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = operand_end(tokens, start);
+        parts.push(&tokens[start..end]);
+        if end == tokens.len() {
+            return parts;
+        }
+        start = end + 1;
+    }
+}
+
+fn is_code(kind: OperandType) -> bool {
+    matches!(kind, OperandType::cpu_code | OperandType::cpu_code_lower)
+}
+
+/// Encodes a line against one template
+struct Encoder<'a> {
+    context: &'a Context,
+    line: &'a str,
+    /// Lowercased, with any suffix
+    mnemonic: &'a str,
+    tokens: &'a [Token],
+}
+
+impl Encoder<'_> {
+    fn text(&self, tokens: &[Token]) -> &str {
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => &self.line[first.start..last.end],
+            _ => "",
+        }
+    }
+
+    fn end_column(&self) -> usize {
+        self.line.trim_end().len() + 1
+    }
+
+    fn decode(&self, word: u32) -> RustInstruction {
+        RustInstruction::new(word, self.context.vram, self.context.category)
+    }
+
+    /// How rabbitizer prints an operand of `word`
+    fn printed(&self, kind: OperandType, word: u32) -> Vec<Token> {
+        print_operand(kind, &self.decode(word))
+            .and_then(|text| tokenize(&text, 0, Abi::O32).ok())
+            .unwrap_or_default()
+    }
+
+    /// The ways an operand of `word` can be written: as rabbitizer prints it,
+    /// and COP0 registers also by number like `$12`
+    fn forms(&self, kind: OperandType, word: u32) -> Vec<Vec<Token>> {
+        let printed = self.printed(kind, word);
+        let is_cop0 = matches!(kind, OperandType::cpu_cop0d | OperandType::rsp_cop0d);
+        let mut forms = Vec::new();
+        if is_cop0 && !printed.is_empty() {
+            let number = fields::rd(&self.decode(word));
+            forms.push(vec![Token {
+                kind: Kind::Register(format!("${}", number)),
+                start: 0,
+                end: 0,
+            }]);
+        }
+        forms.push(printed);
+        forms
+    }
+
+    /// The tokens an operand should print as, with the offset of `($sp)`
+    /// filled in as rabbitizer prints it: `0x0($sp)`
+    ///
+    /// Operands printed in parentheses only, like the `($vi1++)` of R5900
+    /// `vlqi`, are left as they are.
+    fn expected(&self, operand: &Operand, word: u32, span: &[Token]) -> Vec<Token> {
+        let offset_printed = || {
+            self.printed(operand.kind, word)
+                .first()
+                .is_some_and(|first| first.kind != Kind::Punct('('))
+        };
+        match span.first() {
+            Some(first)
+                if operand.inner != 0 && first.kind == Kind::Punct('(') && offset_printed() =>
+            {
+                let zero = Token {
+                    kind: Kind::Number(0),
+                    ..first.clone()
+                };
+                std::iter::once(zero).chain(span.iter().cloned()).collect()
+            }
+            _ => span.to_vec(),
+        }
+    }
+
+    fn invalid(&self, tokens: &[Token]) -> AsmError {
+        match tokens.first() {
+            Some(first) => AsmError::new(
+                first.column(),
+                format!("invalid operand '{}'", self.text(tokens)),
+            ),
+            None => AsmError::new(self.end_column(), "missing operand"),
+        }
+    }
+
+    fn encode(&self, template: &Template) -> Result<u32> {
+        let mut word = template.word;
+        let mut pos = 0;
+        let mut spans = Vec::with_capacity(template.operands.len());
+        // Bits set by earlier operands, which later ones sharing them keep
+        let mut solved_bits = 0;
+        for (i, operand) in template.operands.iter().enumerate() {
+            // The code of `syscall`, `break` and traps may be left out, as
+            // with GNU as
+            if is_code(operand.kind) && pos == self.tokens.len() {
+                word = deposit(word, operand.mask, 0);
+                spans.push(None);
+                continue;
+            }
+            if i > 0 {
+                match self.tokens.get(pos) {
+                    Some(Token {
+                        kind: Kind::Punct(','),
+                        ..
+                    }) => pos += 1,
+                    Some(token) => return Err(AsmError::new(token.column(), "expected ','")),
+                    None => return Err(AsmError::new(self.end_column(), "missing operand")),
+                }
+            }
+            let last = i + 1 == template.operands.len();
+            let unsolved = Operand {
+                kind: operand.kind,
+                mask: operand.mask & !solved_bits,
+                inner: operand.inner & !solved_bits,
+            };
+            let (solved, end) = self.solve(word, &unsolved, pos, last)?;
+            word = solved;
+            solved_bits |= operand.mask;
+            spans.push(Some(pos..end));
+            pos = end;
+        }
+        if let Some(token) = self.tokens.get(pos) {
+            return Err(AsmError::new(
+                token.column(),
+                format!("unexpected '{}'", self.text(&self.tokens[pos..])),
+            ));
+        }
+
+        let suffix = template.suffix
+            & !template
+                .operands
+                .iter()
+                .fold(0, |mask, operand| mask | operand.mask);
+        if suffix != 0 {
+            word = self
+                .brute_force(word, suffix, |word| {
+                    mnemonic(&self.decode(word)).as_deref() == Some(self.mnemonic)
+                })
+                .unwrap_or(word);
+        }
+
+        // Operands sharing bits, like the `xyzw` of R5900 vector registers,
+        // must all still print as written
+        for (operand, span) in template.operands.iter().zip(spans) {
+            let Some(span) = span else {
+                continue;
+            };
+            let is_parsed = matches!(
+                operand.kind,
+                OperandType::cpu_branch_target_label
+                    | OperandType::cpu_label
+                    | OperandType::cpu_code
+            );
+            let expected = self.expected(operand, word, &self.tokens[span.clone()]);
+            let prints = |form: &Vec<Token>| same_kinds(form, &expected);
+            if !is_parsed && !self.forms(operand.kind, word).iter().any(prints) {
+                return Err(self.invalid(&self.tokens[span]));
+            }
+        }
+        Ok(word)
+    }
+
+    /// Fills the bits of `operand` to match the tokens at `pos`, returning
+    /// the new word and the index after the operand
+    ///
+    /// The last operand takes the rest of the line, as some print commas
+    /// themselves, like the `, wb` of Allegrex `sv.q`.
+    fn solve(&self, word: u32, operand: &Operand, pos: usize, last: bool) -> Result<(u32, usize)> {
+        let end = if last {
+            self.tokens.len()
+        } else {
+            operand_end(self.tokens, pos)
+        };
+        let span = &self.tokens[pos..end];
+        match operand.kind {
+            OperandType::cpu_branch_target_label => {
+                return self
+                    .branch(word, operand.mask, span)
+                    .map(|word| (word, end))
+            }
+            OperandType::cpu_label => {
+                return self.jump(word, operand.mask, span).map(|word| (word, end))
+            }
+            OperandType::cpu_code => {
+                return self.code(word, operand.mask, span).map(|word| (word, end))
+            }
+            _ => {}
+        }
+
+        if operand.inner == 0 && operand.mask.count_ones() <= BRUTE_FORCE_BITS {
+            return self.match_prefix(word, operand, pos);
+        }
+        if span.is_empty() {
+            return Err(self.invalid(span));
+        }
+
+        // `0x10($sp)`: the part in parentheses first, then the number
+        let paren = span
+            .iter()
+            .rposition(|token| token.kind == Kind::Punct('('))
+            .filter(|_| operand.inner != 0);
+        let mut word = word;
+        let value = match paren {
+            Some(paren) => {
+                let inner = &span[paren..];
+                word = self
+                    .brute_force(word, operand.inner, |word| {
+                        let printed = self.printed(operand.kind, word);
+                        let start = printed.iter().rposition(|t| t.kind == Kind::Punct('('));
+                        start.is_some_and(|start| same_kinds(&printed[start..], inner))
+                    })
+                    .ok_or_else(|| self.invalid(inner))?;
+                &span[..paren]
+            }
+            None => span,
+        };
+
+        let mask = operand.mask & !operand.inner;
+        let expected = self.expected(operand, word, span);
+        let matches = |word: u32| same_kinds(&self.printed(operand.kind, word), &expected);
+        let number = match value {
+            [] => Some(0),
+            [Token {
+                kind: Kind::Number(number),
+                ..
+            }] => Some(*number),
+            _ => None,
+        };
+        // Offsets may be printed scaled by the access size, next to a flag in
+        // the lowest bit
+        let solved = number
+            .and_then(|number| {
+                (0..=4)
+                    .filter(|shift| number % (1 << shift) == 0)
+                    .map(|shift| (number >> shift) as u32)
+                    .flat_map(|value| [value, value << 1, value << 1 | 1])
+                    .map(|value| deposit(word, mask, value))
+                    .find(|&word| matches(word))
+            })
+            .or_else(|| {
+                (mask.count_ones() <= BRUTE_FORCE_MAX_BITS)
+                    .then(|| self.brute_force(word, mask, matches))
+                    .flatten()
+            })
+            .or_else(|| {
+                paren
+                    .is_none()
+                    .then(|| self.solve_parts(word, mask, operand.kind, &expected))
+                    .flatten()
+            });
+        match (solved, number) {
+            (Some(word), _) => Ok((word, end)),
+            (None, Some(_)) if !value.is_empty() => Err(AsmError::new(
+                value[0].column(),
+                format!("value {} is out of range", self.text(value)),
+            )),
+            _ => Err(self.invalid(span)),
+        }
+    }
+
+    /// Solves an operand printed as several comma separated parts, like the
+    /// `ne, S322, S312` of Allegrex `vcmp.s`, one part at a time
+    ///
+    /// The bits of each part are the ones changing how it prints with every
+    /// bit of the operand set, as parts may only be printed when nonzero.
+    /// Bits left over, which no expected part depends on, are solved last.
+    fn solve_parts(
+        &self,
+        word: u32,
+        mask: u32,
+        kind: OperandType,
+        expected: &[Token],
+    ) -> Option<u32> {
+        let parts = split_commas(expected);
+        let part = |word: u32, k: usize| -> Option<Vec<Token>> {
+            let printed = self.printed(kind, word);
+            split_commas(&printed).get(k).map(|part| part.to_vec())
+        };
+
+        let mut word = word | mask;
+        let mut used = 0;
+        for (k, expected) in parts.iter().enumerate() {
+            let current = part(word, k)?;
+            let bits = (0..32)
+                .map(|bit| 1u32 << bit)
+                .filter(|&bit| mask & bit != 0)
+                .filter(|&bit| part(word ^ bit, k).is_none_or(|part| !same_kinds(&part, &current)))
+                .fold(0, |bits, bit| bits | bit);
+            if bits.count_ones() > BRUTE_FORCE_MAX_BITS {
+                return None;
+            }
+            word = self.brute_force(word, bits, |word| {
+                part(word, k).is_some_and(|part| same_kinds(&part, expected))
+            })?;
+            used |= bits;
+        }
+        let rest = mask & !used;
+        if rest.count_ones() > BRUTE_FORCE_MAX_BITS {
+            return None;
+        }
+        self.brute_force(word, rest, |word| {
+            same_kinds(&self.printed(kind, word), expected)
+        })
+    }
+
+    fn brute_force(&self, word: u32, mask: u32, matches: impl Fn(u32) -> bool) -> Option<u32> {
+        (0..1u32 << mask.count_ones())
+            .map(|value| deposit(word, mask, value))
+            .find(|&word| matches(word))
+    }
+
+    /// Tries every value of a small operand, keeping the one printed as the
+    /// longest run of tokens followed by a comma or the end of the line
+    ///
+    /// Some operands print commas themselves, like the optional `rd` of
+    /// `jalr $a0, $t9`.
+    fn match_prefix(&self, word: u32, operand: &Operand, pos: usize) -> Result<(u32, usize)> {
+        let rest = &self.tokens[pos..];
+        let mut best: Option<(u32, usize)> = None;
+        for value in 0..1u32 << operand.mask.count_ones() {
+            let candidate = deposit(word, operand.mask, value);
+            for form in self.forms(operand.kind, candidate) {
+                let len = form.len();
+                let fits = len <= rest.len()
+                    && same_kinds(&form, &rest[..len])
+                    && rest
+                        .get(len)
+                        .is_none_or(|next| next.kind == Kind::Punct(','));
+                if fits && best.is_none_or(|(_, best)| len > best) {
+                    best = Some((candidate, len));
+                }
+            }
+        }
+        match best {
+            Some((word, len)) => Ok((word, pos + len)),
+            None => Err(self.invalid(&rest[..operand_end(rest, 0)])),
+        }
+    }
+
+    fn target(&self, span: &[Token]) -> Result<Target> {
+        parse_target(span, self.context.address_labels).ok_or_else(|| {
+            AsmError::new(
+                span[0].column(),
+                format!("invalid target '{}'", self.text(span)),
+            )
+        })
+    }
+
+    fn branch(&self, word: u32, mask: u32, span: &[Token]) -> Result<u32> {
+        let offset = match self.target(span)? {
+            Target::Relative(offset) => offset,
+            Target::Absolute(address) => {
+                if !address.is_multiple_of(4) {
+                    return Err(AsmError::new(
+                        span[0].column(),
+                        format!("branch target 0x{:08X} is not word aligned", address),
+                    ));
+                }
+                let distance = address.wrapping_sub(self.context.vram.wrapping_add(4));
+                let distance = if self.context.category == InstrCategory::RSP {
+                    // IMEM wraps around every 4 KiB
+                    ((distance << 20) as i32 >> 20) as i64
+                } else {
+                    distance as i32 as i64
+                };
+                distance / 4
+            }
+        };
+        if !(-0x8000..=0x7FFF).contains(&offset) {
+            return Err(AsmError::new(
+                span[0].column(),
+                format!("branch target '{}' is out of range", self.text(span)),
+            ));
+        }
+        Ok(deposit(word, mask, offset as u32))
+    }
+
+    /// Encodes the code of `break` and `sdbbp`, printed as `upper` or
+    /// `upper, lower`
+    fn code(&self, word: u32, mask: u32, span: &[Token]) -> Result<u32> {
+        use Kind::{Number, Punct};
+
+        let kinds: Vec<&Kind> = span.iter().map(|token| &token.kind).collect();
+        let (upper, lower) = match kinds.as_slice() {
+            [Number(upper)] => (*upper, 0),
+            [Number(upper), Punct(','), Number(lower)] => (*upper, *lower),
+            _ => return Err(self.invalid(span)),
+        };
+        if !(0..0x400).contains(&upper) || !(0..0x400).contains(&lower) {
+            return Err(AsmError::new(
+                span[0].column(),
+                format!("code '{}' is out of range", self.text(span)),
+            ));
+        }
+        Ok(deposit(word, mask, (upper << 10 | lower) as u32))
+    }
+
+    fn jump(&self, word: u32, mask: u32, span: &[Token]) -> Result<u32> {
+        let address = match self.target(span)? {
+            Target::Absolute(address) => address,
+            Target::Relative(_) => return Err(self.invalid(span)),
+        };
+        let vram = self.context.vram;
+        if !address.is_multiple_of(4) || !fields::in_jump_region(vram, address) {
+            return Err(AsmError::new(
+                span[0].column(),
+                format!(
+                    "jump target 0x{:08X} is not reachable from 0x{:08X}",
+                    address, vram
+                ),
+            ));
+        }
+        Ok(deposit(word, mask, (address >> 2) & 0x03FF_FFFF))
+    }
+}
+
+/// Parses the comma separated numbers of `.word`
+fn words(tokens: &[Token], line: &str) -> Result<Vec<u32>> {
+    let mut words = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let expected_number = i % 2 == 0;
+        match (&token.kind, expected_number) {
+            (Kind::Number(value), true) if (-(1 << 31)..(1 << 32)).contains(value) => {
+                words.push(*value as u32)
+            }
+            (Kind::Punct(','), false) => {}
+            _ => {
+                return Err(AsmError::new(
+                    token.column(),
+                    format!("unexpected '{}'", &line[token.start..token.end]),
+                ))
+            }
+        }
+    }
+    if words.is_empty() || tokens.len().is_multiple_of(2) {
+        return Err(AsmError::new(line.trim_end().len() + 1, "missing value"));
+    }
+    Ok(words)
+}
+
+/// Expands `li` and `la` into `lui`/`ori`/`addiu` lines
+fn expand_load(
+    mnemonic: &str,
+    tokens: &[Token],
+    line: &str,
+    address_labels: bool,
+) -> Result<Vec<String>> {
+    let (register, value) = match tokens {
+        [Token {
+            kind: Kind::Register(register),
+            ..
+        }, Token {
+            kind: Kind::Punct(','),
+            ..
+        }, value] => (register, value),
+        _ => {
+            let column = tokens
+                .first()
+                .map_or(line.trim_end().len() + 1, Token::column);
+            return Err(AsmError::new(
+                column,
+                format!("{} expects a register and a value", mnemonic),
+            ));
+        }
+    };
+    let address = match &value.kind {
+        Kind::Number(number) => Some(*number),
+        _ => match parse_target(std::slice::from_ref(value), address_labels) {
+            Some(Target::Absolute(address)) if mnemonic == "la" => Some(address as i64),
+            _ => None,
+        },
+    };
+    let value = address
+        .filter(|value| (-(1 << 31)..(1 << 32)).contains(value))
+        .ok_or_else(|| {
+            AsmError::new(
+                value.column(),
+                format!("invalid value '{}'", &line[value.start..value.end]),
+            )
+        })?;
+
+    let (hi, lo) = (value as u32 >> 16, value as u32 & 0xFFFF);
+    Ok(if (-0x8000..0x8000).contains(&value) {
+        vec![format!("addiu {}, $zero, {}", register, value)]
+    } else if mnemonic == "li" && (0..0x10000).contains(&value) {
+        vec![format!("ori {}, $zero, {}", register, value)]
+    } else if lo == 0 {
+        vec![format!("lui {}, {}", register, hi)]
+    } else if mnemonic == "li" {
+        vec![
+            format!("lui {}, {}", register, hi),
+            format!("ori {0}, {0}, {1}", register, lo),
+        ]
+    } else {
+        // %hi is adjusted for the sign extension of %lo
+        let hi = (value as u32).wrapping_add(0x8000) >> 16;
+        vec![
+            format!("lui {}, {}", register, hi),
+            format!("addiu {0}, {0}, {1}", register, lo as u16 as i16),
+        ]
+    })
+}
+
+/// A line split into its mnemonic and operand tokens
+pub(crate) struct Statement<'a> {
+    /// The line without its comment
+    pub code: &'a str,
+    /// Byte range of the mnemonic in the line
+    pub start: usize,
+    pub mnemonic_end: usize,
+    /// Lowercased
+    pub mnemonic: String,
+    pub tokens: Vec<Token>,
+}
+
+impl Statement<'_> {
+    pub fn parse(line: &str, abi: Abi) -> Result<Statement<'_>> {
+        let code = &line[..line.find('#').unwrap_or(line.len())];
+        let start = code.len() - code.trim_start().len();
+        let rest = &code[start..];
+        if rest.trim().is_empty() {
+            return Err(AsmError::new(start + 1, "expected an instruction"));
+        }
+        let mnemonic_end = start + rest.find(char::is_whitespace).unwrap_or(rest.len());
+        Ok(Statement {
+            code,
+            start,
+            mnemonic_end,
+            mnemonic: code[start..mnemonic_end].to_ascii_lowercase(),
+            tokens: tokenize(&code[mnemonic_end..], mnemonic_end, abi)?,
+        })
+    }
+
+    /// The text of tokens as written
+    pub fn text(&self, tokens: &[Token]) -> &str {
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => &self.code[first.start..last.end],
+            _ => "",
+        }
+    }
+}
+
+/// Assembles one line, without labels
+pub(crate) fn assemble_line(line: &str, context: &Context) -> Result<Vec<u32>> {
+    assemble_statement(&Statement::parse(line, context.abi)?, context)
+}
+
+/// Assembles a parsed line, whose tokens may have been rewritten
+pub(crate) fn assemble_statement(statement: &Statement, context: &Context) -> Result<Vec<u32>> {
+    let Statement {
+        code,
+        start,
+        mnemonic_end,
+        ref mnemonic,
+        ref tokens,
+    } = *statement;
+
+    match mnemonic.as_str() {
+        ".word" => return words(tokens, code),
+        "li" | "la" => {
+            let mut words = Vec::new();
+            for expanded in expand_load(mnemonic, tokens, code, context.address_labels)? {
+                let context = Context {
+                    vram: context.vram.wrapping_add(words.len() as u32 * 4),
+                    ..*context
+                };
+                // Only the register can be wrong by now
+                let assembled = assemble_line(&expanded, &context)
+                    .map_err(|error| AsmError::new(tokens[0].column(), error.message))?;
+                words.extend(assembled);
+            }
+            return Ok(words);
+        }
+        _ => {}
+    }
+
+    let table = table(context.category);
+    // Suffixes like the `.xyzw` of R5900 vector instructions are printed from
+    // operand bits and are not part of the name
+    let templates = table.templates.get(mnemonic.as_str()).or_else(|| {
+        let (name, _) = mnemonic.rsplit_once('.')?;
+        table.templates.get(name)
+    });
+    let Some(templates) = templates else {
+        return Err(AsmError::new(
+            start + 1,
+            format!("unknown instruction '{}'", &code[start..mnemonic_end]),
+        ));
+    };
+
+    let encoder = Encoder {
+        context,
+        line: code,
+        mnemonic,
+        tokens,
+    };
+    let mut furthest: Option<AsmError> = None;
+    for template in templates {
+        let result = encoder.encode(template).and_then(|word| {
+            let instr = encoder.decode(word);
+            let name = self::mnemonic(&instr).unwrap_or_default();
+            if name == *mnemonic || instr.is_pseudo() {
+                Ok(word)
+            } else {
+                Err(AsmError::new(
+                    start + 1,
+                    format!("'{}' assembles to '{}'", &code[start..mnemonic_end], name),
+                ))
+            }
+        });
+        match result {
+            Ok(word) => return Ok(vec![word]),
+            Err(error) if furthest.as_ref().is_none_or(|f| error.column > f.column) => {
+                furthest = Some(error)
+            }
+            Err(_) => {}
+        }
+    }
+    Err(furthest.unwrap_or_else(|| AsmError::new(start + 1, "cannot assemble")))
+}
+
+/// Assembles one line of MIPS assembly into instruction words
+///
+/// Takes the syntax rabbitizer disassembles to: any instruction of the
+/// category, with GPRs named by number or by the ABI, numbers in decimal or
+/// hex, and branch and jump targets as `. + 4 + (N << 2)`, an address or a
+/// label ending in one (`.L80001234`, `func_80001234`). `li` and `la` expand
+/// to `lui`/`ori`/`addiu` as needed, `.word` gives its values as is, and
+/// anything after `#` is ignored. Errors start with the column they were
+/// found at, like `column 9: invalid operand '$q0'`.
+///
+/// # Arguments
+/// * `line` - The assembly line, like `addiu $sp, $sp, -0x18`
+/// * `options` - The vram, category and ABI to assemble for (optional)
+#[napi]
+pub fn assemble(line: String, options: Option<AssembleOptions>) -> napi::Result<Vec<u32>> {
+    let options = options.unwrap_or(AssembleOptions {
+        vram: None,
+        category: None,
+        abi: None,
+    });
+    let context = Context {
+        vram: options.vram.unwrap_or(0),
+        category: parse_category(options.category.as_deref()),
+        abi: options.abi.unwrap_or(Abi::O32),
+        address_labels: true,
+    };
+    Ok(assemble_line(&line, &context)?)
+}
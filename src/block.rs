@@ -0,0 +1,633 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! Assembly of whole blocks of source: labels, data directives and
+//! relocations on top of the line assembler
+//!
+//! The source is assembled twice. The first pass only lays it out, with every
+//! label at the start of the block, and the second one uses the addresses the
+//! first pass found. Everything that changes the size of a line is decided by
+//! its syntax alone, so both passes agree.
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::assembler::{
+    assemble_line, assemble_statement, AsmError, Context, Kind, Statement, Token,
+};
+use crate::enums::Abi;
+use crate::instruction::parse_category;
+use crate::section::is_little_endian;
+use crate::symbols;
+
+/// Largest power of two `.align` accepts
+const MAX_ALIGN: i64 = 16;
+
+/// Options for `assembleBlock`
+#[napi(object)]
+pub struct AssembleBlockOptions {
+    /// Address of the start of the block (defaults to 0)
+    pub vram: Option<u32>,
+    /// "cpu", "rsp", "r3000gte", "r4000allegrex" or "r5900" (defaults to "cpu")
+    pub category: Option<String>,
+    /// Names accepted for GPRs besides `$4` (defaults to O32)
+    pub abi: Option<Abi>,
+    /// Contents of a symbol file, with lines like `name = 0x80001234;`, for
+    /// the names that are not labels of the block
+    pub symbols: Option<String>,
+    /// Either "big" or "little" (defaults to "big")
+    pub endianness: Option<String>,
+}
+
+/// How a relocation patches its word
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RelocationType {
+    /// The whole word, from `.word`
+    Mips32,
+    /// The target of `j` and `jal`
+    Mips26,
+    /// The immediate of `%hi()` and of the `lui` of `la`
+    MipsHi16,
+    /// The immediate of `%lo()` and of the `addiu` of `la`
+    MipsLo16,
+}
+
+/// A reference to a symbol the block could not resolve
+#[napi(object)]
+pub struct Relocation {
+    /// Offset of the word to patch from the start of the block
+    pub offset: u32,
+    pub kind: RelocationType,
+    pub symbol: String,
+    /// Added to the address of the symbol, like the 8 of `%lo(table + 8)`
+    pub addend: i32,
+}
+
+/// The bytes of an assembled block
+#[napi(object)]
+pub struct AssembledBlock {
+    pub data: Buffer,
+    /// Where the block refers to unknown symbols, whose fields are left as 0
+    pub relocations: Vec<Relocation>,
+}
+
+/// A source line with its labels and `/* */` comments blanked out, so
+/// columns still match the source
+struct Line {
+    /// 1-based
+    number: usize,
+    labels: Vec<String>,
+    code: String,
+}
+
+/// Blanks the comments and labels of each line
+fn split_lines(source: &str) -> Vec<Line> {
+    let mut in_comment = false;
+    let mut lines = Vec::new();
+    for (i, text) in source.lines().enumerate() {
+        let mut code = text.as_bytes().to_vec();
+        let mut pos = 0;
+        while pos < code.len() {
+            let rest = &text[pos..];
+            if in_comment {
+                let Some(end) = rest.find("*/") else {
+                    code[pos..].fill(b' ');
+                    break;
+                };
+                code[pos..pos + end + 2].fill(b' ');
+                in_comment = false;
+                pos += end + 2;
+            } else if rest.starts_with('#') {
+                break;
+            } else if rest.starts_with("/*") {
+                in_comment = true;
+                code[pos..pos + 2].fill(b' ');
+                pos += 2;
+            } else {
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        // Only ASCII was blanked, so this is still UTF-8
+        let mut code = String::from_utf8(code).unwrap_or_default();
+
+        let mut labels = Vec::new();
+        loop {
+            let start = code.len() - code.trim_start().len();
+            let name_len = code[start..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$')))
+                .unwrap_or(code.len() - start);
+            if name_len == 0 || !code[start + name_len..].starts_with(':') {
+                break;
+            }
+            labels.push(code[start..start + name_len].to_string());
+            code.replace_range(start..=start + name_len, &" ".repeat(name_len + 1));
+        }
+        lines.push(Line {
+            number: i + 1,
+            labels,
+            code,
+        });
+    }
+    lines
+}
+
+/// The value of an expression, unless it names an unknown symbol
+enum Value {
+    Known(i64),
+    Unknown(String, i64),
+}
+
+/// Checks for a name that is not a number or a float
+fn is_name(text: &str) -> bool {
+    text != "." && text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '.')
+}
+
+/// An error found on a line
+struct LineError {
+    line: usize,
+    error: AsmError,
+}
+
+impl From<LineError> for napi::Error {
+    fn from(error: LineError) -> Self {
+        napi::Error::new(
+            napi::Status::InvalidArg,
+            format!(
+                "line {}, column {}: {}",
+                error.line, error.error.column, error.error.message
+            ),
+        )
+    }
+}
+
+type Result<T> = std::result::Result<T, AsmError>;
+
+/// A relocation of the instruction being assembled: kind, symbol and addend
+type Pending = (RelocationType, String, i64);
+
+/// One pass over the source
+struct Pass<'a> {
+    context: Context,
+    little_endian: bool,
+    /// Addresses of the labels and of the symbols
+    names: &'a HashMap<String, u32>,
+    data: Vec<u8>,
+    relocations: Vec<Relocation>,
+    /// Labels at the address this pass put them at
+    labels: HashMap<String, u32>,
+    /// Whether branch delay slots are filled with `nop`
+    reorder: bool,
+}
+
+impl Pass<'_> {
+    fn vram(&self) -> u32 {
+        self.context.vram.wrapping_add(self.data.len() as u32)
+    }
+
+    fn align(&mut self, alignment: usize) {
+        let padding = self.data.len().next_multiple_of(alignment) - self.data.len();
+        self.data.extend(std::iter::repeat_n(0, padding));
+    }
+
+    fn push(&mut self, value: u32, size: usize) {
+        self.align(size);
+        let bytes = if self.little_endian {
+            value.to_le_bytes()[..size].to_vec()
+        } else {
+            value.to_be_bytes()[4 - size..].to_vec()
+        };
+        self.data.extend(bytes);
+    }
+
+    fn relocate(&mut self, offset: usize, kind: RelocationType, symbol: String, addend: i64) {
+        self.relocations.push(Relocation {
+            offset: offset as u32,
+            kind,
+            symbol,
+            addend: addend as i32,
+        });
+    }
+
+    /// Reads a number, `name` or `name + 4` at the start of `tokens`, with the
+    /// number of tokens it took
+    fn expression(&self, statement: &Statement, tokens: &[Token]) -> Option<(Value, usize)> {
+        let offset = match tokens.get(1..3).map(|t| (&t[0].kind, &t[1].kind)) {
+            Some((Kind::Punct('+'), Kind::Number(offset))) => Some(*offset),
+            Some((Kind::Punct('-'), Kind::Number(offset))) => Some(-offset),
+            _ => None,
+        };
+        match &tokens.first()?.kind {
+            Kind::Number(number) => Some((Value::Known(*number), 1)),
+            Kind::Word(_) => {
+                let name = statement.text(&tokens[..1]);
+                if !is_name(name) {
+                    return None;
+                }
+                let addend = offset.unwrap_or(0);
+                let value = match self.names.get(name).copied() {
+                    Some(address) => Value::Known(address as i64 + addend),
+                    None => Value::Unknown(name.to_string(), addend),
+                };
+                Some((value, if offset.is_some() { 3 } else { 1 }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Replaces `%hi()`, `%lo()` and known names with their value
+    ///
+    /// Unknown symbols become 0, or the instruction's own address as a jump
+    /// target, and give relocations instead. `%lo()` is sign extended unless
+    /// `unsigned_lo`, for `ori` and the like.
+    fn resolve(
+        &self,
+        statement: &Statement,
+        unsigned_lo: bool,
+    ) -> Result<(Vec<Token>, Vec<Pending>)> {
+        let tokens = &statement.tokens;
+        let is_jump = matches!(statement.mnemonic.as_str(), "j" | "jal");
+        let mut resolved = Vec::new();
+        let mut relocations = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            let modifier = match (&token.kind, tokens.get(i + 1), tokens.get(i + 2)) {
+                (Kind::Punct('%'), Some(name), Some(open)) if open.kind == Kind::Punct('(') => {
+                    match &name.kind {
+                        Kind::Word(word) if word == "hi" => Some(RelocationType::MipsHi16),
+                        Kind::Word(word) if word == "lo" => Some(RelocationType::MipsLo16),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+
+            if let Some(kind) = modifier {
+                let close = tokens[i..]
+                    .iter()
+                    .position(|t| t.kind == Kind::Punct(')'))
+                    .map(|close| i + close)
+                    .ok_or_else(|| AsmError::new(token.column(), "missing ')'"))?;
+                let inner = &tokens[i + 3..close];
+                let value = match self.expression(statement, inner) {
+                    Some((value, len)) if len == inner.len() => value,
+                    _ => {
+                        return Err(AsmError::new(
+                            token.column(),
+                            format!("invalid expression '{}'", statement.text(inner)),
+                        ))
+                    }
+                };
+                let number = match value {
+                    Value::Known(value) if kind == RelocationType::MipsHi16 => {
+                        // Adjusted for the sign extension of %lo
+                        (value as u32).wrapping_add(0x8000) as i64 >> 16
+                    }
+                    Value::Known(value) if unsigned_lo => value as u16 as i64,
+                    Value::Known(value) => value as u16 as i16 as i64,
+                    Value::Unknown(symbol, addend) => {
+                        relocations.push((kind, symbol, addend));
+                        0
+                    }
+                };
+                resolved.push(Token {
+                    kind: Kind::Number(number),
+                    start: token.start,
+                    end: tokens[close].end,
+                });
+                i = close + 1;
+                continue;
+            }
+
+            let (number, len) = match self.expression(statement, &tokens[i..]) {
+                Some((Value::Known(value), len)) if matches!(token.kind, Kind::Word(_)) => {
+                    (value, len)
+                }
+                Some((Value::Unknown(symbol, addend), len))
+                    if is_jump && i == 0 && len == tokens.len() =>
+                {
+                    relocations.push((RelocationType::Mips26, symbol, addend));
+                    (self.vram() as i64, len)
+                }
+                _ => {
+                    resolved.push(token.clone());
+                    i += 1;
+                    continue;
+                }
+            };
+            resolved.push(Token {
+                kind: Kind::Number(number),
+                start: token.start,
+                end: tokens[i + len - 1].end,
+            });
+            i += len;
+        }
+        Ok((resolved, relocations))
+    }
+
+    /// Parses the comma separated items of a data directive
+    fn items(&self, statement: &Statement) -> Result<Vec<(Value, usize)>> {
+        let tokens = &statement.tokens;
+        let mut items = Vec::new();
+        let mut i = 0;
+        loop {
+            let Some(token) = tokens.get(i) else {
+                return Err(AsmError::new(
+                    statement.code.trim_end().len() + 1,
+                    "missing value",
+                ));
+            };
+            let (value, len) = self.expression(statement, &tokens[i..]).ok_or_else(|| {
+                AsmError::new(
+                    token.column(),
+                    format!("unexpected '{}'", statement.text(&tokens[i..=i])),
+                )
+            })?;
+            items.push((value, token.column()));
+            i += len;
+            match tokens.get(i) {
+                None => return Ok(items),
+                Some(Token {
+                    kind: Kind::Punct(','),
+                    ..
+                }) => i += 1,
+                Some(token) => return Err(AsmError::new(token.column(), "expected ','")),
+            }
+        }
+    }
+
+    fn data(&mut self, statement: &Statement, size: usize) -> Result<()> {
+        let bits = size as u32 * 8;
+        for (value, column) in self.items(statement)? {
+            let value = match value {
+                Value::Known(value) if (-(1 << (bits - 1))..(1 << bits)).contains(&value) => {
+                    value as u32
+                }
+                Value::Known(value) => {
+                    return Err(AsmError::new(
+                        column,
+                        format!("value {} is out of range", value),
+                    ))
+                }
+                Value::Unknown(symbol, addend) if size == 4 => {
+                    self.align(4);
+                    self.relocate(self.data.len(), RelocationType::Mips32, symbol, addend);
+                    0
+                }
+                Value::Unknown(symbol, _) => {
+                    return Err(AsmError::new(
+                        column,
+                        format!("undefined symbol '{}'", symbol),
+                    ))
+                }
+            };
+            self.push(value, size);
+        }
+        Ok(())
+    }
+
+    fn directive(&mut self, statement: &Statement) -> Result<()> {
+        let argument = || match statement.tokens.as_slice() {
+            [token] => Some(token),
+            _ => None,
+        };
+        let invalid = || {
+            let column = statement
+                .tokens
+                .first()
+                .map_or(statement.code.trim_end().len() + 1, Token::column);
+            AsmError::new(
+                column,
+                format!(
+                    "invalid argument for {}",
+                    &statement.code[statement.start..statement.mnemonic_end]
+                ),
+            )
+        };
+        match statement.mnemonic.as_str() {
+            ".word" => self.data(statement, 4)?,
+            ".half" => self.data(statement, 2)?,
+            ".byte" => self.data(statement, 1)?,
+            ".align" => match argument().map(|token| &token.kind) {
+                Some(Kind::Number(power)) if (0..=MAX_ALIGN).contains(power) => {
+                    self.align(1 << power)
+                }
+                _ => return Err(invalid()),
+            },
+            ".set" => match argument().map(|token| &token.kind) {
+                Some(Kind::Word(option)) if option == "reorder" => self.reorder = true,
+                Some(Kind::Word(option)) if option == "noreorder" => self.reorder = false,
+                // No expansion uses $at
+                Some(Kind::Word(option)) if option == "at" || option == "noat" => {}
+                _ => return Err(invalid()),
+            },
+            _ => {
+                return Err(AsmError::new(
+                    statement.start + 1,
+                    format!(
+                        "unknown directive '{}'",
+                        &statement.code[statement.start..statement.mnemonic_end]
+                    ),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Assembles `la` and `li` of a symbol as a `lui`/`addiu` pair
+    fn load_address(&mut self, statement: &Statement) -> Result<Vec<u32>> {
+        let tokens = &statement.tokens;
+        let value = match self.expression(statement, &tokens[2..]) {
+            Some((value, len)) if len + 2 == tokens.len() => value,
+            _ => {
+                return Err(AsmError::new(
+                    tokens[2].column(),
+                    format!("invalid value '{}'", statement.text(&tokens[2..])),
+                ))
+            }
+        };
+        let address = match value {
+            Value::Known(address) => address as u32,
+            Value::Unknown(symbol, addend) => {
+                let offset = self.data.len();
+                self.relocate(offset, RelocationType::MipsHi16, symbol.clone(), addend);
+                self.relocate(offset + 4, RelocationType::MipsLo16, symbol, addend);
+                0
+            }
+        };
+        let register = statement.text(&tokens[..1]);
+        let lines = [
+            format!("lui {}, {}", register, address.wrapping_add(0x8000) >> 16),
+            format!("addiu {0}, {0}, {1}", register, address as u16 as i16),
+        ];
+        let mut words = Vec::new();
+        for line in lines {
+            let context = Context {
+                vram: self.vram().wrapping_add(words.len() as u32 * 4),
+                ..self.context
+            };
+            // Only the register can be wrong
+            let assembled = assemble_line(&line, &context)
+                .map_err(|error| AsmError::new(tokens[0].column(), error.message))?;
+            words.extend(assembled);
+        }
+        Ok(words)
+    }
+
+    fn instruction(&mut self, statement: &Statement) -> Result<()> {
+        self.align(4);
+        let context = Context {
+            vram: self.vram(),
+            ..self.context
+        };
+        let is_symbolic_load = matches!(statement.mnemonic.as_str(), "la" | "li")
+            && matches!(
+                statement.tokens.as_slice(),
+                [Token { kind: Kind::Register(_), .. }, Token { kind: Kind::Punct(','), .. }, value, ..]
+                    if matches!(value.kind, Kind::Word(_))
+            );
+        let words = if is_symbolic_load {
+            self.load_address(statement)?
+        } else {
+            let assemble = |unsigned_lo| {
+                let (tokens, relocations) = self.resolve(statement, unsigned_lo)?;
+                let resolved = Statement {
+                    tokens,
+                    mnemonic: statement.mnemonic.clone(),
+                    ..*statement
+                };
+                Ok((assemble_statement(&resolved, &context)?, relocations))
+            };
+            let (mut words, relocations) =
+                assemble(false).or_else(|error: AsmError| assemble(true).map_err(|_| error))?;
+            for (kind, symbol, addend) in relocations {
+                if kind == RelocationType::Mips26 {
+                    words[0] &= !0x03FF_FFFF;
+                }
+                self.relocate(self.data.len(), kind, symbol, addend);
+            }
+            words
+        };
+
+        for &word in &words {
+            self.push(word, 4);
+        }
+        let last = words.last().map(|&word| {
+            RustInstruction::new(word, context.vram, context.category).has_delay_slot()
+        });
+        if self.reorder && last == Some(true) {
+            self.push(0, 4);
+        }
+        Ok(())
+    }
+
+    fn line(&mut self, line: &Line) -> Result<()> {
+        for label in &line.labels {
+            let vram = self.vram();
+            self.labels.insert(label.clone(), vram);
+        }
+        let code = &line.code[..line.code.find('#').unwrap_or(line.code.len())];
+        if code.trim().is_empty() {
+            return Ok(());
+        }
+        let statement = Statement::parse(code, self.context.abi)?;
+        if statement.mnemonic.starts_with('.') {
+            self.directive(&statement)
+        } else {
+            self.instruction(&statement)
+        }
+    }
+
+    fn run<'a>(
+        lines: &[Line],
+        names: &'a HashMap<String, u32>,
+        context: Context,
+        little_endian: bool,
+    ) -> std::result::Result<Pass<'a>, LineError> {
+        let mut pass = Pass {
+            context,
+            little_endian,
+            names,
+            data: Vec::new(),
+            relocations: Vec::new(),
+            labels: HashMap::new(),
+            reorder: true,
+        };
+        for line in lines {
+            pass.line(line).map_err(|error| LineError {
+                line: line.number,
+                error,
+            })?;
+        }
+        Ok(pass)
+    }
+}
+
+/// Assembles a block of MIPS assembly into bytes
+///
+/// Takes what `assemble` does, one instruction per line, plus `label:`
+/// definitions, `/* */` comments and the directives `.word`, `.half`,
+/// `.byte`, `.align` and `.set reorder`/`noreorder`. Names are looked up in
+/// the labels of the block and then in `symbols`, and may be used as
+/// branch and jump targets, in `.word`, with `la` and in `%hi()`/`%lo()`.
+/// Like GNU as the block starts in `reorder` mode, where a `nop` is added
+/// after each instruction with a delay slot. Errors start with the 1-based
+/// line and column they were found at.
+///
+/// # Arguments
+/// * `source` - The assembly source
+/// * `options` - The vram, category, ABI, symbols and endianness to assemble for (optional)
+#[napi]
+pub fn assemble_block(
+    source: String,
+    options: Option<AssembleBlockOptions>,
+) -> napi::Result<AssembledBlock> {
+    let options = options.unwrap_or(AssembleBlockOptions {
+        vram: None,
+        category: None,
+        abi: None,
+        symbols: None,
+        endianness: None,
+    });
+    let context = Context {
+        vram: options.vram.unwrap_or(0),
+        category: parse_category(options.category.as_deref()),
+        abi: options.abi.unwrap_or(Abi::O32),
+        // Names are looked up instead, and never read as addresses
+        address_labels: false,
+    };
+    let little_endian = is_little_endian(options.endianness.as_deref())?;
+
+    let lines = split_lines(&source);
+    let mut names: HashMap<String, u32> = match &options.symbols {
+        Some(text) => symbols::parse(text)?
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.address))
+            .collect(),
+        None => HashMap::new(),
+    };
+    let mut defined = HashMap::new();
+    for line in &lines {
+        for label in &line.labels {
+            if defined.insert(label.clone(), line.number).is_some() {
+                return Err(napi::Error::new(
+                    napi::Status::InvalidArg,
+                    format!("line {}: label '{}' is already defined", line.number, label),
+                ));
+            }
+            names.insert(label.clone(), context.vram);
+        }
+    }
+
+    let layout = Pass::run(&lines, &names, context, little_endian)?;
+    names.extend(layout.labels);
+    let pass = Pass::run(&lines, &names, context, little_endian)?;
+    Ok(AssembledBlock {
+        data: pass.data.into(),
+        relocations: pass.relocations,
+    })
+}
@@ -0,0 +1,156 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeSet;
+
+use napi_derive::napi;
+
+use crate::fields;
+use crate::section::CodeSection;
+use crate::traversal::{self, Traversal};
+
+/// A single call instruction found in a CodeSection
+#[napi(object)]
+#[derive(Clone)]
+pub struct CallSite {
+    /// Entry point of the function containing the call
+    pub caller: u32,
+    /// Address of the call instruction
+    pub vram: u32,
+    /// Called address, missing for indirect calls that could not be resolved
+    pub target: Option<u32>,
+    /// Whether the call goes through a register (`jalr`)
+    pub indirect: bool,
+}
+
+/// Direct and indirect calls between the functions of a CodeSection
+#[napi]
+pub struct CallGraph {
+    functions: BTreeSet<u32>,
+    calls: Vec<CallSite>,
+}
+
+impl CallGraph {
+    pub(crate) fn build(section: &CodeSection, traversal: &Traversal) -> Self {
+        let mut calls = Vec::new();
+
+        traversal.walk_functions(section, |caller, instr, tracker| {
+            if !instr.is_function_call() {
+                return;
+            }
+            if traversal::is_direct_call(instr) {
+                calls.push(CallSite {
+                    caller,
+                    vram: instr.vram,
+                    target: traversal::branch_target(instr),
+                    indirect: false,
+                });
+            } else if instr.is_jump() {
+                calls.push(CallSite {
+                    caller,
+                    vram: instr.vram,
+                    target: tracker.get(fields::rs(instr)),
+                    indirect: true,
+                });
+            }
+        });
+
+        CallGraph {
+            functions: traversal.functions.clone(),
+            calls,
+        }
+    }
+
+    fn node_name(vram: u32) -> String {
+        format!("func_{:08X}", vram)
+    }
+}
+
+#[napi]
+impl CallGraph {
+    /// Gets the entry points of every known function, sorted
+    #[napi]
+    pub fn functions(&self) -> Vec<u32> {
+        self.functions.iter().copied().collect()
+    }
+
+    /// Gets every call site, in address order
+    #[napi]
+    pub fn calls(&self) -> Vec<CallSite> {
+        self.calls.clone()
+    }
+
+    /// Gets the call sites that call the function at `vram`
+    #[napi]
+    pub fn callers(&self, vram: u32) -> Vec<CallSite> {
+        self.calls
+            .iter()
+            .filter(|call| call.target == Some(vram))
+            .cloned()
+            .collect()
+    }
+
+    /// Gets the call sites inside the function at `vram`
+    #[napi]
+    pub fn callees(&self, vram: u32) -> Vec<CallSite> {
+        self.calls
+            .iter()
+            .filter(|call| call.caller == vram)
+            .cloned()
+            .collect()
+    }
+
+    /// Exports the graph in Graphviz DOT format
+    ///
+    /// Unresolved indirect calls point to a single `indirect` node.
+    #[napi]
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph calls {\n");
+        for &function in &self.functions {
+            out += &format!("    {};\n", Self::node_name(function));
+        }
+
+        let mut edges = BTreeSet::new();
+        for call in &self.calls {
+            let target = match call.target {
+                Some(target) => Self::node_name(target),
+                None => "indirect".to_string(),
+            };
+            edges.insert((Self::node_name(call.caller), target, call.indirect));
+        }
+        for (caller, target, indirect) in edges {
+            let style = if indirect { " [style=dashed]" } else { "" };
+            out += &format!("    {} -> {}{};\n", caller, target, style);
+        }
+
+        out += "}\n";
+        out
+    }
+
+    /// Exports the graph as a JSON string of the form
+    /// `{ "functions": [...], "calls": [{ caller, vram, target, indirect }] }`
+    #[napi]
+    pub fn to_json(&self) -> String {
+        let functions: Vec<String> = self.functions.iter().map(|f| f.to_string()).collect();
+        let calls: Vec<String> = self
+            .calls
+            .iter()
+            .map(|call| {
+                let target = match call.target {
+                    Some(target) => target.to_string(),
+                    None => "null".to_string(),
+                };
+                format!(
+                    "{{\"caller\":{},\"vram\":{},\"target\":{},\"indirect\":{}}}",
+                    call.caller, call.vram, target, call.indirect
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"functions\":[{}],\"calls\":[{}]}}",
+            functions.join(","),
+            calls.join(",")
+        )
+    }
+}
@@ -0,0 +1,180 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeSet;
+
+use napi_derive::napi;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::traversal;
+
+/// A basic block of a Function
+#[napi(object)]
+pub struct BasicBlock {
+    /// Address of the first instruction
+    pub start: u32,
+    /// Address right after the last instruction
+    pub end: u32,
+    /// Start addresses of the blocks control can flow to
+    pub successors: Vec<u32>,
+    /// Start addresses of the blocks control can flow from
+    pub predecessors: Vec<u32>,
+}
+
+/// A basic block as instruction indices into its function
+pub(crate) struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub successors: Vec<usize>,
+    pub predecessors: Vec<usize>,
+}
+
+/// Control flow graph of a single function
+///
+/// Delay slots belong to the block of their branch. The delay slot of a
+/// branch-likely instruction is only executed when the branch is taken, so it
+/// gets a block of its own on the taken edge.
+pub(crate) struct Cfg {
+    pub blocks: Vec<Block>,
+}
+
+impl Cfg {
+    pub(crate) fn build(instrs: &[RustInstruction]) -> Self {
+        let count = instrs.len();
+        let vram = instrs.first().map(|instr| instr.vram).unwrap_or(0);
+        let index_of = |target: u32| -> Option<usize> {
+            let offset = target.wrapping_sub(vram);
+            let index = (offset / 4) as usize;
+            (offset.is_multiple_of(4) && index < count).then_some(index)
+        };
+
+        let mut leaders = BTreeSet::new();
+        if count > 0 {
+            leaders.insert(0);
+        }
+        for (i, instr) in instrs.iter().enumerate() {
+            if instr.is_function_call() {
+                continue;
+            }
+            let is_control_flow = traversal::branch_target(instr).is_some() || instr.is_jump();
+            if !is_control_flow {
+                continue;
+            }
+            if let Some(target) = traversal::branch_target(instr).and_then(index_of) {
+                leaders.insert(target);
+            }
+            if instr.is_branch_likely() {
+                leaders.insert(i + 1);
+            }
+            let after = if instr.has_delay_slot() { i + 2 } else { i + 1 };
+            leaders.insert(after);
+        }
+        leaders.retain(|&leader| leader < count);
+
+        let starts: Vec<usize> = leaders.into_iter().collect();
+        let block_of = |index: usize| starts.partition_point(|&start| start <= index) - 1;
+
+        let mut blocks: Vec<Block> = starts
+            .iter()
+            .enumerate()
+            .map(|(b, &start)| Block {
+                start,
+                end: starts.get(b + 1).copied().unwrap_or(count),
+                successors: Vec::new(),
+                predecessors: Vec::new(),
+            })
+            .collect();
+
+        for block in blocks.iter_mut() {
+            let (start, end) = (block.start, block.end);
+            let mut successors = Vec::new();
+
+            // The control flow instruction is either the last one or the one
+            // before its delay slot
+            let branch = (start..end).rev().take(2).find(|&i| {
+                let instr = &instrs[i];
+                !instr.is_function_call()
+                    && (traversal::branch_target(instr).is_some() || instr.is_jump())
+                    && (i + 1 == end || (i + 2 == end && instr.has_delay_slot()))
+            });
+
+            match branch {
+                Some(i) => {
+                    let instr = &instrs[i];
+                    let target = traversal::branch_target(instr).and_then(index_of);
+                    if instr.is_branch_likely() && i + 1 < count {
+                        // Taken edge goes through the delay slot block
+                        successors.push(block_of(i + 1));
+                    } else if let Some(target) = target {
+                        successors.push(block_of(target));
+                    }
+                    if !traversal::ends_flow(instr) {
+                        let after = if instr.has_delay_slot() { i + 2 } else { i + 1 };
+                        if after < count {
+                            successors.push(block_of(after));
+                        }
+                    }
+                }
+                None => {
+                    let previous = start.checked_sub(1).map(|i| &instrs[i]);
+                    let is_likely_slot = previous.is_some_and(|instr| instr.is_branch_likely());
+                    if is_likely_slot {
+                        let branch = &instrs[start - 1];
+                        if let Some(target) = traversal::branch_target(branch).and_then(index_of) {
+                            successors.push(block_of(target));
+                        }
+                    } else if end < count {
+                        successors.push(block_of(end));
+                    }
+                }
+            }
+
+            successors.dedup();
+            block.successors = successors;
+        }
+
+        for b in 0..blocks.len() {
+            for s in blocks[b].successors.clone() {
+                if !blocks[s].predecessors.contains(&b) {
+                    blocks[s].predecessors.push(b);
+                }
+            }
+        }
+
+        Cfg { blocks }
+    }
+
+    /// Returns the block containing the instruction at `index`
+    pub(crate) fn block_of(&self, index: usize) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|block| block.start <= index && index < block.end)
+    }
+
+    /// Returns the block starting at the instruction at `index`
+    pub(crate) fn block_starting_at(&self, index: usize) -> Option<usize> {
+        self.blocks.iter().position(|block| block.start == index)
+    }
+
+    pub(crate) fn to_napi(&self, instrs: &[RustInstruction]) -> Vec<BasicBlock> {
+        let vram_of = |index: usize| instrs[0].vram.wrapping_add(index as u32 * 4);
+
+        self.blocks
+            .iter()
+            .map(|block| BasicBlock {
+                start: vram_of(block.start),
+                end: vram_of(block.end),
+                successors: block
+                    .successors
+                    .iter()
+                    .map(|&s| vram_of(self.blocks[s].start))
+                    .collect(),
+                predecessors: block
+                    .predecessors
+                    .iter()
+                    .map(|&p| vram_of(self.blocks[p].start))
+                    .collect(),
+            })
+            .collect()
+    }
+}
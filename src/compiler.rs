@@ -0,0 +1,244 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use napi_derive::napi;
+use rabbitizer::InstrCategory;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::enums::Abi;
+use crate::fields;
+use crate::registers;
+use crate::stack;
+
+/// Compilers the classifier can tell apart
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Compiler {
+    /// IDO 5.3 or 7.1, which generate the same patterns
+    IDO,
+    /// KMC GCC 2.7.2
+    KMC,
+    /// SN Systems' GCC for the N64
+    SN64,
+    /// EGCS 1.1
+    EGCS,
+    /// PSY-Q GCC for the PlayStation
+    PSYQ,
+}
+
+const COMPILERS: [Compiler; 5] = [
+    Compiler::IDO,
+    Compiler::KMC,
+    Compiler::SN64,
+    Compiler::EGCS,
+    Compiler::PSYQ,
+];
+
+/// One entry of a ranked compiler guess
+#[napi(object)]
+pub struct CompilerGuess {
+    pub compiler: Compiler,
+    /// Between 0 and 1, the confidences of a guess add up to 1
+    pub confidence: f64,
+    /// The signals that counted in favour of this compiler
+    pub reasons: Vec<String>,
+}
+
+const IDO: &[Compiler] = &[Compiler::IDO];
+const GCC: &[Compiler] = &[
+    Compiler::KMC,
+    Compiler::SN64,
+    Compiler::EGCS,
+    Compiler::PSYQ,
+];
+const N64_GCC: &[Compiler] = &[Compiler::KMC, Compiler::SN64, Compiler::EGCS];
+
+struct Scores {
+    scores: [f64; 5],
+    reasons: [Vec<String>; 5],
+}
+
+impl Scores {
+    fn add(&mut self, compilers: &[Compiler], weight: f64, reason: &str) {
+        for &compiler in compilers {
+            let i = compiler as usize;
+            self.scores[i] += weight;
+            if weight > 0.0 {
+                self.reasons[i].push(reason.to_string());
+            }
+        }
+    }
+}
+
+fn is_div(instr: &RustInstruction) -> bool {
+    matches!(
+        instr.unique_id,
+        InstrId::cpu_div
+            | InstrId::cpu_divu
+            | InstrId::cpu_sn64_div
+            | InstrId::cpu_sn64_divu
+            | InstrId::cpu_ddiv
+            | InstrId::cpu_ddivu
+    )
+}
+
+/// Checks for the `bnez $divisor` guarding a division against zero
+fn is_zero_check(instr: &RustInstruction, divisor: u32) -> bool {
+    matches!(instr.unique_id, InstrId::cpu_bnez | InstrId::cpu_bne)
+        && fields::rt(instr) == 0
+        && fields::rs(instr) == divisor
+}
+
+fn is_64bit_gpr_op(instr: &RustInstruction) -> bool {
+    let name = instr.opcode_name();
+    instr.category == InstrCategory::CPU
+        && (name.starts_with("dadd")
+            || name.starts_with("dsub")
+            || name.starts_with("dsll")
+            || name.starts_with("dsrl")
+            || name.starts_with("dsra")
+            || name.starts_with("dmult")
+            || name.starts_with("ddiv")
+            || name == "ld"
+            || name == "sd")
+}
+
+/// Ranks the compilers that may have produced a function
+///
+/// The classifier weighs a handful of code generation habits:
+/// * how divisions are checked: IDO emits `div` and then `bnez`/`break 7`,
+///   while GNU as puts the `div` in the delay slot of the `bnez`, and SN64's
+///   assembler encodes the `break` code in the low bits of the field
+/// * branch likely and 64-bit GPR instructions, which MIPS I (PSY-Q) does not
+///   have and which IDO does not emit for `-mips2`
+/// * `nop` in load delay slots, only needed on the R3000
+/// * the temporaries picked first: IDO starts at `$t6`, GCC at `$v0`/`$t0`
+/// * whether the prologue saves registers in one block or interleaves them
+///   with the function body, and whether the `jr $ra` delay slot is filled
+///
+/// Scores are turned into confidences with a softmax, so a function without
+/// any telling instruction gets the same confidence for every compiler.
+pub(crate) fn classify(instrs: &[RustInstruction], abi: Abi) -> Vec<CompilerGuess> {
+    let mut scores = Scores {
+        scores: [0.0; 5],
+        reasons: Default::default(),
+    };
+
+    let mut gas_div = false;
+    let mut ido_div = false;
+    let mut sn64_break = false;
+    let mut branch_likely = false;
+    let mut wide = false;
+    let mut load_delay_nop = false;
+    let mut gte = false;
+
+    for (i, instr) in instrs.iter().enumerate() {
+        if is_div(instr) {
+            let divisor = fields::rt(instr);
+            if i > 0 && is_zero_check(&instrs[i - 1], divisor) {
+                gas_div = true;
+            } else if instrs
+                .get(i + 1)
+                .is_some_and(|next| is_zero_check(next, divisor))
+            {
+                ido_div = true;
+            }
+        }
+        if instr.unique_id == InstrId::cpu_break {
+            let code = (instr.raw() >> 6) & 0xFFFFF;
+            if code != 0 && code & 0xFFC00 == 0 {
+                sn64_break = true;
+            }
+        }
+        branch_likely |= instr.is_branch_likely();
+        wide |= is_64bit_gpr_op(instr);
+        gte |= instr.category == InstrCategory::R3000GTE || instr.raw() >> 26 == 0x12;
+
+        // A `nop` right after a load, which the next instruction needs
+        if instr.does_load() && i + 2 < instrs.len() && instrs[i + 1].is_nop() {
+            let in_delay_slot = i > 0 && instrs[i - 1].has_delay_slot();
+            let loaded = registers::defs(instr);
+            if !in_delay_slot && registers::uses(&instrs[i + 2]) & loaded != 0 {
+                load_delay_nop = true;
+            }
+        }
+    }
+
+    if gas_div {
+        scores.add(GCC, 2.0, "div in the delay slot of its zero check");
+    }
+    if ido_div {
+        scores.add(IDO, 2.0, "zero check after the div");
+    }
+    if sn64_break {
+        scores.add(&[Compiler::SN64], 3.0, "break code in the low bits");
+    }
+    if branch_likely {
+        scores.add(N64_GCC, 1.0, "branch likely");
+        scores.add(&[Compiler::PSYQ], -5.0, "");
+    }
+    if wide {
+        scores.add(N64_GCC, 1.0, "64-bit GPR instructions");
+        scores.add(IDO, -2.0, "");
+        scores.add(&[Compiler::PSYQ], -5.0, "");
+    }
+    if load_delay_nop {
+        scores.add(&[Compiler::PSYQ], 3.0, "nop in a load delay slot");
+    }
+    if gte {
+        scores.add(&[Compiler::PSYQ], 5.0, "GTE instructions");
+    }
+
+    // The first temporary the register allocator reached for
+    let first_temporary = instrs.iter().find_map(|instr| {
+        registers::iter(registers::defs(instr)).find(|reg| matches!(reg, 8..=15 | 24 | 25))
+    });
+    match first_temporary {
+        Some(14 | 15 | 24 | 25) => scores.add(IDO, 2.0, "temporaries start at $t6"),
+        Some(_) => scores.add(GCC, 1.0, "temporaries start at $t0"),
+        None => {}
+    }
+
+    let frame = stack::analyze(instrs, abi);
+    if let Some(allocation) = frame.allocation {
+        if frame.saved_gprs.len() >= 2 {
+            let contiguous = frame
+                .saved_gprs
+                .iter()
+                .enumerate()
+                .all(|(k, slot)| slot.vram == allocation + 4 * (k as u32 + 1));
+            if contiguous {
+                scores.add(GCC, 1.0, "registers saved in one block");
+            } else {
+                scores.add(IDO, 1.0, "register saves scheduled into the body");
+            }
+        }
+
+        let return_slot = instrs
+            .iter()
+            .position(|instr| instr.is_return())
+            .and_then(|i| instrs.get(i + 1));
+        if return_slot.is_some_and(|slot| slot.is_nop()) {
+            scores.add(GCC, 1.0, "unfilled jr $ra delay slot");
+            scores.add(IDO, -1.0, "");
+        }
+    }
+
+    let max = scores.scores.iter().cloned().fold(f64::MIN, f64::max);
+    let weights: Vec<f64> = scores.scores.iter().map(|s| (s - max).exp()).collect();
+    let total: f64 = weights.iter().sum();
+
+    let mut guesses: Vec<CompilerGuess> = COMPILERS
+        .iter()
+        .zip(scores.reasons)
+        .map(|(&compiler, reasons)| CompilerGuess {
+            compiler,
+            confidence: weights[compiler as usize] / total,
+            reasons,
+        })
+        .collect();
+    // Stable, so ties keep the order of `Compiler`
+    guesses.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    guesses
+}
@@ -0,0 +1,439 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use napi::bindgen_prelude::BigInt;
+use napi_derive::napi;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::cfg::Cfg;
+use crate::enums::Abi;
+use crate::fields;
+use crate::registers;
+use crate::stack::SP;
+
+/// A known register value
+#[napi(object)]
+pub struct RegisterValue {
+    /// The constant, sign-extended to 64 bits, for constants
+    pub value: Option<BigInt>,
+    /// The low 32 bits of the constant, for constants
+    pub word: Option<u32>,
+    /// Offset from `$sp` on function entry, for stack addresses
+    pub stack_offset: Option<i32>,
+}
+
+/// Abstract value of a GPR or stack slot
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Value {
+    Unknown,
+    Const(u64),
+    /// `$sp` on function entry plus an offset
+    Stack(i64),
+    /// The value a GPR held on function entry plus an offset
+    Entry(u32, i64),
+}
+
+impl Value {
+    pub(crate) fn to_napi(self) -> Option<RegisterValue> {
+        match self {
+            Value::Unknown | Value::Entry(..) => None,
+            Value::Const(value) => Some(RegisterValue {
+                value: Some(BigInt::from(value as i64)),
+                word: Some(value as u32),
+                stack_offset: None,
+            }),
+            Value::Stack(offset) => Some(RegisterValue {
+                value: None,
+                word: None,
+                stack_offset: Some(offset as i32),
+            }),
+        }
+    }
+}
+
+fn sign_extend_32(value: u64) -> u64 {
+    value as u32 as i32 as i64 as u64
+}
+
+fn sign_extend_16(imm: u32) -> u64 {
+    imm as u16 as i16 as i64 as u64
+}
+
+/// Adds two values, keeping track of stack addresses
+fn add(a: Value, b: Value, is_32bit: bool) -> Value {
+    let wrap = |value: u64| {
+        if is_32bit {
+            sign_extend_32(value)
+        } else {
+            value
+        }
+    };
+    match (a, b) {
+        (Value::Const(a), Value::Const(b)) => Value::Const(wrap(a.wrapping_add(b))),
+        (Value::Stack(offset), Value::Const(c)) | (Value::Const(c), Value::Stack(offset)) => {
+            Value::Stack(offset.wrapping_add(c as i64))
+        }
+        (Value::Entry(reg, offset), Value::Const(c))
+        | (Value::Const(c), Value::Entry(reg, offset)) => {
+            Value::Entry(reg, offset.wrapping_add(c as i64))
+        }
+        _ => Value::Unknown,
+    }
+}
+
+fn sub(a: Value, b: Value, is_32bit: bool) -> Value {
+    match (a, b) {
+        (Value::Const(a), Value::Const(b)) => {
+            let value = a.wrapping_sub(b);
+            Value::Const(if is_32bit {
+                sign_extend_32(value)
+            } else {
+                value
+            })
+        }
+        (Value::Stack(offset), Value::Const(c)) => Value::Stack(offset.wrapping_sub(c as i64)),
+        (Value::Entry(reg, offset), Value::Const(c)) => {
+            Value::Entry(reg, offset.wrapping_sub(c as i64))
+        }
+        (Value::Stack(a), Value::Stack(b)) => Value::Const(a.wrapping_sub(b) as u64),
+        (Value::Entry(a, x), Value::Entry(b, y)) if a == b => {
+            Value::Const(x.wrapping_sub(y) as u64)
+        }
+        _ => Value::Unknown,
+    }
+}
+
+/// Applies an operation on constants, anything else is unknown
+fn map2(a: Value, b: Value, op: impl Fn(u64, u64) -> u64) -> Value {
+    match (a, b) {
+        (Value::Const(a), Value::Const(b)) => Value::Const(op(a, b)),
+        _ => Value::Unknown,
+    }
+}
+
+/// Known GPR values and stack slot contents at a program point
+#[derive(Clone, PartialEq)]
+pub(crate) struct State {
+    regs: [Value; 32],
+    /// Values stored to the stack, keyed by offset from `$sp` on entry, with
+    /// the size of the store
+    slots: BTreeMap<i64, (Value, u32)>,
+}
+
+impl State {
+    pub(crate) fn entry() -> Self {
+        let mut regs: [Value; 32] = std::array::from_fn(|reg| Value::Entry(reg as u32, 0));
+        regs[0] = Value::Const(0);
+        regs[SP as usize] = Value::Stack(0);
+        State {
+            regs,
+            slots: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn get(&self, reg: u32) -> Value {
+        self.regs[reg as usize]
+    }
+
+    fn set(&mut self, reg: u32, value: Value) {
+        if reg != 0 {
+            self.regs[reg as usize] = value;
+        }
+    }
+
+    /// Keeps only what both states agree on
+    fn meet(&mut self, other: &State) {
+        for (reg, value) in self.regs.iter_mut().enumerate() {
+            if *value != other.regs[reg] {
+                *value = Value::Unknown;
+            }
+        }
+        self.slots
+            .retain(|offset, slot| other.slots.get(offset) == Some(slot));
+    }
+
+    pub(crate) fn clobber_call(&mut self, abi: Abi) {
+        let clobbers = registers::call_clobbers(abi);
+        for reg in 1..32 {
+            if registers::contains(clobbers, reg as usize) {
+                self.regs[reg as usize] = Value::Unknown;
+            }
+        }
+    }
+
+    fn store(&mut self, instr: &RustInstruction) {
+        let Value::Stack(base) = self.get(fields::rs(instr)) else {
+            return;
+        };
+        let offset = base.wrapping_add(instr.processed_immediate() as i64);
+        let size = fields::access_size(instr.access_type());
+        let end = offset + size as i64;
+        self.slots
+            .retain(|&slot, &mut (_, slot_size)| slot + slot_size as i64 <= offset || end <= slot);
+
+        let is_gpr_store = matches!(
+            instr.unique_id,
+            InstrId::cpu_sw | InstrId::cpu_sd | InstrId::rsp_sw
+        );
+        if is_gpr_store {
+            self.slots
+                .insert(offset, (self.get(fields::rt(instr)), size));
+        }
+    }
+
+    fn load(&self, instr: &RustInstruction) -> Value {
+        let Value::Stack(base) = self.get(fields::rs(instr)) else {
+            return Value::Unknown;
+        };
+        let offset = base.wrapping_add(instr.processed_immediate() as i64);
+        match (instr.unique_id, self.slots.get(&offset)) {
+            (InstrId::cpu_lw | InstrId::rsp_lw, Some(&(Value::Const(value), 4))) => {
+                Value::Const(sign_extend_32(value))
+            }
+            (InstrId::cpu_lw | InstrId::rsp_lw, Some(&(value, 4)))
+            | (InstrId::cpu_ld, Some(&(value, 8))) => value,
+            _ => Value::Unknown,
+        }
+    }
+
+    /// Applies the register effects of `instr`, except for call clobbers
+    pub(crate) fn step(&mut self, instr: &RustInstruction) {
+        let rs = self.get(fields::rs(instr));
+        let rt = self.get(fields::rt(instr));
+        let imm = fields::immediate(instr);
+        let simm = Value::Const(sign_extend_16(imm));
+        let uimm = Value::Const(imm as u64);
+        let sa = fields::sa(instr);
+
+        let shift = |value: Value, op: &dyn Fn(u64) -> u64| match value {
+            Value::Const(value) => Value::Const(op(value)),
+            _ => Value::Unknown,
+        };
+        let variable_shift = |mask: u64, op: &dyn Fn(u64, u32) -> u64| match (rt, rs) {
+            (Value::Const(value), Value::Const(amount)) => {
+                Value::Const(op(value, (amount & mask) as u32))
+            }
+            _ => Value::Unknown,
+        };
+
+        let (dest, value) = match instr.unique_id {
+            InstrId::cpu_lui | InstrId::rsp_lui => (
+                fields::rt(instr),
+                Value::Const(sign_extend_32((imm as u64) << 16)),
+            ),
+            InstrId::cpu_addiu | InstrId::cpu_addi | InstrId::rsp_addiu | InstrId::rsp_addi => {
+                (fields::rt(instr), add(rs, simm, true))
+            }
+            InstrId::cpu_daddiu | InstrId::cpu_daddi => (fields::rt(instr), add(rs, simm, false)),
+            InstrId::cpu_ori | InstrId::rsp_ori => {
+                (fields::rt(instr), map2(rs, uimm, |a, b| a | b))
+            }
+            InstrId::cpu_andi | InstrId::rsp_andi => {
+                (fields::rt(instr), map2(rs, uimm, |a, b| a & b))
+            }
+            InstrId::cpu_xori | InstrId::rsp_xori => {
+                (fields::rt(instr), map2(rs, uimm, |a, b| a ^ b))
+            }
+            InstrId::cpu_slti | InstrId::rsp_slti => (
+                fields::rt(instr),
+                map2(rs, simm, |a, b| ((a as i64) < (b as i64)) as u64),
+            ),
+            InstrId::cpu_sltiu | InstrId::rsp_sltiu => {
+                (fields::rt(instr), map2(rs, simm, |a, b| (a < b) as u64))
+            }
+
+            InstrId::cpu_addu | InstrId::cpu_add | InstrId::rsp_addu | InstrId::rsp_add => {
+                (fields::rd(instr), add(rs, rt, true))
+            }
+            InstrId::cpu_daddu | InstrId::cpu_dadd => (fields::rd(instr), add(rs, rt, false)),
+            InstrId::cpu_subu | InstrId::cpu_sub | InstrId::rsp_subu | InstrId::rsp_sub => {
+                (fields::rd(instr), sub(rs, rt, true))
+            }
+            InstrId::cpu_dsubu | InstrId::cpu_dsub => (fields::rd(instr), sub(rs, rt, false)),
+            InstrId::cpu_negu | InstrId::rsp_negu => {
+                (fields::rd(instr), sub(Value::Const(0), rt, true))
+            }
+            InstrId::cpu_move | InstrId::rsp_move => (fields::rd(instr), rs),
+            InstrId::cpu_or | InstrId::rsp_or => (fields::rd(instr), map2(rs, rt, |a, b| a | b)),
+            InstrId::cpu_and | InstrId::rsp_and => (fields::rd(instr), map2(rs, rt, |a, b| a & b)),
+            InstrId::cpu_xor | InstrId::rsp_xor => (fields::rd(instr), map2(rs, rt, |a, b| a ^ b)),
+            InstrId::cpu_nor | InstrId::rsp_nor => {
+                (fields::rd(instr), map2(rs, rt, |a, b| !(a | b)))
+            }
+            InstrId::cpu_not | InstrId::rsp_not => (fields::rd(instr), map2(rs, rs, |a, _| !a)),
+            InstrId::cpu_slt | InstrId::rsp_slt => (
+                fields::rd(instr),
+                map2(rs, rt, |a, b| ((a as i64) < (b as i64)) as u64),
+            ),
+            InstrId::cpu_sltu | InstrId::rsp_sltu => {
+                (fields::rd(instr), map2(rs, rt, |a, b| (a < b) as u64))
+            }
+
+            InstrId::cpu_sll | InstrId::rsp_sll => (
+                fields::rd(instr),
+                shift(rt, &|v| sign_extend_32(((v as u32) << sa) as u64)),
+            ),
+            InstrId::cpu_srl | InstrId::rsp_srl => (
+                fields::rd(instr),
+                shift(rt, &|v| sign_extend_32(((v as u32) >> sa) as u64)),
+            ),
+            InstrId::cpu_sra | InstrId::rsp_sra => (
+                fields::rd(instr),
+                shift(rt, &|v| {
+                    sign_extend_32(((v as u32 as i32) >> sa) as u32 as u64)
+                }),
+            ),
+            InstrId::cpu_sllv | InstrId::rsp_sllv => (
+                fields::rd(instr),
+                variable_shift(31, &|v, n| sign_extend_32(((v as u32) << n) as u64)),
+            ),
+            InstrId::cpu_srlv | InstrId::rsp_srlv => (
+                fields::rd(instr),
+                variable_shift(31, &|v, n| sign_extend_32(((v as u32) >> n) as u64)),
+            ),
+            InstrId::cpu_srav | InstrId::rsp_srav => (
+                fields::rd(instr),
+                variable_shift(31, &|v, n| {
+                    sign_extend_32(((v as u32 as i32) >> n) as u32 as u64)
+                }),
+            ),
+            InstrId::cpu_dsll => (fields::rd(instr), shift(rt, &|v| v << sa)),
+            InstrId::cpu_dsrl => (fields::rd(instr), shift(rt, &|v| v >> sa)),
+            InstrId::cpu_dsra => (fields::rd(instr), shift(rt, &|v| ((v as i64) >> sa) as u64)),
+            InstrId::cpu_dsll32 => (fields::rd(instr), shift(rt, &|v| v << (sa + 32))),
+            InstrId::cpu_dsrl32 => (fields::rd(instr), shift(rt, &|v| v >> (sa + 32))),
+            InstrId::cpu_dsra32 => (
+                fields::rd(instr),
+                shift(rt, &|v| ((v as i64) >> (sa + 32)) as u64),
+            ),
+            InstrId::cpu_dsllv => (fields::rd(instr), variable_shift(63, &|v, n| v << n)),
+            InstrId::cpu_dsrlv => (fields::rd(instr), variable_shift(63, &|v, n| v >> n)),
+            InstrId::cpu_dsrav => (
+                fields::rd(instr),
+                variable_shift(63, &|v, n| ((v as i64) >> n) as u64),
+            ),
+
+            _ => {
+                if instr.does_store() {
+                    self.store(instr);
+                }
+                let value = if instr.does_load() {
+                    self.load(instr)
+                } else if instr.does_link() && !instr.modifies_rd() {
+                    // `jal` and `bal` return after their delay slot
+                    Value::Const(sign_extend_32(instr.vram.wrapping_add(8) as u64))
+                } else {
+                    Value::Unknown
+                };
+                let defs = registers::defs(instr);
+                for reg in registers::iter(defs).filter(|&reg| reg < 32) {
+                    let is_link = reg == registers::RA && instr.does_link();
+                    let is_loaded = instr.does_load() && reg as u32 == fields::rt(instr);
+                    let value = if is_link || is_loaded {
+                        value
+                    } else {
+                        Value::Unknown
+                    };
+                    self.set(reg as u32, value);
+                }
+                return;
+            }
+        };
+        self.set(dest, value);
+    }
+}
+
+/// Known register values of a function, computed over its CFG
+pub(crate) struct ConstantPropagation {
+    cfg: Cfg,
+    block_entry: Vec<Option<State>>,
+    abi: Abi,
+}
+
+impl ConstantPropagation {
+    pub(crate) fn build(instrs: &[RustInstruction], abi: Abi) -> Self {
+        let cfg = Cfg::build(instrs);
+        let mut block_entry: Vec<Option<State>> = vec![None; cfg.blocks.len()];
+        if !cfg.blocks.is_empty() {
+            block_entry[0] = Some(State::entry());
+        }
+
+        let mut worklist = vec![0];
+        if cfg.blocks.is_empty() {
+            worklist.clear();
+        }
+        while let Some(b) = worklist.pop() {
+            let Some(entry) = block_entry[b].clone() else {
+                continue;
+            };
+            let block = &cfg.blocks[b];
+            let exit = walk(instrs, block.start, block.end, entry, abi, block.end);
+
+            for &s in &block.successors {
+                let merged = match &block_entry[s] {
+                    Some(current) => {
+                        let mut merged = current.clone();
+                        merged.meet(&exit);
+                        merged
+                    }
+                    None => exit.clone(),
+                };
+                if block_entry[s].as_ref() != Some(&merged) {
+                    block_entry[s] = Some(merged);
+                    worklist.push(s);
+                }
+            }
+        }
+
+        ConstantPropagation {
+            cfg,
+            block_entry,
+            abi,
+        }
+    }
+
+    /// State right before the instruction at `index` executes
+    pub(crate) fn state_before(&self, instrs: &[RustInstruction], index: usize) -> Option<State> {
+        let b = self.cfg.block_of(index)?;
+        let entry = self.block_entry[b].clone()?;
+        let start = self.cfg.blocks[b].start;
+        Some(walk(
+            instrs,
+            start,
+            self.cfg.blocks[b].end,
+            entry,
+            self.abi,
+            index,
+        ))
+    }
+}
+
+/// Runs the instructions of a block in `[start, stop)`, where `end` is the end of the block
+fn walk(
+    instrs: &[RustInstruction],
+    start: usize,
+    end: usize,
+    mut state: State,
+    abi: Abi,
+    stop: usize,
+) -> State {
+    for i in start..stop {
+        let instr = &instrs[i];
+        state.step(instr);
+
+        let after_call_slot = i > start && {
+            let previous = &instrs[i - 1];
+            previous.is_function_call() && previous.has_delay_slot()
+        };
+        // Calls clobber registers once their delay slot has run, or right
+        // away if the delay slot is not part of this block
+        let slot_outside = !instr.has_delay_slot() || i + 1 == end;
+        if after_call_slot || (instr.is_function_call() && slot_outside) {
+            state.clobber_call(abi);
+        }
+    }
+    state
+}
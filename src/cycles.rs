@@ -0,0 +1,342 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use napi_derive::napi;
+use rabbitizer::InstrCategory;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::registers;
+
+/// Timing of one instruction inside a cycle estimate
+#[napi(object)]
+pub struct InstructionCycles {
+    pub vram: u32,
+    /// Cycle the instruction issues at, counted from the start of the block
+    pub issue: u32,
+    /// Cycles spent waiting for operands or a busy unit before issuing
+    pub stall: u32,
+}
+
+/// Approximate cost of running a basic block once
+#[napi(object)]
+pub struct CycleEstimate {
+    /// Cycles with the fastest timings and the cheapest way out of the block
+    pub min: u32,
+    /// Cycles with typical timings, counting branch-likely penalties as if
+    /// the branch were taken half of the time
+    pub expected: f64,
+    /// Timing of each instruction with the fastest timings
+    pub instructions: Vec<InstructionCycles>,
+}
+
+/// A latency that depends on the operands, as `(fastest, typical)`
+type Latency = (u32, u32);
+
+/// Approximate timings of a CPU, in cycles from issue until the result can
+/// be used
+struct Timing {
+    mult: Latency,
+    dmult: Latency,
+    div: Latency,
+    ddiv: Latency,
+    /// Extra cycles before a loaded register can be used, 0 when the CPU
+    /// has a load delay slot instead of an interlock
+    load_use: u32,
+    fpu_add: u32,
+    fpu_mul_s: u32,
+    fpu_mul_d: u32,
+    fpu_div_s: u32,
+    fpu_div_d: u32,
+    /// Cycles lost when a branch likely falls through and its delay slot is
+    /// nullified
+    likely_miss: u32,
+    /// Latency of RSP vector unit results
+    vector: u32,
+    /// Whether a scalar and a vector instruction can issue together (RSP)
+    dual_issue: bool,
+}
+
+const R4300I: Timing = Timing {
+    mult: (5, 5),
+    dmult: (8, 8),
+    div: (37, 37),
+    ddiv: (69, 69),
+    load_use: 1,
+    fpu_add: 3,
+    fpu_mul_s: 5,
+    fpu_mul_d: 8,
+    fpu_div_s: 29,
+    fpu_div_d: 58,
+    likely_miss: 1,
+    vector: 1,
+    dual_issue: false,
+};
+
+/// The multiplier finishes early for small operands
+const R3000A: Timing = Timing {
+    mult: (6, 9),
+    dmult: (6, 9),
+    div: (36, 36),
+    ddiv: (36, 36),
+    load_use: 0,
+    fpu_add: 1,
+    fpu_mul_s: 1,
+    fpu_mul_d: 1,
+    fpu_div_s: 1,
+    fpu_div_d: 1,
+    likely_miss: 0,
+    vector: 1,
+    dual_issue: false,
+};
+
+/// The FPU only does single precision, doubles are emulated in software
+const R5900: Timing = Timing {
+    mult: (4, 4),
+    dmult: (4, 4),
+    div: (37, 37),
+    ddiv: (37, 37),
+    load_use: 1,
+    fpu_add: 4,
+    fpu_mul_s: 4,
+    fpu_mul_d: 4,
+    fpu_div_s: 8,
+    fpu_div_d: 8,
+    likely_miss: 1,
+    vector: 1,
+    dual_issue: false,
+};
+
+const ALLEGREX: Timing = Timing {
+    mult: (5, 5),
+    dmult: (5, 5),
+    div: (36, 36),
+    ddiv: (36, 36),
+    load_use: 1,
+    fpu_add: 4,
+    fpu_mul_s: 5,
+    fpu_mul_d: 5,
+    fpu_div_s: 20,
+    fpu_div_d: 20,
+    likely_miss: 1,
+    vector: 1,
+    dual_issue: false,
+};
+
+const RSP: Timing = Timing {
+    mult: (1, 1),
+    dmult: (1, 1),
+    div: (1, 1),
+    ddiv: (1, 1),
+    load_use: 1,
+    fpu_add: 1,
+    fpu_mul_s: 1,
+    fpu_mul_d: 1,
+    fpu_div_s: 1,
+    fpu_div_d: 1,
+    likely_miss: 0,
+    vector: 4,
+    dual_issue: true,
+};
+
+fn timing(category: InstrCategory) -> &'static Timing {
+    match category {
+        InstrCategory::RSP => &RSP,
+        InstrCategory::R3000GTE => &R3000A,
+        InstrCategory::R5900 => &R5900,
+        InstrCategory::R4000ALLEGREX => &ALLEGREX,
+        InstrCategory::CPU | InstrCategory::MAX => &R4300I,
+    }
+}
+
+/// Cycles a GTE command keeps the GTE busy, from the PlayStation's timings
+fn gte_latency(name: &str) -> u32 {
+    match name {
+        "rtps" => 15,
+        "rtpt" => 23,
+        "nclip" => 8,
+        "op" => 6,
+        "dpcs" => 8,
+        "intpl" => 8,
+        "mvmva" => 8,
+        "ncds" => 19,
+        "cdp" => 13,
+        "ncdt" => 44,
+        "nccs" => 17,
+        "cc" => 11,
+        "ncs" => 14,
+        "nct" => 30,
+        "sqr" => 5,
+        "dcpl" => 8,
+        "dpct" => 17,
+        "avsz3" => 5,
+        "avsz4" => 6,
+        "gpf" => 5,
+        "gpl" => 5,
+        "ncct" => 39,
+        _ => 8,
+    }
+}
+
+fn is_cop2(instr: &RustInstruction) -> bool {
+    matches!(instr.raw() >> 26, 0x12 | 0x32 | 0x3A)
+}
+
+/// Checks for COP2 computational instructions (GTE commands, RSP vector ops)
+fn is_cop2_operation(instr: &RustInstruction) -> bool {
+    instr.raw() >> 26 == 0x12 && instr.raw() & (1 << 25) != 0
+}
+
+/// Vector registers read and written by an RSP instruction
+fn vector_registers(instr: &RustInstruction) -> (u32, u32) {
+    let field = |shift: u32| 1u32 << ((instr.raw() >> shift) & 0x1F);
+    match instr.raw() >> 26 {
+        // Vector ops read vs and vt and write vd
+        0x12 if is_cop2_operation(instr) => (field(11) | field(16), field(6)),
+        // mtc2 writes, mfc2 reads the vector register in the rd field
+        0x12 => match (instr.raw() >> 21) & 0x1F {
+            4 => (0, field(11)),
+            0 => (field(11), 0),
+            _ => (0, 0),
+        },
+        // Vector loads write, vector stores read the register in the rt field
+        0x32 => (0, field(16)),
+        0x3A => (field(16), 0),
+        _ => (0, 0),
+    }
+}
+
+/// Cycles from issue until the results of `instr` can be used
+fn result_latency(instr: &RustInstruction, timing: &Timing, typical: bool) -> u32 {
+    let pick = |latency: Latency| if typical { latency.1 } else { latency.0 };
+    let name = instr.opcode_name();
+    match name {
+        "mult" | "multu" => pick(timing.mult),
+        "dmult" | "dmultu" => pick(timing.dmult),
+        "div" | "divu" => pick(timing.div),
+        "ddiv" | "ddivu" => pick(timing.ddiv),
+        "add.s" | "sub.s" | "add.d" | "sub.d" => timing.fpu_add,
+        "mul.s" => timing.fpu_mul_s,
+        "mul.d" => timing.fpu_mul_d,
+        "div.s" | "sqrt.s" => timing.fpu_div_s,
+        "div.d" | "sqrt.d" => timing.fpu_div_d,
+        _ if name.starts_with("cvt.") => timing.fpu_add,
+        _ if instr.does_load() => 1 + timing.load_use,
+        _ => 1,
+    }
+}
+
+fn is_mult_div(instr: &RustInstruction) -> bool {
+    (instr.modifies_hi() || instr.modifies_lo()) && !instr.opcode_name().starts_with("mt")
+}
+
+/// Schedules the instructions in order, one per cycle, stalling until their
+/// operands are ready and the multiplier, divider or GTE is free
+fn schedule(
+    instrs: &[RustInstruction],
+    category: InstrCategory,
+    typical: bool,
+) -> Vec<InstructionCycles> {
+    let timing = timing(category);
+    let mut ready = [0u32; registers::COUNT];
+    let mut vector_ready = [0u32; 32];
+    let mut mult_div_free = 0;
+    let mut gte_free = 0;
+    let mut cycle = 0;
+    // Issue cycle and unit of the previous instruction, if it can still pair
+    let mut pairable: Option<(u32, bool)> = None;
+    let mut out = Vec::with_capacity(instrs.len());
+
+    for instr in instrs {
+        let uses = registers::uses(instr);
+        let defs = registers::defs(instr);
+        let is_vector = category == InstrCategory::RSP && is_cop2(instr);
+        let (vector_uses, vector_defs) = if is_vector {
+            vector_registers(instr)
+        } else {
+            (0, 0)
+        };
+
+        let mut earliest = registers::iter(uses)
+            .map(|reg| ready[reg])
+            .chain(
+                (0..32)
+                    .filter(|v| vector_uses & (1 << v) != 0)
+                    .map(|v| vector_ready[v]),
+            )
+            .fold(0, u32::max);
+        if is_mult_div(instr) {
+            earliest = earliest.max(mult_div_free);
+        }
+        if category == InstrCategory::R3000GTE && is_cop2(instr) {
+            earliest = earliest.max(gte_free);
+        }
+
+        let issue = match pairable {
+            Some((previous, previous_vector))
+                if timing.dual_issue && previous_vector != is_vector && earliest <= previous =>
+            {
+                pairable = None;
+                previous
+            }
+            _ => {
+                let issue = earliest.max(cycle);
+                pairable = Some((issue, is_vector));
+                issue
+            }
+        };
+        out.push(InstructionCycles {
+            vram: instr.vram,
+            issue,
+            stall: issue.saturating_sub(cycle),
+        });
+        cycle = cycle.max(issue + 1);
+
+        let latency = if is_vector && is_cop2_operation(instr) {
+            timing.vector
+        } else {
+            result_latency(instr, timing, typical)
+        };
+        for reg in registers::iter(defs) {
+            ready[reg] = issue + latency;
+        }
+        for (v, slot) in vector_ready.iter_mut().enumerate() {
+            if vector_defs & (1 << v) != 0 {
+                *slot = issue + latency;
+            }
+        }
+        if is_mult_div(instr) {
+            mult_div_free = issue + latency;
+        }
+        if category == InstrCategory::R3000GTE && is_cop2_operation(instr) {
+            gte_free = issue + gte_latency(&instr.opcode_name().to_lowercase());
+        }
+    }
+    out
+}
+
+/// Estimates the cycles a block takes to run once
+///
+/// Instructions issue in order, one per cycle (two on the RSP when a scalar
+/// and a vector instruction pair up), stalling until their operands are
+/// ready: loads followed by a use, `mfhi`/`mflo` waiting for a `mult`/`div`,
+/// FPU and RSP vector results, and busy multiplier, divider or GTE. Caches are
+/// assumed warm and the latencies of the last instructions are not waited
+/// for. `expected` also uses the typical timings of operand-dependent
+/// instructions and, for a block ending in a branch likely, counts the cost of
+/// falling through half of the time.
+pub(crate) fn estimate(instrs: &[RustInstruction], category: InstrCategory) -> CycleEstimate {
+    let fastest = schedule(instrs, category, false);
+    let typical = schedule(instrs, category, true);
+    let total = |schedule: &[InstructionCycles]| schedule.last().map_or(0, |last| last.issue + 1);
+
+    let likely_penalty = match instrs.last() {
+        Some(last) if last.is_branch_likely() => timing(category).likely_miss as f64 / 2.0,
+        _ => 0.0,
+    };
+
+    CycleEstimate {
+        min: total(&fastest),
+        expected: total(&typical) as f64 + likely_penalty,
+        instructions: fastest,
+    }
+}
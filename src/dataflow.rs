@@ -0,0 +1,324 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeSet;
+
+use napi_derive::napi;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::cfg::Cfg;
+use crate::enums::Abi;
+use crate::registers::{self, RegSet};
+
+/// Definitions of a register that may reach an instruction
+#[napi(object)]
+pub struct ReachingDefinitions {
+    /// Addresses of the instructions writing the register
+    pub definitions: Vec<u32>,
+    /// Whether the value the register held on function entry may reach it
+    pub from_entry: bool,
+}
+
+/// A register effect of (part of) an instruction
+struct Step {
+    index: usize,
+    uses: RegSet,
+    defs: RegSet,
+}
+
+/// Register uses and definitions of a function, block by block
+///
+/// Calls are split around their delay slot: the call reads its own operands
+/// first, the delay slot runs, and only then the callee reads the argument
+/// registers and clobbers the caller-saved ones. Returns read, after their
+/// delay slot, the return value registers the function writes anywhere.
+pub(crate) struct Dataflow {
+    pub cfg: Cfg,
+    steps: Vec<Vec<Step>>,
+    live_in: Vec<RegSet>,
+}
+
+/// Every register used to pass arguments under `abi`
+fn argument_registers(abi: Abi) -> RegSet {
+    let mut arguments = 0;
+    for &reg in registers::argument_gprs(abi) {
+        arguments |= registers::bit(reg);
+    }
+    for reg in registers::argument_fprs(abi) {
+        arguments |= registers::bit(reg);
+    }
+    arguments
+}
+
+fn block_steps(
+    instrs: &[RustInstruction],
+    start: usize,
+    end: usize,
+    abi: Abi,
+    arguments: RegSet,
+    returned: RegSet,
+) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let mut i = start;
+    while i < end {
+        let instr = &instrs[i];
+        let uses = registers::uses(instr);
+        let defs = registers::defs(instr);
+
+        let is_call = instr.is_function_call();
+        let is_return = instr.is_return();
+        if (is_call || is_return) && instr.has_delay_slot() && i + 1 < end {
+            let slot = &instrs[i + 1];
+            steps.push(Step {
+                index: i,
+                uses,
+                defs: 0,
+            });
+            steps.push(Step {
+                index: i + 1,
+                uses: registers::uses(slot),
+                defs: registers::defs(slot),
+            });
+            if is_call {
+                steps.push(Step {
+                    index: i,
+                    uses: arguments,
+                    defs: defs | registers::call_clobbers(abi),
+                });
+            } else {
+                steps.push(Step {
+                    index: i,
+                    uses: returned,
+                    defs: 0,
+                });
+            }
+            i += 2;
+            continue;
+        }
+
+        steps.push(Step {
+            index: i,
+            uses,
+            defs,
+        });
+        i += 1;
+    }
+    steps
+}
+
+impl Dataflow {
+    pub(crate) fn build(instrs: &[RustInstruction], abi: Abi) -> Self {
+        Self::build_with_call_arguments(instrs, abi, argument_registers(abi))
+    }
+
+    /// Builds the dataflow assuming calls only read the `arguments` registers
+    pub(crate) fn build_with_call_arguments(
+        instrs: &[RustInstruction],
+        abi: Abi,
+        arguments: RegSet,
+    ) -> Self {
+        let cfg = Cfg::build(instrs);
+        let written = instrs.iter().fold(0, |acc, instr| {
+            let clobbers = if instr.is_function_call() {
+                registers::call_clobbers(abi)
+            } else {
+                0
+            };
+            acc | registers::defs(instr) | clobbers
+        });
+        let returned = registers::return_values() & written;
+        let steps: Vec<Vec<Step>> = cfg
+            .blocks
+            .iter()
+            .map(|block| block_steps(instrs, block.start, block.end, abi, arguments, returned))
+            .collect();
+
+        let mut live_in: Vec<RegSet> = vec![0; cfg.blocks.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for b in (0..cfg.blocks.len()).rev() {
+                let mut live = cfg.blocks[b]
+                    .successors
+                    .iter()
+                    .fold(0, |acc, &s| acc | live_in[s]);
+                for step in steps[b].iter().rev() {
+                    live = (live & !step.defs) | step.uses;
+                }
+                if live != live_in[b] {
+                    live_in[b] = live;
+                    changed = true;
+                }
+            }
+        }
+
+        Dataflow {
+            cfg,
+            steps,
+            live_in,
+        }
+    }
+
+    /// Registers live on entry to block `b`
+    pub(crate) fn live_in(&self, b: usize) -> RegSet {
+        self.live_in[b]
+    }
+
+    /// Definitions of `reg` that may reach the start of each block
+    fn reach_in(&self, reg: usize) -> Vec<BTreeSet<Option<usize>>> {
+        let blocks = &self.cfg.blocks;
+
+        // Last definition of `reg` in each block
+        let last_def: Vec<Option<usize>> = self
+            .steps
+            .iter()
+            .map(|steps| {
+                steps
+                    .iter()
+                    .rev()
+                    .find(|step| registers::contains(step.defs, reg))
+                    .map(|step| step.index)
+            })
+            .collect();
+
+        let mut reach_in: Vec<BTreeSet<Option<usize>>> = vec![BTreeSet::new(); blocks.len()];
+        if !blocks.is_empty() {
+            reach_in[0].insert(None);
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for b in 0..blocks.len() {
+                let out = match last_def[b] {
+                    Some(def) => BTreeSet::from([Some(def)]),
+                    None => reach_in[b].clone(),
+                };
+                for &s in &blocks[b].successors {
+                    let before = reach_in[s].len();
+                    reach_in[s].extend(out.iter().copied());
+                    changed |= reach_in[s].len() != before;
+                }
+            }
+        }
+        reach_in
+    }
+
+    /// Definitions of `reg` that may reach any return, after its delay slot
+    pub(crate) fn reaching_returns(
+        &self,
+        instrs: &[RustInstruction],
+        reg: usize,
+    ) -> BTreeSet<Option<usize>> {
+        let reach_in = self.reach_in(reg);
+        let mut reaching = BTreeSet::new();
+        for (b, steps) in self.steps.iter().enumerate() {
+            for (position, step) in steps.iter().enumerate() {
+                let is_last_step = steps[position + 1..]
+                    .iter()
+                    .all(|later| later.index != step.index);
+                if instrs[step.index].is_return() && is_last_step {
+                    reaching.extend(self.reaching_before(&reach_in[b], b, position, reg));
+                }
+            }
+        }
+        reaching
+    }
+
+    /// Instructions whose definition of `reg` may reach the instruction at `index`
+    ///
+    /// Entry definitions are reported as `None`.
+    pub(crate) fn reaching(&self, index: usize, reg: usize) -> BTreeSet<Option<usize>> {
+        self.reaching_step(index, reg, false)
+    }
+
+    /// Like `reaching`, but for the last effect of the instruction
+    ///
+    /// For calls this is the callee reading its arguments, after the delay slot.
+    pub(crate) fn reaching_after(&self, index: usize, reg: usize) -> BTreeSet<Option<usize>> {
+        self.reaching_step(index, reg, true)
+    }
+
+    fn reaching_step(&self, index: usize, reg: usize, last: bool) -> BTreeSet<Option<usize>> {
+        let reach_in = self.reach_in(reg);
+
+        let Some(b) = self.cfg.block_of(index) else {
+            return BTreeSet::new();
+        };
+        let mut steps = self.steps[b].iter();
+        let position = if last {
+            steps.rposition(|step| step.index == index)
+        } else {
+            steps.position(|step| step.index == index)
+        };
+        self.reaching_before(&reach_in[b], b, position.unwrap_or(0), reg)
+    }
+
+    /// Definitions of `reg` that may reach the `position`-th step of block `b`
+    fn reaching_before(
+        &self,
+        reach_in: &BTreeSet<Option<usize>>,
+        b: usize,
+        position: usize,
+        reg: usize,
+    ) -> BTreeSet<Option<usize>> {
+        let mut reaching = reach_in.clone();
+        for step in &self.steps[b][..position] {
+            if registers::contains(step.defs, reg) {
+                reaching = BTreeSet::from([Some(step.index)]);
+            }
+        }
+        reaching
+    }
+
+    /// Instructions that may read the value of `reg` written by the instruction at `index`
+    ///
+    /// Returns `None` if the instruction does not write `reg`.
+    pub(crate) fn uses_of(&self, index: usize, reg: usize) -> Option<BTreeSet<usize>> {
+        let b = self.cfg.block_of(index)?;
+        let def_position = self.steps[b]
+            .iter()
+            .rposition(|step| step.index == index && registers::contains(step.defs, reg))?;
+
+        Some(self.uses_from(b, def_position + 1, reg))
+    }
+
+    /// Instructions that may read the value `reg` holds on function entry
+    pub(crate) fn entry_uses(&self, reg: usize) -> BTreeSet<usize> {
+        if self.steps.is_empty() {
+            return BTreeSet::new();
+        }
+        self.uses_from(0, 0, reg)
+    }
+
+    /// Instructions that may read the value `reg` holds before the `position`-th step of block `b`
+    fn uses_from(&self, b: usize, position: usize, reg: usize) -> BTreeSet<usize> {
+        let mut uses = BTreeSet::new();
+        // Walks `steps`, returning whether the value survives to the end
+        let mut scan = |steps: &[Step]| -> bool {
+            for step in steps {
+                if registers::contains(step.uses, reg) {
+                    uses.insert(step.index);
+                }
+                if registers::contains(step.defs, reg) {
+                    return false;
+                }
+            }
+            true
+        };
+
+        let mut worklist = Vec::new();
+        if scan(&self.steps[b][position..]) {
+            worklist.extend(self.cfg.blocks[b].successors.iter().copied());
+        }
+        let mut visited = vec![false; self.cfg.blocks.len()];
+        while let Some(b) = worklist.pop() {
+            if std::mem::replace(&mut visited[b], true) {
+                continue;
+            }
+            if scan(&self.steps[b]) {
+                worklist.extend(self.cfg.blocks[b].successors.iter().copied());
+            }
+        }
+        uses
+    }
+}
@@ -0,0 +1,237 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashSet;
+
+use napi_derive::napi;
+use rabbitizer::Instruction as RustInstruction;
+use rabbitizer::OperandType;
+
+use crate::assembler;
+use crate::relocate::hi_lo_pairs;
+use crate::section::CodeSection;
+
+/// How the instructions of a diff row compare
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// The words are the same
+    Equal,
+    /// Same opcode, and some register operand differs, whether or not a value does too
+    RegisterRenamed,
+    /// Same opcode and registers, with a different immediate, branch offset or shift amount
+    ImmediateDiffers,
+    /// Same opcode and registers, and only a relocated field differs: a
+    /// `j`/`jal` target or the immediate of a `%hi`/`%lo` pair
+    RelocationOnly,
+    /// Only in the second section
+    Inserted,
+    /// Only in the first section
+    Deleted,
+}
+
+/// Options for `diffSections`
+#[napi(object)]
+pub struct DiffOptions {
+    /// Equal rows kept around each change, like a unified diff (defaults to every row)
+    pub context: Option<u32>,
+}
+
+/// One row of an aligned diff
+#[napi(object)]
+pub struct DiffRow {
+    pub kind: DiffKind,
+    /// Address in the first section, missing for inserted rows
+    pub a_vram: Option<u32>,
+    /// Address in the second section, missing for deleted rows
+    pub b_vram: Option<u32>,
+    pub a_text: Option<String>,
+    pub b_text: Option<String>,
+}
+
+/// Checks if two instructions can share a row
+fn aligns(a: &RustInstruction, b: &RustInstruction) -> bool {
+    a.same_opcode(b) || a.raw() == b.raw()
+}
+
+/// Bits of a word holding a value rather than a register
+fn value_mask(instr: &RustInstruction) -> u32 {
+    if instr.is_jump_with_address() {
+        return 0x03FF_FFFF;
+    }
+    let mut mask = 0;
+    if instr.is_branch() || instr.has_operand_alias(OperandType::cpu_immediate) {
+        mask |= 0xFFFF;
+    }
+    if instr.has_operand_alias(OperandType::cpu_sa) {
+        mask |= 0x7C0;
+    }
+    mask
+}
+
+/// Indices of the `lui`s and `%lo` instructions paired up in a section
+fn hi_lo_indices(instrs: &[RustInstruction]) -> HashSet<usize> {
+    hi_lo_pairs(instrs)
+        .into_iter()
+        .flat_map(|(hi, los)| std::iter::once(hi).chain(los))
+        .collect()
+}
+
+/// Compares two lined-up instructions, `hi_lo` telling if either is part of a
+/// `%hi`/`%lo` pair
+fn classify(a: &RustInstruction, b: &RustInstruction, hi_lo: bool) -> DiffKind {
+    // Aligned rows share their opcode unless their words are equal
+    if !a.same_opcode_but_different_arguments(b) {
+        return DiffKind::Equal;
+    }
+    if (a.raw() ^ b.raw()) & !value_mask(a) != 0 {
+        return DiffKind::RegisterRenamed;
+    }
+    if a.is_jump_with_address() || hi_lo {
+        DiffKind::RelocationOnly
+    } else {
+        DiffKind::ImmediateDiffers
+    }
+}
+
+/// Pairs of indices `(a, b)` where either side may be missing, in order
+type Alignment = Vec<(Option<usize>, Option<usize>)>;
+
+/// Aligns two instruction lists with Myers' O(ND) difference algorithm
+fn align(a: &[RustInstruction], b: &[RustInstruction]) -> Alignment {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = n + m;
+    let offset = max as usize;
+    let mut v = vec![0isize; 2 * offset + 2];
+    // The furthest x on each diagonal -d..=d after every step d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let index = (k + max) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && aligns(&a[x as usize], &b[y as usize]) {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                trace.push(v[offset - d as usize..=offset + d as usize].to_vec());
+                break 'search;
+            }
+        }
+        trace.push(v[offset - d as usize..=offset + d as usize].to_vec());
+    }
+
+    let mut rows = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        let k = x - y;
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let previous = &trace[d as usize - 1];
+            let at = |k: isize| previous[(k + d - 1) as usize];
+            let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+            (at(prev_k), at(prev_k) - prev_k)
+        };
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            rows.push((Some(x as usize), Some(y as usize)));
+        }
+        if d > 0 {
+            if x == prev_x {
+                rows.push((None, Some(prev_y as usize)));
+            } else {
+                rows.push((Some(prev_x as usize), None));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    rows.reverse();
+    rows
+}
+
+/// Aligns the instructions of two sections and tells how each row differs
+///
+/// Instructions with the same opcode are lined up using the longest common
+/// subsequence, found with Myers' algorithm, so the diff stays fast when the
+/// sections are mostly the same. Lined-up instructions are then compared field
+/// by field; everything else is inserted or deleted.
+///
+/// # Arguments
+/// * `a` - The first section, usually the original code
+/// * `b` - The second section, usually the compiled code
+/// * `options` - How many equal rows to keep around changes (optional)
+#[napi]
+pub fn diff_sections(
+    a: &CodeSection,
+    b: &CodeSection,
+    options: Option<DiffOptions>,
+) -> Vec<DiffRow> {
+    let (a, b) = (a.instrs(), b.instrs());
+    let alignment = align(a, b);
+    let (a_hi_lo, b_hi_lo) = (hi_lo_indices(a), hi_lo_indices(b));
+    let kinds: Vec<DiffKind> = alignment
+        .iter()
+        .map(|row| match *row {
+            (Some(i), Some(j)) => {
+                classify(&a[i], &b[j], a_hi_lo.contains(&i) || b_hi_lo.contains(&j))
+            }
+            (None, _) => DiffKind::Inserted,
+            (_, None) => DiffKind::Deleted,
+        })
+        .collect();
+
+    // Distance of every row to the closest change
+    let mut distance = vec![usize::MAX; kinds.len()];
+    let mut last = None;
+    for (i, kind) in kinds.iter().enumerate() {
+        if *kind != DiffKind::Equal {
+            last = Some(i);
+        }
+        distance[i] = last.map_or(usize::MAX, |last| i - last);
+    }
+    let mut next = None;
+    for (i, kind) in kinds.iter().enumerate().rev() {
+        if *kind != DiffKind::Equal {
+            next = Some(i);
+        }
+        if let Some(next) = next {
+            distance[i] = distance[i].min(next - i);
+        }
+    }
+
+    let context = options
+        .and_then(|options| options.context)
+        .map_or(usize::MAX, |context| context as usize);
+    alignment
+        .into_iter()
+        .zip(kinds)
+        .zip(distance)
+        .filter(|(_, distance)| *distance <= context)
+        .map(|(((i, j), kind), _)| {
+            let a = i.map(|i| &a[i]);
+            let b = j.map(|j| &b[j]);
+            DiffRow {
+                kind,
+                a_vram: a.map(|instr| instr.vram),
+                b_vram: b.map(|instr| instr.vram),
+                a_text: a.map(|instr| assembler::disassemble(instr, None)),
+                b_text: b.map(|instr| assembler::disassemble(instr, None)),
+            }
+        })
+        .collect()
+}
@@ -20,6 +20,7 @@ pub enum InstrCategory {
 
 /// Memory access type enumeration
 #[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum AccessType {
     Invalid,
     Byte,
@@ -44,6 +45,7 @@ pub enum InstrSuffix {
 
 /// Instruction ID type enumeration
 #[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum InstrIdType {
     AllInvalid,
     CpuInvalid,
@@ -118,6 +120,7 @@ pub enum InstrIdType {
     R4000allegrexVfpu4Fmt0Cst,
     R4000allegrexVfpu4Fmt2,
     R4000allegrexVfpu4Fmt2Cndmove,
+    R4000allegrexLvl,
     R4000allegrexVfpu5,
     R4000allegrexVfpu6,
     R4000allegrexVfpu6Fmt7,
@@ -147,6 +150,1921 @@ pub enum InstrIdType {
     R5900MmiPmthl,
 }
 
+/// Instruction ID enumeration, with the values of rabbitizer's `InstrId`
+///
+/// The placeholder IDs (`INVALID`, `USERDEF_*` and `MAX`) are left out.
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InstrId {
+    CpuJ = 1,
+    CpuJal = 2,
+    CpuBeq = 3,
+    CpuBne = 4,
+    CpuBeql = 5,
+    CpuBnel = 6,
+    CpuBlez = 7,
+    CpuBlezl = 8,
+    CpuBgtz = 9,
+    CpuBgtzl = 10,
+    CpuAddi = 11,
+    CpuAddiu = 12,
+    CpuSlti = 13,
+    CpuSltiu = 14,
+    CpuAndi = 15,
+    CpuOri = 16,
+    CpuXori = 17,
+    CpuDaddi = 18,
+    CpuDaddiu = 19,
+    CpuLui = 20,
+    CpuLdl = 21,
+    CpuLdr = 22,
+    CpuLb = 23,
+    CpuLh = 24,
+    CpuLwl = 25,
+    CpuLw = 26,
+    CpuLbu = 27,
+    CpuLhu = 28,
+    CpuLwr = 29,
+    CpuLwu = 30,
+    CpuSb = 31,
+    CpuSh = 32,
+    CpuSwl = 33,
+    CpuSw = 34,
+    CpuSdl = 35,
+    CpuSdr = 36,
+    CpuSwr = 37,
+    CpuLl = 38,
+    CpuPref = 39,
+    CpuLld = 40,
+    CpuLd = 41,
+    CpuSc = 42,
+    CpuScd = 43,
+    CpuSd = 44,
+    CpuCache = 45,
+    CpuLwc1 = 46,
+    CpuLdc1 = 47,
+    CpuSwc1 = 48,
+    CpuSdc1 = 49,
+    CpuLwc2 = 50,
+    CpuLdc2 = 51,
+    CpuSwc2 = 52,
+    CpuSdc2 = 53,
+    CpuB = 54,
+    CpuBeqz = 55,
+    CpuBnez = 56,
+    CpuSll = 57,
+    CpuSrl = 58,
+    CpuSra = 59,
+    CpuDsll = 60,
+    CpuDsrl = 61,
+    CpuDsra = 62,
+    CpuDsll32 = 63,
+    CpuDsrl32 = 64,
+    CpuDsra32 = 65,
+    CpuDsllv = 66,
+    CpuDsrlv = 67,
+    CpuDsrav = 68,
+    CpuSllv = 69,
+    CpuSrlv = 70,
+    CpuSrav = 71,
+    CpuMthi = 72,
+    CpuMtlo = 73,
+    CpuJr = 74,
+    CpuJalr = 75,
+    CpuMfhi = 76,
+    CpuMflo = 77,
+    CpuMovz = 78,
+    CpuMovn = 79,
+    CpuDiv = 80,
+    CpuDivu = 81,
+    CpuSn64Div = 82,
+    CpuSn64Divu = 83,
+    CpuDdiv = 84,
+    CpuDdivu = 85,
+    CpuAdd = 86,
+    CpuAddu = 87,
+    CpuSub = 88,
+    CpuSubu = 89,
+    CpuAnd = 90,
+    CpuOr = 91,
+    CpuXor = 92,
+    CpuNor = 93,
+    CpuSlt = 94,
+    CpuSltu = 95,
+    CpuDadd = 96,
+    CpuDaddu = 97,
+    CpuDsub = 98,
+    CpuDsubu = 99,
+    CpuSyscall = 100,
+    CpuBreak = 101,
+    CpuSync = 102,
+    CpuMult = 103,
+    CpuMultu = 104,
+    CpuDmult = 105,
+    CpuDmultu = 106,
+    CpuTge = 107,
+    CpuTgeu = 108,
+    CpuTlt = 109,
+    CpuTltu = 110,
+    CpuTeq = 111,
+    CpuTne = 112,
+    CpuNop = 113,
+    CpuMove = 114,
+    CpuNot = 115,
+    CpuNeg = 116,
+    CpuNegu = 117,
+    CpuBltz = 118,
+    CpuBgez = 119,
+    CpuBltzl = 120,
+    CpuBgezl = 121,
+    CpuTgei = 122,
+    CpuTgeiu = 123,
+    CpuTlti = 124,
+    CpuTltiu = 125,
+    CpuTeqi = 126,
+    CpuTnei = 127,
+    CpuBltzal = 128,
+    CpuBgezal = 129,
+    CpuBltzall = 130,
+    CpuBgezall = 131,
+    CpuBal = 132,
+    CpuMfc0 = 133,
+    CpuDmfc0 = 134,
+    CpuCfc0 = 135,
+    CpuMtc0 = 136,
+    CpuDmtc0 = 137,
+    CpuCtc0 = 138,
+    CpuBc0f = 139,
+    CpuBc0t = 140,
+    CpuBc0fl = 141,
+    CpuBc0tl = 142,
+    CpuTlbr = 143,
+    CpuTlbwi = 144,
+    CpuTlbwr = 145,
+    CpuTlbp = 146,
+    CpuRfe = 147,
+    CpuEret = 148,
+    CpuMfc1 = 149,
+    CpuDmfc1 = 150,
+    CpuMtc1 = 151,
+    CpuDmtc1 = 152,
+    CpuCfc1 = 153,
+    CpuCtc1 = 154,
+    CpuBc1f = 155,
+    CpuBc1t = 156,
+    CpuBc1fl = 157,
+    CpuBc1tl = 158,
+    CpuAddS = 159,
+    CpuSubS = 160,
+    CpuMulS = 161,
+    CpuDivS = 162,
+    CpuSqrtS = 163,
+    CpuAbsS = 164,
+    CpuMovS = 165,
+    CpuNegS = 166,
+    CpuRoundLS = 167,
+    CpuTruncLS = 168,
+    CpuCeilLS = 169,
+    CpuFloorLS = 170,
+    CpuRoundWS = 171,
+    CpuTruncWS = 172,
+    CpuCeilWS = 173,
+    CpuFloorWS = 174,
+    CpuCvtDS = 175,
+    CpuCvtWS = 176,
+    CpuCvtLS = 177,
+    CpuCFS = 178,
+    CpuCUnS = 179,
+    CpuCEqS = 180,
+    CpuCUeqS = 181,
+    CpuCOltS = 182,
+    CpuCUltS = 183,
+    CpuCOleS = 184,
+    CpuCUleS = 185,
+    CpuCSfS = 186,
+    CpuCNgleS = 187,
+    CpuCSeqS = 188,
+    CpuCNglS = 189,
+    CpuCLtS = 190,
+    CpuCNgeS = 191,
+    CpuCLeS = 192,
+    CpuCNgtS = 193,
+    CpuAddD = 194,
+    CpuSubD = 195,
+    CpuMulD = 196,
+    CpuDivD = 197,
+    CpuSqrtD = 198,
+    CpuAbsD = 199,
+    CpuMovD = 200,
+    CpuNegD = 201,
+    CpuRoundLD = 202,
+    CpuTruncLD = 203,
+    CpuCeilLD = 204,
+    CpuFloorLD = 205,
+    CpuRoundWD = 206,
+    CpuTruncWD = 207,
+    CpuCeilWD = 208,
+    CpuFloorWD = 209,
+    CpuCvtSD = 210,
+    CpuCvtWD = 211,
+    CpuCvtLD = 212,
+    CpuCFD = 213,
+    CpuCUnD = 214,
+    CpuCEqD = 215,
+    CpuCUeqD = 216,
+    CpuCOltD = 217,
+    CpuCUltD = 218,
+    CpuCOleD = 219,
+    CpuCUleD = 220,
+    CpuCDfD = 221,
+    CpuCNgleD = 222,
+    CpuCSeqD = 223,
+    CpuCNglD = 224,
+    CpuCLtD = 225,
+    CpuCNgeD = 226,
+    CpuCLeD = 227,
+    CpuCNgtD = 228,
+    CpuCvtSW = 229,
+    CpuCvtDW = 230,
+    CpuCvtSL = 231,
+    CpuCvtDL = 232,
+    CpuMfc2 = 233,
+    CpuMtc2 = 234,
+    CpuCfc2 = 235,
+    CpuCtc2 = 236,
+    RspMfc2 = 259,
+    RspMtc2 = 260,
+    RspCfc2 = 261,
+    RspCtc2 = 262,
+    RspVmulf = 263,
+    RspVmulu = 264,
+    RspVrndp = 265,
+    RspVmulq = 266,
+    RspVmudl = 267,
+    RspVmudm = 268,
+    RspVmudn = 269,
+    RspVmudh = 270,
+    RspVmacf = 271,
+    RspVmacu = 272,
+    RspVrndn = 273,
+    RspVmacq = 274,
+    RspVmadl = 275,
+    RspVmadm = 276,
+    RspVmadn = 277,
+    RspVmadh = 278,
+    RspVadd = 279,
+    RspVsub = 280,
+    RspVabs = 281,
+    RspVaddc = 282,
+    RspVsubc = 283,
+    RspVsar = 284,
+    RspVand = 285,
+    RspVnand = 286,
+    RspVor = 287,
+    RspVnor = 288,
+    RspVxor = 289,
+    RspVnxor = 290,
+    RspVlt = 291,
+    RspVeq = 292,
+    RspVne = 293,
+    RspVge = 294,
+    RspVcl = 295,
+    RspVch = 296,
+    RspVcr = 297,
+    RspVmrg = 298,
+    RspVrcp = 299,
+    RspVrcpl = 300,
+    RspVrcph = 301,
+    RspVmov = 302,
+    RspVrsq = 303,
+    RspVrsql = 304,
+    RspVrsqh = 305,
+    RspVnop = 306,
+    RspLbv = 307,
+    RspLsv = 308,
+    RspLlv = 309,
+    RspLdv = 310,
+    RspLqv = 311,
+    RspLrv = 312,
+    RspLpv = 313,
+    RspLuv = 314,
+    RspLhv = 315,
+    RspLfv = 316,
+    RspLtv = 317,
+    RspSbv = 318,
+    RspSsv = 319,
+    RspSlv = 320,
+    RspSdv = 321,
+    RspSqv = 322,
+    RspSrv = 323,
+    RspSpv = 324,
+    RspSuv = 325,
+    RspShv = 326,
+    RspSfv = 327,
+    RspStv = 328,
+    RspSwv = 329,
+    RspJ = 330,
+    RspJal = 331,
+    RspBeq = 332,
+    RspBne = 333,
+    RspBlez = 334,
+    RspBgtz = 335,
+    RspAddi = 336,
+    RspAddiu = 337,
+    RspSlti = 338,
+    RspSltiu = 339,
+    RspAndi = 340,
+    RspOri = 341,
+    RspXori = 342,
+    RspLui = 343,
+    RspLb = 344,
+    RspLh = 345,
+    RspLw = 346,
+    RspLbu = 347,
+    RspLhu = 348,
+    RspSb = 349,
+    RspSh = 350,
+    RspSw = 351,
+    RspPref = 352,
+    RspB = 353,
+    RspBeqz = 354,
+    RspBnez = 355,
+    RspSll = 356,
+    RspSrl = 357,
+    RspSra = 358,
+    RspSllv = 359,
+    RspSrlv = 360,
+    RspSrav = 361,
+    RspJr = 362,
+    RspJalr = 363,
+    RspMovz = 364,
+    RspMovn = 365,
+    RspAdd = 366,
+    RspAddu = 367,
+    RspSub = 368,
+    RspSubu = 369,
+    RspAnd = 370,
+    RspOr = 371,
+    RspXor = 372,
+    RspNor = 373,
+    RspSlt = 374,
+    RspSltu = 375,
+    RspBreak = 376,
+    RspNop = 377,
+    RspMove = 378,
+    RspNot = 379,
+    RspNeg = 380,
+    RspNegu = 381,
+    RspBltz = 382,
+    RspBgez = 383,
+    RspBltzal = 384,
+    RspBgezal = 385,
+    RspBal = 386,
+    RspMfc0 = 387,
+    RspMtc0 = 388,
+    R3000gteRtps = 411,
+    R3000gteRtpt = 412,
+    R3000gteDpcl = 413,
+    R3000gteDpcs = 414,
+    R3000gteDpct = 415,
+    R3000gteIntpl = 416,
+    R3000gteNcs = 417,
+    R3000gteNct = 418,
+    R3000gteNcds = 419,
+    R3000gteNcdt = 420,
+    R3000gteNccs = 421,
+    R3000gteNcct = 422,
+    R3000gteCdp = 423,
+    R3000gteCc = 424,
+    R3000gteNclip = 425,
+    R3000gteAvsz3 = 426,
+    R3000gteAvsz4 = 427,
+    R3000gteMvmva = 428,
+    R3000gteSqr = 429,
+    R3000gteOp = 430,
+    R3000gteGpf = 431,
+    R3000gteGpl = 432,
+    R4000allegrexLvS = 455,
+    R4000allegrexSvS = 456,
+    R4000allegrexLvQ = 457,
+    R4000allegrexSvQ = 458,
+    R4000allegrexClz = 459,
+    R4000allegrexClo = 460,
+    R4000allegrexMadd = 461,
+    R4000allegrexMaddu = 462,
+    R4000allegrexMsub = 463,
+    R4000allegrexMsubu = 464,
+    R4000allegrexMax = 465,
+    R4000allegrexMin = 466,
+    R4000allegrexSrl = 467,
+    R4000allegrexRotr = 468,
+    R4000allegrexSrlv = 469,
+    R4000allegrexRotrv = 470,
+    R4000allegrexSleep = 471,
+    R4000allegrexMfie = 472,
+    R4000allegrexMtie = 473,
+    R4000allegrexExt = 474,
+    R4000allegrexIns = 475,
+    R4000allegrexWsbh = 476,
+    R4000allegrexWsbw = 477,
+    R4000allegrexSeb = 478,
+    R4000allegrexSeh = 479,
+    R4000allegrexBitrev = 480,
+    R4000allegrexBvf = 481,
+    R4000allegrexBvt = 482,
+    R4000allegrexBvfl = 483,
+    R4000allegrexBvtl = 484,
+    R4000allegrexMfv = 485,
+    R4000allegrexMfvc = 486,
+    R4000allegrexVsync2 = 487,
+    R4000allegrexMtv = 488,
+    R4000allegrexMtvc = 489,
+    R4000allegrexVaddS = 490,
+    R4000allegrexVaddP = 491,
+    R4000allegrexVaddT = 492,
+    R4000allegrexVaddQ = 493,
+    R4000allegrexVsubS = 494,
+    R4000allegrexVsubP = 495,
+    R4000allegrexVsubT = 496,
+    R4000allegrexVsubQ = 497,
+    R4000allegrexVsbnS = 498,
+    R4000allegrexVdivS = 499,
+    R4000allegrexVdivP = 500,
+    R4000allegrexVdivT = 501,
+    R4000allegrexVdivQ = 502,
+    R4000allegrexVmulS = 503,
+    R4000allegrexVmulP = 504,
+    R4000allegrexVmulT = 505,
+    R4000allegrexVmulQ = 506,
+    R4000allegrexVdotP = 507,
+    R4000allegrexVdotT = 508,
+    R4000allegrexVdotQ = 509,
+    R4000allegrexVsclP = 510,
+    R4000allegrexVsclT = 511,
+    R4000allegrexVsclQ = 512,
+    R4000allegrexVhdpP = 513,
+    R4000allegrexVhdpT = 514,
+    R4000allegrexVhdpQ = 515,
+    R4000allegrexVcrsT = 516,
+    R4000allegrexVdetP = 517,
+    R4000allegrexVcmpS = 518,
+    R4000allegrexVcmpP = 519,
+    R4000allegrexVcmpT = 520,
+    R4000allegrexVcmpQ = 521,
+    R4000allegrexVminS = 522,
+    R4000allegrexVminP = 523,
+    R4000allegrexVminT = 524,
+    R4000allegrexVminQ = 525,
+    R4000allegrexVmaxS = 526,
+    R4000allegrexVmaxP = 527,
+    R4000allegrexVmaxT = 528,
+    R4000allegrexVmaxQ = 529,
+    R4000allegrexVscmpS = 530,
+    R4000allegrexVscmpP = 531,
+    R4000allegrexVscmpT = 532,
+    R4000allegrexVscmpQ = 533,
+    R4000allegrexVsgeS = 534,
+    R4000allegrexVsgeP = 535,
+    R4000allegrexVsgeT = 536,
+    R4000allegrexVsgeQ = 537,
+    R4000allegrexVsltS = 538,
+    R4000allegrexVsltP = 539,
+    R4000allegrexVsltT = 540,
+    R4000allegrexVsltQ = 541,
+    R4000allegrexVwbnS = 542,
+    R4000allegrexVmovS = 543,
+    R4000allegrexVmovP = 544,
+    R4000allegrexVmovT = 545,
+    R4000allegrexVmovQ = 546,
+    R4000allegrexVabsS = 547,
+    R4000allegrexVabsP = 548,
+    R4000allegrexVabsT = 549,
+    R4000allegrexVabsQ = 550,
+    R4000allegrexVnegS = 551,
+    R4000allegrexVnegP = 552,
+    R4000allegrexVnegT = 553,
+    R4000allegrexVnegQ = 554,
+    R4000allegrexVidtP = 555,
+    R4000allegrexVidtQ = 556,
+    R4000allegrexVsat0S = 557,
+    R4000allegrexVsat0P = 558,
+    R4000allegrexVsat0T = 559,
+    R4000allegrexVsat0Q = 560,
+    R4000allegrexVsat1S = 561,
+    R4000allegrexVsat1P = 562,
+    R4000allegrexVsat1T = 563,
+    R4000allegrexVsat1Q = 564,
+    R4000allegrexVzeroS = 565,
+    R4000allegrexVzeroP = 566,
+    R4000allegrexVzeroT = 567,
+    R4000allegrexVzeroQ = 568,
+    R4000allegrexVoneS = 569,
+    R4000allegrexVoneP = 570,
+    R4000allegrexVoneT = 571,
+    R4000allegrexVoneQ = 572,
+    R4000allegrexVrcpS = 573,
+    R4000allegrexVrcpP = 574,
+    R4000allegrexVrcpT = 575,
+    R4000allegrexVrcpQ = 576,
+    R4000allegrexVrsqS = 577,
+    R4000allegrexVrsqP = 578,
+    R4000allegrexVrsqT = 579,
+    R4000allegrexVrsqQ = 580,
+    R4000allegrexVsinS = 581,
+    R4000allegrexVsinP = 582,
+    R4000allegrexVsinT = 583,
+    R4000allegrexVsinQ = 584,
+    R4000allegrexVcosS = 585,
+    R4000allegrexVcosP = 586,
+    R4000allegrexVcosT = 587,
+    R4000allegrexVcosQ = 588,
+    R4000allegrexVexp2S = 589,
+    R4000allegrexVexp2P = 590,
+    R4000allegrexVexp2T = 591,
+    R4000allegrexVexp2Q = 592,
+    R4000allegrexVlog2S = 593,
+    R4000allegrexVlog2P = 594,
+    R4000allegrexVlog2T = 595,
+    R4000allegrexVlog2Q = 596,
+    R4000allegrexVsqrtS = 597,
+    R4000allegrexVsqrtP = 598,
+    R4000allegrexVsqrtT = 599,
+    R4000allegrexVsqrtQ = 600,
+    R4000allegrexVasinS = 601,
+    R4000allegrexVasinP = 602,
+    R4000allegrexVasinT = 603,
+    R4000allegrexVasinQ = 604,
+    R4000allegrexVnrcpS = 605,
+    R4000allegrexVnrcpP = 606,
+    R4000allegrexVnrcpT = 607,
+    R4000allegrexVnrcpQ = 608,
+    R4000allegrexVnsinS = 609,
+    R4000allegrexVnsinP = 610,
+    R4000allegrexVnsinT = 611,
+    R4000allegrexVnsinQ = 612,
+    R4000allegrexVrexp2S = 613,
+    R4000allegrexVrexp2P = 614,
+    R4000allegrexVrexp2T = 615,
+    R4000allegrexVrexp2Q = 616,
+    R4000allegrexVrndsS = 617,
+    R4000allegrexVrndiS = 618,
+    R4000allegrexVrndiP = 619,
+    R4000allegrexVrndiT = 620,
+    R4000allegrexVrndiQ = 621,
+    R4000allegrexVrndf1S = 622,
+    R4000allegrexVrndf1P = 623,
+    R4000allegrexVrndf1T = 624,
+    R4000allegrexVrndf1Q = 625,
+    R4000allegrexVrndf2S = 626,
+    R4000allegrexVrndf2P = 627,
+    R4000allegrexVrndf2T = 628,
+    R4000allegrexVrndf2Q = 629,
+    R4000allegrexVf2hP = 630,
+    R4000allegrexVf2hQ = 631,
+    R4000allegrexVh2fS = 632,
+    R4000allegrexVh2fP = 633,
+    R4000allegrexVsbzS = 634,
+    R4000allegrexVlgbS = 635,
+    R4000allegrexVuc2ifsS = 636,
+    R4000allegrexVc2iS = 637,
+    R4000allegrexVus2iS = 638,
+    R4000allegrexVus2iP = 639,
+    R4000allegrexVs2iS = 640,
+    R4000allegrexVs2iP = 641,
+    R4000allegrexVi2ucQ = 642,
+    R4000allegrexVi2cQ = 643,
+    R4000allegrexVi2usP = 644,
+    R4000allegrexVi2usQ = 645,
+    R4000allegrexVi2sP = 646,
+    R4000allegrexVi2sQ = 647,
+    R4000allegrexVsrt1Q = 648,
+    R4000allegrexVsrt2Q = 649,
+    R4000allegrexVbfy1P = 650,
+    R4000allegrexVbfy1Q = 651,
+    R4000allegrexVbfy2Q = 652,
+    R4000allegrexVocpS = 653,
+    R4000allegrexVocpP = 654,
+    R4000allegrexVocpT = 655,
+    R4000allegrexVocpQ = 656,
+    R4000allegrexVsocpS = 657,
+    R4000allegrexVsocpP = 658,
+    R4000allegrexVfadP = 659,
+    R4000allegrexVfadT = 660,
+    R4000allegrexVfadQ = 661,
+    R4000allegrexVavgP = 662,
+    R4000allegrexVavgT = 663,
+    R4000allegrexVavgQ = 664,
+    R4000allegrexVsrt3Q = 665,
+    R4000allegrexVsrt4Q = 666,
+    R4000allegrexVsgnS = 667,
+    R4000allegrexVsgnP = 668,
+    R4000allegrexVsgnT = 669,
+    R4000allegrexVsgnQ = 670,
+    R4000allegrexVmfvc = 671,
+    R4000allegrexVmtvc = 672,
+    R4000allegrexVt4444Q = 673,
+    R4000allegrexVt5551Q = 674,
+    R4000allegrexVt5650Q = 675,
+    R4000allegrexVcstS = 676,
+    R4000allegrexVcstP = 677,
+    R4000allegrexVcstT = 678,
+    R4000allegrexVcstQ = 679,
+    R4000allegrexVf2inS = 680,
+    R4000allegrexVf2inP = 681,
+    R4000allegrexVf2inT = 682,
+    R4000allegrexVf2inQ = 683,
+    R4000allegrexVf2izS = 684,
+    R4000allegrexVf2izP = 685,
+    R4000allegrexVf2izT = 686,
+    R4000allegrexVf2izQ = 687,
+    R4000allegrexVf2iuS = 688,
+    R4000allegrexVf2iuP = 689,
+    R4000allegrexVf2iuT = 690,
+    R4000allegrexVf2iuQ = 691,
+    R4000allegrexVf2idS = 692,
+    R4000allegrexVf2idP = 693,
+    R4000allegrexVf2idT = 694,
+    R4000allegrexVf2idQ = 695,
+    R4000allegrexVi2fS = 696,
+    R4000allegrexVi2fP = 697,
+    R4000allegrexVi2fT = 698,
+    R4000allegrexVi2fQ = 699,
+    R4000allegrexVcmovtS = 700,
+    R4000allegrexVcmovtP = 701,
+    R4000allegrexVcmovtT = 702,
+    R4000allegrexVcmovtQ = 703,
+    R4000allegrexVcmovfS = 704,
+    R4000allegrexVcmovfP = 705,
+    R4000allegrexVcmovfT = 706,
+    R4000allegrexVcmovfQ = 707,
+    R4000allegrexLvlQ = 708,
+    R4000allegrexLvrQ = 709,
+    R4000allegrexVpfxs = 710,
+    R4000allegrexVpfxt = 711,
+    R4000allegrexVpfxd = 712,
+    R4000allegrexViimS = 713,
+    R4000allegrexVfimS = 714,
+    R4000allegrexVmmulP = 715,
+    R4000allegrexVmmulT = 716,
+    R4000allegrexVmmulQ = 717,
+    R4000allegrexVhtfm2P = 718,
+    R4000allegrexVtfm2P = 719,
+    R4000allegrexVhtfm3T = 720,
+    R4000allegrexVtfm3T = 721,
+    R4000allegrexVhtfm4Q = 722,
+    R4000allegrexVtfm4Q = 723,
+    R4000allegrexVmsclP = 724,
+    R4000allegrexVmsclT = 725,
+    R4000allegrexVmsclQ = 726,
+    R4000allegrexVcrspT = 727,
+    R4000allegrexVqmulQ = 728,
+    R4000allegrexVrotP = 729,
+    R4000allegrexVrotT = 730,
+    R4000allegrexVrotQ = 731,
+    R4000allegrexVmmovP = 732,
+    R4000allegrexVmmovT = 733,
+    R4000allegrexVmmovQ = 734,
+    R4000allegrexVmidtP = 735,
+    R4000allegrexVmidtT = 736,
+    R4000allegrexVmidtQ = 737,
+    R4000allegrexVmzeroP = 738,
+    R4000allegrexVmzeroT = 739,
+    R4000allegrexVmzeroQ = 740,
+    R4000allegrexVmoneP = 741,
+    R4000allegrexVmoneT = 742,
+    R4000allegrexVmoneQ = 743,
+    R4000allegrexVnop = 744,
+    R4000allegrexVsync = 745,
+    R4000allegrexVflush = 746,
+    R4000allegrexSvlQ = 747,
+    R4000allegrexSvrQ = 748,
+    R5900Lq = 771,
+    R5900Sq = 772,
+    R5900Lqc2 = 773,
+    R5900Sqc2 = 774,
+    R5900SyncP = 775,
+    R5900Mult = 776,
+    R5900Mfsa = 777,
+    R5900Mtsa = 778,
+    R5900Mtsab = 779,
+    R5900Mtsah = 780,
+    R5900Madd = 781,
+    R5900Maddu = 782,
+    R5900Plzcw = 783,
+    R5900Mfhi1 = 784,
+    R5900Mthi1 = 785,
+    R5900Mflo1 = 786,
+    R5900Mtlo1 = 787,
+    R5900Mult1 = 788,
+    R5900Multu1 = 789,
+    R5900Div1 = 790,
+    R5900Divu1 = 791,
+    R5900Madd1 = 792,
+    R5900Maddu1 = 793,
+    R5900Psllh = 794,
+    R5900Psrlh = 795,
+    R5900Psrah = 796,
+    R5900Psllw = 797,
+    R5900Psrlw = 798,
+    R5900Psraw = 799,
+    R5900Paddw = 800,
+    R5900Psubw = 801,
+    R5900Pcgtw = 802,
+    R5900Pmaxw = 803,
+    R5900Paddh = 804,
+    R5900Psubh = 805,
+    R5900Pcgth = 806,
+    R5900Pmaxh = 807,
+    R5900Paddb = 808,
+    R5900Psubb = 809,
+    R5900Pcgtb = 810,
+    R5900Paddsw = 811,
+    R5900Psubsw = 812,
+    R5900Pextlw = 813,
+    R5900Ppacw = 814,
+    R5900Paddsh = 815,
+    R5900Psubsh = 816,
+    R5900Pextlh = 817,
+    R5900Ppach = 818,
+    R5900Paddsb = 819,
+    R5900Psubsb = 820,
+    R5900Pextlb = 821,
+    R5900Ppacb = 822,
+    R5900Pext5 = 823,
+    R5900Ppac5 = 824,
+    R5900Pabsw = 825,
+    R5900Pceqw = 826,
+    R5900Pminw = 827,
+    R5900Padsbh = 828,
+    R5900Pabsh = 829,
+    R5900Pceqh = 830,
+    R5900Pminh = 831,
+    R5900Pceqb = 832,
+    R5900Padduw = 833,
+    R5900Psubuw = 834,
+    R5900Pextuw = 835,
+    R5900Padduh = 836,
+    R5900Psubuh = 837,
+    R5900Pextuh = 838,
+    R5900Paddub = 839,
+    R5900Psubub = 840,
+    R5900Pextub = 841,
+    R5900Qfsrv = 842,
+    R5900Pmaddw = 843,
+    R5900Psllvw = 844,
+    R5900Psrlvw = 845,
+    R5900Pmsubw = 846,
+    R5900Pmfhi = 847,
+    R5900Pmflo = 848,
+    R5900Pinth = 849,
+    R5900Pmultw = 850,
+    R5900Pdivw = 851,
+    R5900Pcpyld = 852,
+    R5900Pmaddh = 853,
+    R5900Phmadh = 854,
+    R5900Pand = 855,
+    R5900Pxor = 856,
+    R5900Pmsubh = 857,
+    R5900Phmsbh = 858,
+    R5900Pexeh = 859,
+    R5900Prevh = 860,
+    R5900Pmulth = 861,
+    R5900Pdivbw = 862,
+    R5900Pexew = 863,
+    R5900Prot3w = 864,
+    R5900Pmadduw = 865,
+    R5900Psravw = 866,
+    R5900Pmthi = 867,
+    R5900Pmtlo = 868,
+    R5900Pinteh = 869,
+    R5900Pmultuw = 870,
+    R5900Pdivuw = 871,
+    R5900Pcpyud = 872,
+    R5900Por = 873,
+    R5900Pnor = 874,
+    R5900Pexch = 875,
+    R5900Pcpyh = 876,
+    R5900Pexcw = 877,
+    R5900PmfhlLw = 878,
+    R5900PmfhlUw = 879,
+    R5900PmfhlSlw = 880,
+    R5900PmfhlLh = 881,
+    R5900PmfhlSh = 882,
+    R5900PmthlLw = 883,
+    R5900Ei = 884,
+    R5900Di = 885,
+    R5900C1SqrtS = 886,
+    R5900RsqrtS = 887,
+    R5900AddaS = 888,
+    R5900SubaS = 889,
+    R5900MulaS = 890,
+    R5900MaddS = 891,
+    R5900MsubS = 892,
+    R5900MaddaS = 893,
+    R5900MsubaS = 894,
+    R5900MaxS = 895,
+    R5900MinS = 896,
+    R5900CLtS = 897,
+    R5900CLeS = 898,
+    R5900Qmfc2Ni = 899,
+    R5900Cfc2Ni = 900,
+    R5900Qmtc2Ni = 901,
+    R5900Ctc2Ni = 902,
+    R5900Qmfc2I = 903,
+    R5900Cfc2I = 904,
+    R5900Qmtc2I = 905,
+    R5900Ctc2I = 906,
+    R5900Bc2f = 907,
+    R5900Bc2t = 908,
+    R5900Bc2fl = 909,
+    R5900Bc2tl = 910,
+    R5900Vaddx = 911,
+    R5900Vaddy = 912,
+    R5900Vaddz = 913,
+    R5900Vaddw = 914,
+    R5900Vsubx = 915,
+    R5900Vsuby = 916,
+    R5900Vsubz = 917,
+    R5900Vsubw = 918,
+    R5900Vmaddx = 919,
+    R5900Vmaddy = 920,
+    R5900Vmaddz = 921,
+    R5900Vmaddw = 922,
+    R5900Vmsubx = 923,
+    R5900Vmsuby = 924,
+    R5900Vmsubz = 925,
+    R5900Vmsubw = 926,
+    R5900Vmaxx = 927,
+    R5900Vmaxy = 928,
+    R5900Vmaxz = 929,
+    R5900Vmaxw = 930,
+    R5900Vminix = 931,
+    R5900Vminiy = 932,
+    R5900Vminiz = 933,
+    R5900Vminiw = 934,
+    R5900Vmulx = 935,
+    R5900Vmuly = 936,
+    R5900Vmulz = 937,
+    R5900Vmulw = 938,
+    R5900Vmulq = 939,
+    R5900Vmaxi = 940,
+    R5900Vmuli = 941,
+    R5900Vminii = 942,
+    R5900Vaddq = 943,
+    R5900Vmaddq = 944,
+    R5900Vaddi = 945,
+    R5900Vmaddi = 946,
+    R5900Vsubq = 947,
+    R5900Vmsubq = 948,
+    R5900Vsubi = 949,
+    R5900Vmsubi = 950,
+    R5900Vadd = 951,
+    R5900Vmadd = 952,
+    R5900Vmul = 953,
+    R5900Vmax = 954,
+    R5900Vsub = 955,
+    R5900Vmsub = 956,
+    R5900Vopmsub = 957,
+    R5900Vmini = 958,
+    R5900Viadd = 959,
+    R5900Visub = 960,
+    R5900Viaddi = 961,
+    R5900Viand = 962,
+    R5900Vior = 963,
+    R5900Vcallms = 964,
+    R5900Vcallmsr = 965,
+    R5900Vaddax = 966,
+    R5900Vadday = 967,
+    R5900Vaddaz = 968,
+    R5900Vaddaw = 969,
+    R5900Vsubax = 970,
+    R5900Vsubay = 971,
+    R5900Vsubaz = 972,
+    R5900Vsubaw = 973,
+    R5900Vmaddax = 974,
+    R5900Vmadday = 975,
+    R5900Vmaddaz = 976,
+    R5900Vmaddaw = 977,
+    R5900Vmsubax = 978,
+    R5900Vmsubay = 979,
+    R5900Vmsubaz = 980,
+    R5900Vmsubaw = 981,
+    R5900Vitof0 = 982,
+    R5900Vitof4 = 983,
+    R5900Vitof12 = 984,
+    R5900Vitof15 = 985,
+    R5900Vftoi0 = 986,
+    R5900Vftoi4 = 987,
+    R5900Vftoi12 = 988,
+    R5900Vftoi15 = 989,
+    R5900Vmulax = 990,
+    R5900Vmulay = 991,
+    R5900Vmulaz = 992,
+    R5900Vmulaw = 993,
+    R5900Vmulaq = 994,
+    R5900Vabs = 995,
+    R5900Vmulai = 996,
+    R5900Vclipw = 997,
+    R5900Vaddaq = 998,
+    R5900Vmaddaq = 999,
+    R5900Vaddai = 1000,
+    R5900Vmaddai = 1001,
+    R5900Vsubaq = 1002,
+    R5900Vmsubaq = 1003,
+    R5900Vsubai = 1004,
+    R5900Vmsubai = 1005,
+    R5900Vadda = 1006,
+    R5900Vmadda = 1007,
+    R5900Vmula = 1008,
+    R5900Vsuba = 1009,
+    R5900Vmsuba = 1010,
+    R5900Vopmula = 1011,
+    R5900Vnop = 1012,
+    R5900Vmove = 1013,
+    R5900Vmr32 = 1014,
+    R5900Vlqi = 1015,
+    R5900Vsqi = 1016,
+    R5900Vlqd = 1017,
+    R5900Vsqd = 1018,
+    R5900Vdiv = 1019,
+    R5900Vsqrt = 1020,
+    R5900Vrsqrt = 1021,
+    R5900Vwaitq = 1022,
+    R5900Vmtir = 1023,
+    R5900Vmfir = 1024,
+    R5900Vrnext = 1025,
+    R5900Vrget = 1026,
+    R5900Vrinit = 1027,
+    R5900Vrxor = 1028,
+    R5900VilwrW = 1029,
+    R5900VilwrZ = 1030,
+    R5900VilwrY = 1031,
+    R5900VilwrX = 1032,
+    R5900ViswrW = 1033,
+    R5900ViswrZ = 1034,
+    R5900ViswrY = 1035,
+    R5900ViswrX = 1036,
+}
+
+impl InstrId {
+    /// The variant of a rabbitizer ID, or `None` for the placeholders
+    pub(crate) fn from_rabbitizer(id: rabbitizer::InstrId) -> Option<Self> {
+        use rabbitizer::InstrId as Id;
+
+        Some(match id {
+            Id::cpu_j => InstrId::CpuJ,
+            Id::cpu_jal => InstrId::CpuJal,
+            Id::cpu_beq => InstrId::CpuBeq,
+            Id::cpu_bne => InstrId::CpuBne,
+            Id::cpu_beql => InstrId::CpuBeql,
+            Id::cpu_bnel => InstrId::CpuBnel,
+            Id::cpu_blez => InstrId::CpuBlez,
+            Id::cpu_blezl => InstrId::CpuBlezl,
+            Id::cpu_bgtz => InstrId::CpuBgtz,
+            Id::cpu_bgtzl => InstrId::CpuBgtzl,
+            Id::cpu_addi => InstrId::CpuAddi,
+            Id::cpu_addiu => InstrId::CpuAddiu,
+            Id::cpu_slti => InstrId::CpuSlti,
+            Id::cpu_sltiu => InstrId::CpuSltiu,
+            Id::cpu_andi => InstrId::CpuAndi,
+            Id::cpu_ori => InstrId::CpuOri,
+            Id::cpu_xori => InstrId::CpuXori,
+            Id::cpu_daddi => InstrId::CpuDaddi,
+            Id::cpu_daddiu => InstrId::CpuDaddiu,
+            Id::cpu_lui => InstrId::CpuLui,
+            Id::cpu_ldl => InstrId::CpuLdl,
+            Id::cpu_ldr => InstrId::CpuLdr,
+            Id::cpu_lb => InstrId::CpuLb,
+            Id::cpu_lh => InstrId::CpuLh,
+            Id::cpu_lwl => InstrId::CpuLwl,
+            Id::cpu_lw => InstrId::CpuLw,
+            Id::cpu_lbu => InstrId::CpuLbu,
+            Id::cpu_lhu => InstrId::CpuLhu,
+            Id::cpu_lwr => InstrId::CpuLwr,
+            Id::cpu_lwu => InstrId::CpuLwu,
+            Id::cpu_sb => InstrId::CpuSb,
+            Id::cpu_sh => InstrId::CpuSh,
+            Id::cpu_swl => InstrId::CpuSwl,
+            Id::cpu_sw => InstrId::CpuSw,
+            Id::cpu_sdl => InstrId::CpuSdl,
+            Id::cpu_sdr => InstrId::CpuSdr,
+            Id::cpu_swr => InstrId::CpuSwr,
+            Id::cpu_ll => InstrId::CpuLl,
+            Id::cpu_pref => InstrId::CpuPref,
+            Id::cpu_lld => InstrId::CpuLld,
+            Id::cpu_ld => InstrId::CpuLd,
+            Id::cpu_sc => InstrId::CpuSc,
+            Id::cpu_scd => InstrId::CpuScd,
+            Id::cpu_sd => InstrId::CpuSd,
+            Id::cpu_cache => InstrId::CpuCache,
+            Id::cpu_lwc1 => InstrId::CpuLwc1,
+            Id::cpu_ldc1 => InstrId::CpuLdc1,
+            Id::cpu_swc1 => InstrId::CpuSwc1,
+            Id::cpu_sdc1 => InstrId::CpuSdc1,
+            Id::cpu_lwc2 => InstrId::CpuLwc2,
+            Id::cpu_ldc2 => InstrId::CpuLdc2,
+            Id::cpu_swc2 => InstrId::CpuSwc2,
+            Id::cpu_sdc2 => InstrId::CpuSdc2,
+            Id::cpu_b => InstrId::CpuB,
+            Id::cpu_beqz => InstrId::CpuBeqz,
+            Id::cpu_bnez => InstrId::CpuBnez,
+            Id::cpu_sll => InstrId::CpuSll,
+            Id::cpu_srl => InstrId::CpuSrl,
+            Id::cpu_sra => InstrId::CpuSra,
+            Id::cpu_dsll => InstrId::CpuDsll,
+            Id::cpu_dsrl => InstrId::CpuDsrl,
+            Id::cpu_dsra => InstrId::CpuDsra,
+            Id::cpu_dsll32 => InstrId::CpuDsll32,
+            Id::cpu_dsrl32 => InstrId::CpuDsrl32,
+            Id::cpu_dsra32 => InstrId::CpuDsra32,
+            Id::cpu_dsllv => InstrId::CpuDsllv,
+            Id::cpu_dsrlv => InstrId::CpuDsrlv,
+            Id::cpu_dsrav => InstrId::CpuDsrav,
+            Id::cpu_sllv => InstrId::CpuSllv,
+            Id::cpu_srlv => InstrId::CpuSrlv,
+            Id::cpu_srav => InstrId::CpuSrav,
+            Id::cpu_mthi => InstrId::CpuMthi,
+            Id::cpu_mtlo => InstrId::CpuMtlo,
+            Id::cpu_jr => InstrId::CpuJr,
+            Id::cpu_jalr => InstrId::CpuJalr,
+            Id::cpu_mfhi => InstrId::CpuMfhi,
+            Id::cpu_mflo => InstrId::CpuMflo,
+            Id::cpu_movz => InstrId::CpuMovz,
+            Id::cpu_movn => InstrId::CpuMovn,
+            Id::cpu_div => InstrId::CpuDiv,
+            Id::cpu_divu => InstrId::CpuDivu,
+            Id::cpu_sn64_div => InstrId::CpuSn64Div,
+            Id::cpu_sn64_divu => InstrId::CpuSn64Divu,
+            Id::cpu_ddiv => InstrId::CpuDdiv,
+            Id::cpu_ddivu => InstrId::CpuDdivu,
+            Id::cpu_add => InstrId::CpuAdd,
+            Id::cpu_addu => InstrId::CpuAddu,
+            Id::cpu_sub => InstrId::CpuSub,
+            Id::cpu_subu => InstrId::CpuSubu,
+            Id::cpu_and => InstrId::CpuAnd,
+            Id::cpu_or => InstrId::CpuOr,
+            Id::cpu_xor => InstrId::CpuXor,
+            Id::cpu_nor => InstrId::CpuNor,
+            Id::cpu_slt => InstrId::CpuSlt,
+            Id::cpu_sltu => InstrId::CpuSltu,
+            Id::cpu_dadd => InstrId::CpuDadd,
+            Id::cpu_daddu => InstrId::CpuDaddu,
+            Id::cpu_dsub => InstrId::CpuDsub,
+            Id::cpu_dsubu => InstrId::CpuDsubu,
+            Id::cpu_syscall => InstrId::CpuSyscall,
+            Id::cpu_break => InstrId::CpuBreak,
+            Id::cpu_sync => InstrId::CpuSync,
+            Id::cpu_mult => InstrId::CpuMult,
+            Id::cpu_multu => InstrId::CpuMultu,
+            Id::cpu_dmult => InstrId::CpuDmult,
+            Id::cpu_dmultu => InstrId::CpuDmultu,
+            Id::cpu_tge => InstrId::CpuTge,
+            Id::cpu_tgeu => InstrId::CpuTgeu,
+            Id::cpu_tlt => InstrId::CpuTlt,
+            Id::cpu_tltu => InstrId::CpuTltu,
+            Id::cpu_teq => InstrId::CpuTeq,
+            Id::cpu_tne => InstrId::CpuTne,
+            Id::cpu_nop => InstrId::CpuNop,
+            Id::cpu_move => InstrId::CpuMove,
+            Id::cpu_not => InstrId::CpuNot,
+            Id::cpu_neg => InstrId::CpuNeg,
+            Id::cpu_negu => InstrId::CpuNegu,
+            Id::cpu_bltz => InstrId::CpuBltz,
+            Id::cpu_bgez => InstrId::CpuBgez,
+            Id::cpu_bltzl => InstrId::CpuBltzl,
+            Id::cpu_bgezl => InstrId::CpuBgezl,
+            Id::cpu_tgei => InstrId::CpuTgei,
+            Id::cpu_tgeiu => InstrId::CpuTgeiu,
+            Id::cpu_tlti => InstrId::CpuTlti,
+            Id::cpu_tltiu => InstrId::CpuTltiu,
+            Id::cpu_teqi => InstrId::CpuTeqi,
+            Id::cpu_tnei => InstrId::CpuTnei,
+            Id::cpu_bltzal => InstrId::CpuBltzal,
+            Id::cpu_bgezal => InstrId::CpuBgezal,
+            Id::cpu_bltzall => InstrId::CpuBltzall,
+            Id::cpu_bgezall => InstrId::CpuBgezall,
+            Id::cpu_bal => InstrId::CpuBal,
+            Id::cpu_mfc0 => InstrId::CpuMfc0,
+            Id::cpu_dmfc0 => InstrId::CpuDmfc0,
+            Id::cpu_cfc0 => InstrId::CpuCfc0,
+            Id::cpu_mtc0 => InstrId::CpuMtc0,
+            Id::cpu_dmtc0 => InstrId::CpuDmtc0,
+            Id::cpu_ctc0 => InstrId::CpuCtc0,
+            Id::cpu_bc0f => InstrId::CpuBc0f,
+            Id::cpu_bc0t => InstrId::CpuBc0t,
+            Id::cpu_bc0fl => InstrId::CpuBc0fl,
+            Id::cpu_bc0tl => InstrId::CpuBc0tl,
+            Id::cpu_tlbr => InstrId::CpuTlbr,
+            Id::cpu_tlbwi => InstrId::CpuTlbwi,
+            Id::cpu_tlbwr => InstrId::CpuTlbwr,
+            Id::cpu_tlbp => InstrId::CpuTlbp,
+            Id::cpu_rfe => InstrId::CpuRfe,
+            Id::cpu_eret => InstrId::CpuEret,
+            Id::cpu_mfc1 => InstrId::CpuMfc1,
+            Id::cpu_dmfc1 => InstrId::CpuDmfc1,
+            Id::cpu_mtc1 => InstrId::CpuMtc1,
+            Id::cpu_dmtc1 => InstrId::CpuDmtc1,
+            Id::cpu_cfc1 => InstrId::CpuCfc1,
+            Id::cpu_ctc1 => InstrId::CpuCtc1,
+            Id::cpu_bc1f => InstrId::CpuBc1f,
+            Id::cpu_bc1t => InstrId::CpuBc1t,
+            Id::cpu_bc1fl => InstrId::CpuBc1fl,
+            Id::cpu_bc1tl => InstrId::CpuBc1tl,
+            Id::cpu_add_s => InstrId::CpuAddS,
+            Id::cpu_sub_s => InstrId::CpuSubS,
+            Id::cpu_mul_s => InstrId::CpuMulS,
+            Id::cpu_div_s => InstrId::CpuDivS,
+            Id::cpu_sqrt_s => InstrId::CpuSqrtS,
+            Id::cpu_abs_s => InstrId::CpuAbsS,
+            Id::cpu_mov_s => InstrId::CpuMovS,
+            Id::cpu_neg_s => InstrId::CpuNegS,
+            Id::cpu_round_l_s => InstrId::CpuRoundLS,
+            Id::cpu_trunc_l_s => InstrId::CpuTruncLS,
+            Id::cpu_ceil_l_s => InstrId::CpuCeilLS,
+            Id::cpu_floor_l_s => InstrId::CpuFloorLS,
+            Id::cpu_round_w_s => InstrId::CpuRoundWS,
+            Id::cpu_trunc_w_s => InstrId::CpuTruncWS,
+            Id::cpu_ceil_w_s => InstrId::CpuCeilWS,
+            Id::cpu_floor_w_s => InstrId::CpuFloorWS,
+            Id::cpu_cvt_d_s => InstrId::CpuCvtDS,
+            Id::cpu_cvt_w_s => InstrId::CpuCvtWS,
+            Id::cpu_cvt_l_s => InstrId::CpuCvtLS,
+            Id::cpu_c_f_s => InstrId::CpuCFS,
+            Id::cpu_c_un_s => InstrId::CpuCUnS,
+            Id::cpu_c_eq_s => InstrId::CpuCEqS,
+            Id::cpu_c_ueq_s => InstrId::CpuCUeqS,
+            Id::cpu_c_olt_s => InstrId::CpuCOltS,
+            Id::cpu_c_ult_s => InstrId::CpuCUltS,
+            Id::cpu_c_ole_s => InstrId::CpuCOleS,
+            Id::cpu_c_ule_s => InstrId::CpuCUleS,
+            Id::cpu_c_sf_s => InstrId::CpuCSfS,
+            Id::cpu_c_ngle_s => InstrId::CpuCNgleS,
+            Id::cpu_c_seq_s => InstrId::CpuCSeqS,
+            Id::cpu_c_ngl_s => InstrId::CpuCNglS,
+            Id::cpu_c_lt_s => InstrId::CpuCLtS,
+            Id::cpu_c_nge_s => InstrId::CpuCNgeS,
+            Id::cpu_c_le_s => InstrId::CpuCLeS,
+            Id::cpu_c_ngt_s => InstrId::CpuCNgtS,
+            Id::cpu_add_d => InstrId::CpuAddD,
+            Id::cpu_sub_d => InstrId::CpuSubD,
+            Id::cpu_mul_d => InstrId::CpuMulD,
+            Id::cpu_div_d => InstrId::CpuDivD,
+            Id::cpu_sqrt_d => InstrId::CpuSqrtD,
+            Id::cpu_abs_d => InstrId::CpuAbsD,
+            Id::cpu_mov_d => InstrId::CpuMovD,
+            Id::cpu_neg_d => InstrId::CpuNegD,
+            Id::cpu_round_l_d => InstrId::CpuRoundLD,
+            Id::cpu_trunc_l_d => InstrId::CpuTruncLD,
+            Id::cpu_ceil_l_d => InstrId::CpuCeilLD,
+            Id::cpu_floor_l_d => InstrId::CpuFloorLD,
+            Id::cpu_round_w_d => InstrId::CpuRoundWD,
+            Id::cpu_trunc_w_d => InstrId::CpuTruncWD,
+            Id::cpu_ceil_w_d => InstrId::CpuCeilWD,
+            Id::cpu_floor_w_d => InstrId::CpuFloorWD,
+            Id::cpu_cvt_s_d => InstrId::CpuCvtSD,
+            Id::cpu_cvt_w_d => InstrId::CpuCvtWD,
+            Id::cpu_cvt_l_d => InstrId::CpuCvtLD,
+            Id::cpu_c_f_d => InstrId::CpuCFD,
+            Id::cpu_c_un_d => InstrId::CpuCUnD,
+            Id::cpu_c_eq_d => InstrId::CpuCEqD,
+            Id::cpu_c_ueq_d => InstrId::CpuCUeqD,
+            Id::cpu_c_olt_d => InstrId::CpuCOltD,
+            Id::cpu_c_ult_d => InstrId::CpuCUltD,
+            Id::cpu_c_ole_d => InstrId::CpuCOleD,
+            Id::cpu_c_ule_d => InstrId::CpuCUleD,
+            Id::cpu_c_df_d => InstrId::CpuCDfD,
+            Id::cpu_c_ngle_d => InstrId::CpuCNgleD,
+            Id::cpu_c_seq_d => InstrId::CpuCSeqD,
+            Id::cpu_c_ngl_d => InstrId::CpuCNglD,
+            Id::cpu_c_lt_d => InstrId::CpuCLtD,
+            Id::cpu_c_nge_d => InstrId::CpuCNgeD,
+            Id::cpu_c_le_d => InstrId::CpuCLeD,
+            Id::cpu_c_ngt_d => InstrId::CpuCNgtD,
+            Id::cpu_cvt_s_w => InstrId::CpuCvtSW,
+            Id::cpu_cvt_d_w => InstrId::CpuCvtDW,
+            Id::cpu_cvt_s_l => InstrId::CpuCvtSL,
+            Id::cpu_cvt_d_l => InstrId::CpuCvtDL,
+            Id::cpu_mfc2 => InstrId::CpuMfc2,
+            Id::cpu_mtc2 => InstrId::CpuMtc2,
+            Id::cpu_cfc2 => InstrId::CpuCfc2,
+            Id::cpu_ctc2 => InstrId::CpuCtc2,
+            Id::rsp_mfc2 => InstrId::RspMfc2,
+            Id::rsp_mtc2 => InstrId::RspMtc2,
+            Id::rsp_cfc2 => InstrId::RspCfc2,
+            Id::rsp_ctc2 => InstrId::RspCtc2,
+            Id::rsp_vmulf => InstrId::RspVmulf,
+            Id::rsp_vmulu => InstrId::RspVmulu,
+            Id::rsp_vrndp => InstrId::RspVrndp,
+            Id::rsp_vmulq => InstrId::RspVmulq,
+            Id::rsp_vmudl => InstrId::RspVmudl,
+            Id::rsp_vmudm => InstrId::RspVmudm,
+            Id::rsp_vmudn => InstrId::RspVmudn,
+            Id::rsp_vmudh => InstrId::RspVmudh,
+            Id::rsp_vmacf => InstrId::RspVmacf,
+            Id::rsp_vmacu => InstrId::RspVmacu,
+            Id::rsp_vrndn => InstrId::RspVrndn,
+            Id::rsp_vmacq => InstrId::RspVmacq,
+            Id::rsp_vmadl => InstrId::RspVmadl,
+            Id::rsp_vmadm => InstrId::RspVmadm,
+            Id::rsp_vmadn => InstrId::RspVmadn,
+            Id::rsp_vmadh => InstrId::RspVmadh,
+            Id::rsp_vadd => InstrId::RspVadd,
+            Id::rsp_vsub => InstrId::RspVsub,
+            Id::rsp_vabs => InstrId::RspVabs,
+            Id::rsp_vaddc => InstrId::RspVaddc,
+            Id::rsp_vsubc => InstrId::RspVsubc,
+            Id::rsp_vsar => InstrId::RspVsar,
+            Id::rsp_vand => InstrId::RspVand,
+            Id::rsp_vnand => InstrId::RspVnand,
+            Id::rsp_vor => InstrId::RspVor,
+            Id::rsp_vnor => InstrId::RspVnor,
+            Id::rsp_vxor => InstrId::RspVxor,
+            Id::rsp_vnxor => InstrId::RspVnxor,
+            Id::rsp_vlt => InstrId::RspVlt,
+            Id::rsp_veq => InstrId::RspVeq,
+            Id::rsp_vne => InstrId::RspVne,
+            Id::rsp_vge => InstrId::RspVge,
+            Id::rsp_vcl => InstrId::RspVcl,
+            Id::rsp_vch => InstrId::RspVch,
+            Id::rsp_vcr => InstrId::RspVcr,
+            Id::rsp_vmrg => InstrId::RspVmrg,
+            Id::rsp_vrcp => InstrId::RspVrcp,
+            Id::rsp_vrcpl => InstrId::RspVrcpl,
+            Id::rsp_vrcph => InstrId::RspVrcph,
+            Id::rsp_vmov => InstrId::RspVmov,
+            Id::rsp_vrsq => InstrId::RspVrsq,
+            Id::rsp_vrsql => InstrId::RspVrsql,
+            Id::rsp_vrsqh => InstrId::RspVrsqh,
+            Id::rsp_vnop => InstrId::RspVnop,
+            Id::rsp_lbv => InstrId::RspLbv,
+            Id::rsp_lsv => InstrId::RspLsv,
+            Id::rsp_llv => InstrId::RspLlv,
+            Id::rsp_ldv => InstrId::RspLdv,
+            Id::rsp_lqv => InstrId::RspLqv,
+            Id::rsp_lrv => InstrId::RspLrv,
+            Id::rsp_lpv => InstrId::RspLpv,
+            Id::rsp_luv => InstrId::RspLuv,
+            Id::rsp_lhv => InstrId::RspLhv,
+            Id::rsp_lfv => InstrId::RspLfv,
+            Id::rsp_ltv => InstrId::RspLtv,
+            Id::rsp_sbv => InstrId::RspSbv,
+            Id::rsp_ssv => InstrId::RspSsv,
+            Id::rsp_slv => InstrId::RspSlv,
+            Id::rsp_sdv => InstrId::RspSdv,
+            Id::rsp_sqv => InstrId::RspSqv,
+            Id::rsp_srv => InstrId::RspSrv,
+            Id::rsp_spv => InstrId::RspSpv,
+            Id::rsp_suv => InstrId::RspSuv,
+            Id::rsp_shv => InstrId::RspShv,
+            Id::rsp_sfv => InstrId::RspSfv,
+            Id::rsp_stv => InstrId::RspStv,
+            Id::rsp_swv => InstrId::RspSwv,
+            Id::rsp_j => InstrId::RspJ,
+            Id::rsp_jal => InstrId::RspJal,
+            Id::rsp_beq => InstrId::RspBeq,
+            Id::rsp_bne => InstrId::RspBne,
+            Id::rsp_blez => InstrId::RspBlez,
+            Id::rsp_bgtz => InstrId::RspBgtz,
+            Id::rsp_addi => InstrId::RspAddi,
+            Id::rsp_addiu => InstrId::RspAddiu,
+            Id::rsp_slti => InstrId::RspSlti,
+            Id::rsp_sltiu => InstrId::RspSltiu,
+            Id::rsp_andi => InstrId::RspAndi,
+            Id::rsp_ori => InstrId::RspOri,
+            Id::rsp_xori => InstrId::RspXori,
+            Id::rsp_lui => InstrId::RspLui,
+            Id::rsp_lb => InstrId::RspLb,
+            Id::rsp_lh => InstrId::RspLh,
+            Id::rsp_lw => InstrId::RspLw,
+            Id::rsp_lbu => InstrId::RspLbu,
+            Id::rsp_lhu => InstrId::RspLhu,
+            Id::rsp_sb => InstrId::RspSb,
+            Id::rsp_sh => InstrId::RspSh,
+            Id::rsp_sw => InstrId::RspSw,
+            Id::rsp_pref => InstrId::RspPref,
+            Id::rsp_b => InstrId::RspB,
+            Id::rsp_beqz => InstrId::RspBeqz,
+            Id::rsp_bnez => InstrId::RspBnez,
+            Id::rsp_sll => InstrId::RspSll,
+            Id::rsp_srl => InstrId::RspSrl,
+            Id::rsp_sra => InstrId::RspSra,
+            Id::rsp_sllv => InstrId::RspSllv,
+            Id::rsp_srlv => InstrId::RspSrlv,
+            Id::rsp_srav => InstrId::RspSrav,
+            Id::rsp_jr => InstrId::RspJr,
+            Id::rsp_jalr => InstrId::RspJalr,
+            Id::rsp_movz => InstrId::RspMovz,
+            Id::rsp_movn => InstrId::RspMovn,
+            Id::rsp_add => InstrId::RspAdd,
+            Id::rsp_addu => InstrId::RspAddu,
+            Id::rsp_sub => InstrId::RspSub,
+            Id::rsp_subu => InstrId::RspSubu,
+            Id::rsp_and => InstrId::RspAnd,
+            Id::rsp_or => InstrId::RspOr,
+            Id::rsp_xor => InstrId::RspXor,
+            Id::rsp_nor => InstrId::RspNor,
+            Id::rsp_slt => InstrId::RspSlt,
+            Id::rsp_sltu => InstrId::RspSltu,
+            Id::rsp_break => InstrId::RspBreak,
+            Id::rsp_nop => InstrId::RspNop,
+            Id::rsp_move => InstrId::RspMove,
+            Id::rsp_not => InstrId::RspNot,
+            Id::rsp_neg => InstrId::RspNeg,
+            Id::rsp_negu => InstrId::RspNegu,
+            Id::rsp_bltz => InstrId::RspBltz,
+            Id::rsp_bgez => InstrId::RspBgez,
+            Id::rsp_bltzal => InstrId::RspBltzal,
+            Id::rsp_bgezal => InstrId::RspBgezal,
+            Id::rsp_bal => InstrId::RspBal,
+            Id::rsp_mfc0 => InstrId::RspMfc0,
+            Id::rsp_mtc0 => InstrId::RspMtc0,
+            Id::r3000gte_rtps => InstrId::R3000gteRtps,
+            Id::r3000gte_rtpt => InstrId::R3000gteRtpt,
+            Id::r3000gte_dpcl => InstrId::R3000gteDpcl,
+            Id::r3000gte_dpcs => InstrId::R3000gteDpcs,
+            Id::r3000gte_dpct => InstrId::R3000gteDpct,
+            Id::r3000gte_intpl => InstrId::R3000gteIntpl,
+            Id::r3000gte_ncs => InstrId::R3000gteNcs,
+            Id::r3000gte_nct => InstrId::R3000gteNct,
+            Id::r3000gte_ncds => InstrId::R3000gteNcds,
+            Id::r3000gte_ncdt => InstrId::R3000gteNcdt,
+            Id::r3000gte_nccs => InstrId::R3000gteNccs,
+            Id::r3000gte_ncct => InstrId::R3000gteNcct,
+            Id::r3000gte_cdp => InstrId::R3000gteCdp,
+            Id::r3000gte_cc => InstrId::R3000gteCc,
+            Id::r3000gte_nclip => InstrId::R3000gteNclip,
+            Id::r3000gte_avsz3 => InstrId::R3000gteAvsz3,
+            Id::r3000gte_avsz4 => InstrId::R3000gteAvsz4,
+            Id::r3000gte_mvmva => InstrId::R3000gteMvmva,
+            Id::r3000gte_sqr => InstrId::R3000gteSqr,
+            Id::r3000gte_op => InstrId::R3000gteOp,
+            Id::r3000gte_gpf => InstrId::R3000gteGpf,
+            Id::r3000gte_gpl => InstrId::R3000gteGpl,
+            Id::r4000allegrex_lv_s => InstrId::R4000allegrexLvS,
+            Id::r4000allegrex_sv_s => InstrId::R4000allegrexSvS,
+            Id::r4000allegrex_lv_q => InstrId::R4000allegrexLvQ,
+            Id::r4000allegrex_sv_q => InstrId::R4000allegrexSvQ,
+            Id::r4000allegrex_clz => InstrId::R4000allegrexClz,
+            Id::r4000allegrex_clo => InstrId::R4000allegrexClo,
+            Id::r4000allegrex_madd => InstrId::R4000allegrexMadd,
+            Id::r4000allegrex_maddu => InstrId::R4000allegrexMaddu,
+            Id::r4000allegrex_msub => InstrId::R4000allegrexMsub,
+            Id::r4000allegrex_msubu => InstrId::R4000allegrexMsubu,
+            Id::r4000allegrex_max => InstrId::R4000allegrexMax,
+            Id::r4000allegrex_min => InstrId::R4000allegrexMin,
+            Id::r4000allegrex_srl => InstrId::R4000allegrexSrl,
+            Id::r4000allegrex_rotr => InstrId::R4000allegrexRotr,
+            Id::r4000allegrex_srlv => InstrId::R4000allegrexSrlv,
+            Id::r4000allegrex_rotrv => InstrId::R4000allegrexRotrv,
+            Id::r4000allegrex_sleep => InstrId::R4000allegrexSleep,
+            Id::r4000allegrex_mfie => InstrId::R4000allegrexMfie,
+            Id::r4000allegrex_mtie => InstrId::R4000allegrexMtie,
+            Id::r4000allegrex_ext => InstrId::R4000allegrexExt,
+            Id::r4000allegrex_ins => InstrId::R4000allegrexIns,
+            Id::r4000allegrex_wsbh => InstrId::R4000allegrexWsbh,
+            Id::r4000allegrex_wsbw => InstrId::R4000allegrexWsbw,
+            Id::r4000allegrex_seb => InstrId::R4000allegrexSeb,
+            Id::r4000allegrex_seh => InstrId::R4000allegrexSeh,
+            Id::r4000allegrex_bitrev => InstrId::R4000allegrexBitrev,
+            Id::r4000allegrex_bvf => InstrId::R4000allegrexBvf,
+            Id::r4000allegrex_bvt => InstrId::R4000allegrexBvt,
+            Id::r4000allegrex_bvfl => InstrId::R4000allegrexBvfl,
+            Id::r4000allegrex_bvtl => InstrId::R4000allegrexBvtl,
+            Id::r4000allegrex_mfv => InstrId::R4000allegrexMfv,
+            Id::r4000allegrex_mfvc => InstrId::R4000allegrexMfvc,
+            Id::r4000allegrex_vsync2 => InstrId::R4000allegrexVsync2,
+            Id::r4000allegrex_mtv => InstrId::R4000allegrexMtv,
+            Id::r4000allegrex_mtvc => InstrId::R4000allegrexMtvc,
+            Id::r4000allegrex_vadd_s => InstrId::R4000allegrexVaddS,
+            Id::r4000allegrex_vadd_p => InstrId::R4000allegrexVaddP,
+            Id::r4000allegrex_vadd_t => InstrId::R4000allegrexVaddT,
+            Id::r4000allegrex_vadd_q => InstrId::R4000allegrexVaddQ,
+            Id::r4000allegrex_vsub_s => InstrId::R4000allegrexVsubS,
+            Id::r4000allegrex_vsub_p => InstrId::R4000allegrexVsubP,
+            Id::r4000allegrex_vsub_t => InstrId::R4000allegrexVsubT,
+            Id::r4000allegrex_vsub_q => InstrId::R4000allegrexVsubQ,
+            Id::r4000allegrex_vsbn_s => InstrId::R4000allegrexVsbnS,
+            Id::r4000allegrex_vdiv_s => InstrId::R4000allegrexVdivS,
+            Id::r4000allegrex_vdiv_p => InstrId::R4000allegrexVdivP,
+            Id::r4000allegrex_vdiv_t => InstrId::R4000allegrexVdivT,
+            Id::r4000allegrex_vdiv_q => InstrId::R4000allegrexVdivQ,
+            Id::r4000allegrex_vmul_s => InstrId::R4000allegrexVmulS,
+            Id::r4000allegrex_vmul_p => InstrId::R4000allegrexVmulP,
+            Id::r4000allegrex_vmul_t => InstrId::R4000allegrexVmulT,
+            Id::r4000allegrex_vmul_q => InstrId::R4000allegrexVmulQ,
+            Id::r4000allegrex_vdot_p => InstrId::R4000allegrexVdotP,
+            Id::r4000allegrex_vdot_t => InstrId::R4000allegrexVdotT,
+            Id::r4000allegrex_vdot_q => InstrId::R4000allegrexVdotQ,
+            Id::r4000allegrex_vscl_p => InstrId::R4000allegrexVsclP,
+            Id::r4000allegrex_vscl_t => InstrId::R4000allegrexVsclT,
+            Id::r4000allegrex_vscl_q => InstrId::R4000allegrexVsclQ,
+            Id::r4000allegrex_vhdp_p => InstrId::R4000allegrexVhdpP,
+            Id::r4000allegrex_vhdp_t => InstrId::R4000allegrexVhdpT,
+            Id::r4000allegrex_vhdp_q => InstrId::R4000allegrexVhdpQ,
+            Id::r4000allegrex_vcrs_t => InstrId::R4000allegrexVcrsT,
+            Id::r4000allegrex_vdet_p => InstrId::R4000allegrexVdetP,
+            Id::r4000allegrex_vcmp_s => InstrId::R4000allegrexVcmpS,
+            Id::r4000allegrex_vcmp_p => InstrId::R4000allegrexVcmpP,
+            Id::r4000allegrex_vcmp_t => InstrId::R4000allegrexVcmpT,
+            Id::r4000allegrex_vcmp_q => InstrId::R4000allegrexVcmpQ,
+            Id::r4000allegrex_vmin_s => InstrId::R4000allegrexVminS,
+            Id::r4000allegrex_vmin_p => InstrId::R4000allegrexVminP,
+            Id::r4000allegrex_vmin_t => InstrId::R4000allegrexVminT,
+            Id::r4000allegrex_vmin_q => InstrId::R4000allegrexVminQ,
+            Id::r4000allegrex_vmax_s => InstrId::R4000allegrexVmaxS,
+            Id::r4000allegrex_vmax_p => InstrId::R4000allegrexVmaxP,
+            Id::r4000allegrex_vmax_t => InstrId::R4000allegrexVmaxT,
+            Id::r4000allegrex_vmax_q => InstrId::R4000allegrexVmaxQ,
+            Id::r4000allegrex_vscmp_s => InstrId::R4000allegrexVscmpS,
+            Id::r4000allegrex_vscmp_p => InstrId::R4000allegrexVscmpP,
+            Id::r4000allegrex_vscmp_t => InstrId::R4000allegrexVscmpT,
+            Id::r4000allegrex_vscmp_q => InstrId::R4000allegrexVscmpQ,
+            Id::r4000allegrex_vsge_s => InstrId::R4000allegrexVsgeS,
+            Id::r4000allegrex_vsge_p => InstrId::R4000allegrexVsgeP,
+            Id::r4000allegrex_vsge_t => InstrId::R4000allegrexVsgeT,
+            Id::r4000allegrex_vsge_q => InstrId::R4000allegrexVsgeQ,
+            Id::r4000allegrex_vslt_s => InstrId::R4000allegrexVsltS,
+            Id::r4000allegrex_vslt_p => InstrId::R4000allegrexVsltP,
+            Id::r4000allegrex_vslt_t => InstrId::R4000allegrexVsltT,
+            Id::r4000allegrex_vslt_q => InstrId::R4000allegrexVsltQ,
+            Id::r4000allegrex_vwbn_s => InstrId::R4000allegrexVwbnS,
+            Id::r4000allegrex_vmov_s => InstrId::R4000allegrexVmovS,
+            Id::r4000allegrex_vmov_p => InstrId::R4000allegrexVmovP,
+            Id::r4000allegrex_vmov_t => InstrId::R4000allegrexVmovT,
+            Id::r4000allegrex_vmov_q => InstrId::R4000allegrexVmovQ,
+            Id::r4000allegrex_vabs_s => InstrId::R4000allegrexVabsS,
+            Id::r4000allegrex_vabs_p => InstrId::R4000allegrexVabsP,
+            Id::r4000allegrex_vabs_t => InstrId::R4000allegrexVabsT,
+            Id::r4000allegrex_vabs_q => InstrId::R4000allegrexVabsQ,
+            Id::r4000allegrex_vneg_s => InstrId::R4000allegrexVnegS,
+            Id::r4000allegrex_vneg_p => InstrId::R4000allegrexVnegP,
+            Id::r4000allegrex_vneg_t => InstrId::R4000allegrexVnegT,
+            Id::r4000allegrex_vneg_q => InstrId::R4000allegrexVnegQ,
+            Id::r4000allegrex_vidt_p => InstrId::R4000allegrexVidtP,
+            Id::r4000allegrex_vidt_q => InstrId::R4000allegrexVidtQ,
+            Id::r4000allegrex_vsat0_s => InstrId::R4000allegrexVsat0S,
+            Id::r4000allegrex_vsat0_p => InstrId::R4000allegrexVsat0P,
+            Id::r4000allegrex_vsat0_t => InstrId::R4000allegrexVsat0T,
+            Id::r4000allegrex_vsat0_q => InstrId::R4000allegrexVsat0Q,
+            Id::r4000allegrex_vsat1_s => InstrId::R4000allegrexVsat1S,
+            Id::r4000allegrex_vsat1_p => InstrId::R4000allegrexVsat1P,
+            Id::r4000allegrex_vsat1_t => InstrId::R4000allegrexVsat1T,
+            Id::r4000allegrex_vsat1_q => InstrId::R4000allegrexVsat1Q,
+            Id::r4000allegrex_vzero_s => InstrId::R4000allegrexVzeroS,
+            Id::r4000allegrex_vzero_p => InstrId::R4000allegrexVzeroP,
+            Id::r4000allegrex_vzero_t => InstrId::R4000allegrexVzeroT,
+            Id::r4000allegrex_vzero_q => InstrId::R4000allegrexVzeroQ,
+            Id::r4000allegrex_vone_s => InstrId::R4000allegrexVoneS,
+            Id::r4000allegrex_vone_p => InstrId::R4000allegrexVoneP,
+            Id::r4000allegrex_vone_t => InstrId::R4000allegrexVoneT,
+            Id::r4000allegrex_vone_q => InstrId::R4000allegrexVoneQ,
+            Id::r4000allegrex_vrcp_s => InstrId::R4000allegrexVrcpS,
+            Id::r4000allegrex_vrcp_p => InstrId::R4000allegrexVrcpP,
+            Id::r4000allegrex_vrcp_t => InstrId::R4000allegrexVrcpT,
+            Id::r4000allegrex_vrcp_q => InstrId::R4000allegrexVrcpQ,
+            Id::r4000allegrex_vrsq_s => InstrId::R4000allegrexVrsqS,
+            Id::r4000allegrex_vrsq_p => InstrId::R4000allegrexVrsqP,
+            Id::r4000allegrex_vrsq_t => InstrId::R4000allegrexVrsqT,
+            Id::r4000allegrex_vrsq_q => InstrId::R4000allegrexVrsqQ,
+            Id::r4000allegrex_vsin_s => InstrId::R4000allegrexVsinS,
+            Id::r4000allegrex_vsin_p => InstrId::R4000allegrexVsinP,
+            Id::r4000allegrex_vsin_t => InstrId::R4000allegrexVsinT,
+            Id::r4000allegrex_vsin_q => InstrId::R4000allegrexVsinQ,
+            Id::r4000allegrex_vcos_s => InstrId::R4000allegrexVcosS,
+            Id::r4000allegrex_vcos_p => InstrId::R4000allegrexVcosP,
+            Id::r4000allegrex_vcos_t => InstrId::R4000allegrexVcosT,
+            Id::r4000allegrex_vcos_q => InstrId::R4000allegrexVcosQ,
+            Id::r4000allegrex_vexp2_s => InstrId::R4000allegrexVexp2S,
+            Id::r4000allegrex_vexp2_p => InstrId::R4000allegrexVexp2P,
+            Id::r4000allegrex_vexp2_t => InstrId::R4000allegrexVexp2T,
+            Id::r4000allegrex_vexp2_q => InstrId::R4000allegrexVexp2Q,
+            Id::r4000allegrex_vlog2_s => InstrId::R4000allegrexVlog2S,
+            Id::r4000allegrex_vlog2_p => InstrId::R4000allegrexVlog2P,
+            Id::r4000allegrex_vlog2_t => InstrId::R4000allegrexVlog2T,
+            Id::r4000allegrex_vlog2_q => InstrId::R4000allegrexVlog2Q,
+            Id::r4000allegrex_vsqrt_s => InstrId::R4000allegrexVsqrtS,
+            Id::r4000allegrex_vsqrt_p => InstrId::R4000allegrexVsqrtP,
+            Id::r4000allegrex_vsqrt_t => InstrId::R4000allegrexVsqrtT,
+            Id::r4000allegrex_vsqrt_q => InstrId::R4000allegrexVsqrtQ,
+            Id::r4000allegrex_vasin_s => InstrId::R4000allegrexVasinS,
+            Id::r4000allegrex_vasin_p => InstrId::R4000allegrexVasinP,
+            Id::r4000allegrex_vasin_t => InstrId::R4000allegrexVasinT,
+            Id::r4000allegrex_vasin_q => InstrId::R4000allegrexVasinQ,
+            Id::r4000allegrex_vnrcp_s => InstrId::R4000allegrexVnrcpS,
+            Id::r4000allegrex_vnrcp_p => InstrId::R4000allegrexVnrcpP,
+            Id::r4000allegrex_vnrcp_t => InstrId::R4000allegrexVnrcpT,
+            Id::r4000allegrex_vnrcp_q => InstrId::R4000allegrexVnrcpQ,
+            Id::r4000allegrex_vnsin_s => InstrId::R4000allegrexVnsinS,
+            Id::r4000allegrex_vnsin_p => InstrId::R4000allegrexVnsinP,
+            Id::r4000allegrex_vnsin_t => InstrId::R4000allegrexVnsinT,
+            Id::r4000allegrex_vnsin_q => InstrId::R4000allegrexVnsinQ,
+            Id::r4000allegrex_vrexp2_s => InstrId::R4000allegrexVrexp2S,
+            Id::r4000allegrex_vrexp2_p => InstrId::R4000allegrexVrexp2P,
+            Id::r4000allegrex_vrexp2_t => InstrId::R4000allegrexVrexp2T,
+            Id::r4000allegrex_vrexp2_q => InstrId::R4000allegrexVrexp2Q,
+            Id::r4000allegrex_vrnds_s => InstrId::R4000allegrexVrndsS,
+            Id::r4000allegrex_vrndi_s => InstrId::R4000allegrexVrndiS,
+            Id::r4000allegrex_vrndi_p => InstrId::R4000allegrexVrndiP,
+            Id::r4000allegrex_vrndi_t => InstrId::R4000allegrexVrndiT,
+            Id::r4000allegrex_vrndi_q => InstrId::R4000allegrexVrndiQ,
+            Id::r4000allegrex_vrndf1_s => InstrId::R4000allegrexVrndf1S,
+            Id::r4000allegrex_vrndf1_p => InstrId::R4000allegrexVrndf1P,
+            Id::r4000allegrex_vrndf1_t => InstrId::R4000allegrexVrndf1T,
+            Id::r4000allegrex_vrndf1_q => InstrId::R4000allegrexVrndf1Q,
+            Id::r4000allegrex_vrndf2_s => InstrId::R4000allegrexVrndf2S,
+            Id::r4000allegrex_vrndf2_p => InstrId::R4000allegrexVrndf2P,
+            Id::r4000allegrex_vrndf2_t => InstrId::R4000allegrexVrndf2T,
+            Id::r4000allegrex_vrndf2_q => InstrId::R4000allegrexVrndf2Q,
+            Id::r4000allegrex_vf2h_p => InstrId::R4000allegrexVf2hP,
+            Id::r4000allegrex_vf2h_q => InstrId::R4000allegrexVf2hQ,
+            Id::r4000allegrex_vh2f_s => InstrId::R4000allegrexVh2fS,
+            Id::r4000allegrex_vh2f_p => InstrId::R4000allegrexVh2fP,
+            Id::r4000allegrex_vsbz_s => InstrId::R4000allegrexVsbzS,
+            Id::r4000allegrex_vlgb_s => InstrId::R4000allegrexVlgbS,
+            Id::r4000allegrex_vuc2ifs_s => InstrId::R4000allegrexVuc2ifsS,
+            Id::r4000allegrex_vc2i_s => InstrId::R4000allegrexVc2iS,
+            Id::r4000allegrex_vus2i_s => InstrId::R4000allegrexVus2iS,
+            Id::r4000allegrex_vus2i_p => InstrId::R4000allegrexVus2iP,
+            Id::r4000allegrex_vs2i_s => InstrId::R4000allegrexVs2iS,
+            Id::r4000allegrex_vs2i_p => InstrId::R4000allegrexVs2iP,
+            Id::r4000allegrex_vi2uc_q => InstrId::R4000allegrexVi2ucQ,
+            Id::r4000allegrex_vi2c_q => InstrId::R4000allegrexVi2cQ,
+            Id::r4000allegrex_vi2us_p => InstrId::R4000allegrexVi2usP,
+            Id::r4000allegrex_vi2us_q => InstrId::R4000allegrexVi2usQ,
+            Id::r4000allegrex_vi2s_p => InstrId::R4000allegrexVi2sP,
+            Id::r4000allegrex_vi2s_q => InstrId::R4000allegrexVi2sQ,
+            Id::r4000allegrex_vsrt1_q => InstrId::R4000allegrexVsrt1Q,
+            Id::r4000allegrex_vsrt2_q => InstrId::R4000allegrexVsrt2Q,
+            Id::r4000allegrex_vbfy1_p => InstrId::R4000allegrexVbfy1P,
+            Id::r4000allegrex_vbfy1_q => InstrId::R4000allegrexVbfy1Q,
+            Id::r4000allegrex_vbfy2_q => InstrId::R4000allegrexVbfy2Q,
+            Id::r4000allegrex_vocp_s => InstrId::R4000allegrexVocpS,
+            Id::r4000allegrex_vocp_p => InstrId::R4000allegrexVocpP,
+            Id::r4000allegrex_vocp_t => InstrId::R4000allegrexVocpT,
+            Id::r4000allegrex_vocp_q => InstrId::R4000allegrexVocpQ,
+            Id::r4000allegrex_vsocp_s => InstrId::R4000allegrexVsocpS,
+            Id::r4000allegrex_vsocp_p => InstrId::R4000allegrexVsocpP,
+            Id::r4000allegrex_vfad_p => InstrId::R4000allegrexVfadP,
+            Id::r4000allegrex_vfad_t => InstrId::R4000allegrexVfadT,
+            Id::r4000allegrex_vfad_q => InstrId::R4000allegrexVfadQ,
+            Id::r4000allegrex_vavg_p => InstrId::R4000allegrexVavgP,
+            Id::r4000allegrex_vavg_t => InstrId::R4000allegrexVavgT,
+            Id::r4000allegrex_vavg_q => InstrId::R4000allegrexVavgQ,
+            Id::r4000allegrex_vsrt3_q => InstrId::R4000allegrexVsrt3Q,
+            Id::r4000allegrex_vsrt4_q => InstrId::R4000allegrexVsrt4Q,
+            Id::r4000allegrex_vsgn_s => InstrId::R4000allegrexVsgnS,
+            Id::r4000allegrex_vsgn_p => InstrId::R4000allegrexVsgnP,
+            Id::r4000allegrex_vsgn_t => InstrId::R4000allegrexVsgnT,
+            Id::r4000allegrex_vsgn_q => InstrId::R4000allegrexVsgnQ,
+            Id::r4000allegrex_vmfvc => InstrId::R4000allegrexVmfvc,
+            Id::r4000allegrex_vmtvc => InstrId::R4000allegrexVmtvc,
+            Id::r4000allegrex_vt4444_q => InstrId::R4000allegrexVt4444Q,
+            Id::r4000allegrex_vt5551_q => InstrId::R4000allegrexVt5551Q,
+            Id::r4000allegrex_vt5650_q => InstrId::R4000allegrexVt5650Q,
+            Id::r4000allegrex_vcst_s => InstrId::R4000allegrexVcstS,
+            Id::r4000allegrex_vcst_p => InstrId::R4000allegrexVcstP,
+            Id::r4000allegrex_vcst_t => InstrId::R4000allegrexVcstT,
+            Id::r4000allegrex_vcst_q => InstrId::R4000allegrexVcstQ,
+            Id::r4000allegrex_vf2in_s => InstrId::R4000allegrexVf2inS,
+            Id::r4000allegrex_vf2in_p => InstrId::R4000allegrexVf2inP,
+            Id::r4000allegrex_vf2in_t => InstrId::R4000allegrexVf2inT,
+            Id::r4000allegrex_vf2in_q => InstrId::R4000allegrexVf2inQ,
+            Id::r4000allegrex_vf2iz_s => InstrId::R4000allegrexVf2izS,
+            Id::r4000allegrex_vf2iz_p => InstrId::R4000allegrexVf2izP,
+            Id::r4000allegrex_vf2iz_t => InstrId::R4000allegrexVf2izT,
+            Id::r4000allegrex_vf2iz_q => InstrId::R4000allegrexVf2izQ,
+            Id::r4000allegrex_vf2iu_s => InstrId::R4000allegrexVf2iuS,
+            Id::r4000allegrex_vf2iu_p => InstrId::R4000allegrexVf2iuP,
+            Id::r4000allegrex_vf2iu_t => InstrId::R4000allegrexVf2iuT,
+            Id::r4000allegrex_vf2iu_q => InstrId::R4000allegrexVf2iuQ,
+            Id::r4000allegrex_vf2id_s => InstrId::R4000allegrexVf2idS,
+            Id::r4000allegrex_vf2id_p => InstrId::R4000allegrexVf2idP,
+            Id::r4000allegrex_vf2id_t => InstrId::R4000allegrexVf2idT,
+            Id::r4000allegrex_vf2id_q => InstrId::R4000allegrexVf2idQ,
+            Id::r4000allegrex_vi2f_s => InstrId::R4000allegrexVi2fS,
+            Id::r4000allegrex_vi2f_p => InstrId::R4000allegrexVi2fP,
+            Id::r4000allegrex_vi2f_t => InstrId::R4000allegrexVi2fT,
+            Id::r4000allegrex_vi2f_q => InstrId::R4000allegrexVi2fQ,
+            Id::r4000allegrex_vcmovt_s => InstrId::R4000allegrexVcmovtS,
+            Id::r4000allegrex_vcmovt_p => InstrId::R4000allegrexVcmovtP,
+            Id::r4000allegrex_vcmovt_t => InstrId::R4000allegrexVcmovtT,
+            Id::r4000allegrex_vcmovt_q => InstrId::R4000allegrexVcmovtQ,
+            Id::r4000allegrex_vcmovf_s => InstrId::R4000allegrexVcmovfS,
+            Id::r4000allegrex_vcmovf_p => InstrId::R4000allegrexVcmovfP,
+            Id::r4000allegrex_vcmovf_t => InstrId::R4000allegrexVcmovfT,
+            Id::r4000allegrex_vcmovf_q => InstrId::R4000allegrexVcmovfQ,
+            Id::r4000allegrex_lvl_q => InstrId::R4000allegrexLvlQ,
+            Id::r4000allegrex_lvr_q => InstrId::R4000allegrexLvrQ,
+            Id::r4000allegrex_vpfxs => InstrId::R4000allegrexVpfxs,
+            Id::r4000allegrex_vpfxt => InstrId::R4000allegrexVpfxt,
+            Id::r4000allegrex_vpfxd => InstrId::R4000allegrexVpfxd,
+            Id::r4000allegrex_viim_s => InstrId::R4000allegrexViimS,
+            Id::r4000allegrex_vfim_s => InstrId::R4000allegrexVfimS,
+            Id::r4000allegrex_vmmul_p => InstrId::R4000allegrexVmmulP,
+            Id::r4000allegrex_vmmul_t => InstrId::R4000allegrexVmmulT,
+            Id::r4000allegrex_vmmul_q => InstrId::R4000allegrexVmmulQ,
+            Id::r4000allegrex_vhtfm2_p => InstrId::R4000allegrexVhtfm2P,
+            Id::r4000allegrex_vtfm2_p => InstrId::R4000allegrexVtfm2P,
+            Id::r4000allegrex_vhtfm3_t => InstrId::R4000allegrexVhtfm3T,
+            Id::r4000allegrex_vtfm3_t => InstrId::R4000allegrexVtfm3T,
+            Id::r4000allegrex_vhtfm4_q => InstrId::R4000allegrexVhtfm4Q,
+            Id::r4000allegrex_vtfm4_q => InstrId::R4000allegrexVtfm4Q,
+            Id::r4000allegrex_vmscl_p => InstrId::R4000allegrexVmsclP,
+            Id::r4000allegrex_vmscl_t => InstrId::R4000allegrexVmsclT,
+            Id::r4000allegrex_vmscl_q => InstrId::R4000allegrexVmsclQ,
+            Id::r4000allegrex_vcrsp_t => InstrId::R4000allegrexVcrspT,
+            Id::r4000allegrex_vqmul_q => InstrId::R4000allegrexVqmulQ,
+            Id::r4000allegrex_vrot_p => InstrId::R4000allegrexVrotP,
+            Id::r4000allegrex_vrot_t => InstrId::R4000allegrexVrotT,
+            Id::r4000allegrex_vrot_q => InstrId::R4000allegrexVrotQ,
+            Id::r4000allegrex_vmmov_p => InstrId::R4000allegrexVmmovP,
+            Id::r4000allegrex_vmmov_t => InstrId::R4000allegrexVmmovT,
+            Id::r4000allegrex_vmmov_q => InstrId::R4000allegrexVmmovQ,
+            Id::r4000allegrex_vmidt_p => InstrId::R4000allegrexVmidtP,
+            Id::r4000allegrex_vmidt_t => InstrId::R4000allegrexVmidtT,
+            Id::r4000allegrex_vmidt_q => InstrId::R4000allegrexVmidtQ,
+            Id::r4000allegrex_vmzero_p => InstrId::R4000allegrexVmzeroP,
+            Id::r4000allegrex_vmzero_t => InstrId::R4000allegrexVmzeroT,
+            Id::r4000allegrex_vmzero_q => InstrId::R4000allegrexVmzeroQ,
+            Id::r4000allegrex_vmone_p => InstrId::R4000allegrexVmoneP,
+            Id::r4000allegrex_vmone_t => InstrId::R4000allegrexVmoneT,
+            Id::r4000allegrex_vmone_q => InstrId::R4000allegrexVmoneQ,
+            Id::r4000allegrex_vnop => InstrId::R4000allegrexVnop,
+            Id::r4000allegrex_vsync => InstrId::R4000allegrexVsync,
+            Id::r4000allegrex_vflush => InstrId::R4000allegrexVflush,
+            Id::r4000allegrex_svl_q => InstrId::R4000allegrexSvlQ,
+            Id::r4000allegrex_svr_q => InstrId::R4000allegrexSvrQ,
+            Id::r5900_lq => InstrId::R5900Lq,
+            Id::r5900_sq => InstrId::R5900Sq,
+            Id::r5900_lqc2 => InstrId::R5900Lqc2,
+            Id::r5900_sqc2 => InstrId::R5900Sqc2,
+            Id::r5900_sync_p => InstrId::R5900SyncP,
+            Id::r5900_mult => InstrId::R5900Mult,
+            Id::r5900_mfsa => InstrId::R5900Mfsa,
+            Id::r5900_mtsa => InstrId::R5900Mtsa,
+            Id::r5900_mtsab => InstrId::R5900Mtsab,
+            Id::r5900_mtsah => InstrId::R5900Mtsah,
+            Id::r5900_madd => InstrId::R5900Madd,
+            Id::r5900_maddu => InstrId::R5900Maddu,
+            Id::r5900_plzcw => InstrId::R5900Plzcw,
+            Id::r5900_mfhi1 => InstrId::R5900Mfhi1,
+            Id::r5900_mthi1 => InstrId::R5900Mthi1,
+            Id::r5900_mflo1 => InstrId::R5900Mflo1,
+            Id::r5900_mtlo1 => InstrId::R5900Mtlo1,
+            Id::r5900_mult1 => InstrId::R5900Mult1,
+            Id::r5900_multu1 => InstrId::R5900Multu1,
+            Id::r5900_div1 => InstrId::R5900Div1,
+            Id::r5900_divu1 => InstrId::R5900Divu1,
+            Id::r5900_madd1 => InstrId::R5900Madd1,
+            Id::r5900_maddu1 => InstrId::R5900Maddu1,
+            Id::r5900_psllh => InstrId::R5900Psllh,
+            Id::r5900_psrlh => InstrId::R5900Psrlh,
+            Id::r5900_psrah => InstrId::R5900Psrah,
+            Id::r5900_psllw => InstrId::R5900Psllw,
+            Id::r5900_psrlw => InstrId::R5900Psrlw,
+            Id::r5900_psraw => InstrId::R5900Psraw,
+            Id::r5900_paddw => InstrId::R5900Paddw,
+            Id::r5900_psubw => InstrId::R5900Psubw,
+            Id::r5900_pcgtw => InstrId::R5900Pcgtw,
+            Id::r5900_pmaxw => InstrId::R5900Pmaxw,
+            Id::r5900_paddh => InstrId::R5900Paddh,
+            Id::r5900_psubh => InstrId::R5900Psubh,
+            Id::r5900_pcgth => InstrId::R5900Pcgth,
+            Id::r5900_pmaxh => InstrId::R5900Pmaxh,
+            Id::r5900_paddb => InstrId::R5900Paddb,
+            Id::r5900_psubb => InstrId::R5900Psubb,
+            Id::r5900_pcgtb => InstrId::R5900Pcgtb,
+            Id::r5900_paddsw => InstrId::R5900Paddsw,
+            Id::r5900_psubsw => InstrId::R5900Psubsw,
+            Id::r5900_pextlw => InstrId::R5900Pextlw,
+            Id::r5900_ppacw => InstrId::R5900Ppacw,
+            Id::r5900_paddsh => InstrId::R5900Paddsh,
+            Id::r5900_psubsh => InstrId::R5900Psubsh,
+            Id::r5900_pextlh => InstrId::R5900Pextlh,
+            Id::r5900_ppach => InstrId::R5900Ppach,
+            Id::r5900_paddsb => InstrId::R5900Paddsb,
+            Id::r5900_psubsb => InstrId::R5900Psubsb,
+            Id::r5900_pextlb => InstrId::R5900Pextlb,
+            Id::r5900_ppacb => InstrId::R5900Ppacb,
+            Id::r5900_pext5 => InstrId::R5900Pext5,
+            Id::r5900_ppac5 => InstrId::R5900Ppac5,
+            Id::r5900_pabsw => InstrId::R5900Pabsw,
+            Id::r5900_pceqw => InstrId::R5900Pceqw,
+            Id::r5900_pminw => InstrId::R5900Pminw,
+            Id::r5900_padsbh => InstrId::R5900Padsbh,
+            Id::r5900_pabsh => InstrId::R5900Pabsh,
+            Id::r5900_pceqh => InstrId::R5900Pceqh,
+            Id::r5900_pminh => InstrId::R5900Pminh,
+            Id::r5900_pceqb => InstrId::R5900Pceqb,
+            Id::r5900_padduw => InstrId::R5900Padduw,
+            Id::r5900_psubuw => InstrId::R5900Psubuw,
+            Id::r5900_pextuw => InstrId::R5900Pextuw,
+            Id::r5900_padduh => InstrId::R5900Padduh,
+            Id::r5900_psubuh => InstrId::R5900Psubuh,
+            Id::r5900_pextuh => InstrId::R5900Pextuh,
+            Id::r5900_paddub => InstrId::R5900Paddub,
+            Id::r5900_psubub => InstrId::R5900Psubub,
+            Id::r5900_pextub => InstrId::R5900Pextub,
+            Id::r5900_qfsrv => InstrId::R5900Qfsrv,
+            Id::r5900_pmaddw => InstrId::R5900Pmaddw,
+            Id::r5900_psllvw => InstrId::R5900Psllvw,
+            Id::r5900_psrlvw => InstrId::R5900Psrlvw,
+            Id::r5900_pmsubw => InstrId::R5900Pmsubw,
+            Id::r5900_pmfhi => InstrId::R5900Pmfhi,
+            Id::r5900_pmflo => InstrId::R5900Pmflo,
+            Id::r5900_pinth => InstrId::R5900Pinth,
+            Id::r5900_pmultw => InstrId::R5900Pmultw,
+            Id::r5900_pdivw => InstrId::R5900Pdivw,
+            Id::r5900_pcpyld => InstrId::R5900Pcpyld,
+            Id::r5900_pmaddh => InstrId::R5900Pmaddh,
+            Id::r5900_phmadh => InstrId::R5900Phmadh,
+            Id::r5900_pand => InstrId::R5900Pand,
+            Id::r5900_pxor => InstrId::R5900Pxor,
+            Id::r5900_pmsubh => InstrId::R5900Pmsubh,
+            Id::r5900_phmsbh => InstrId::R5900Phmsbh,
+            Id::r5900_pexeh => InstrId::R5900Pexeh,
+            Id::r5900_prevh => InstrId::R5900Prevh,
+            Id::r5900_pmulth => InstrId::R5900Pmulth,
+            Id::r5900_pdivbw => InstrId::R5900Pdivbw,
+            Id::r5900_pexew => InstrId::R5900Pexew,
+            Id::r5900_prot3w => InstrId::R5900Prot3w,
+            Id::r5900_pmadduw => InstrId::R5900Pmadduw,
+            Id::r5900_psravw => InstrId::R5900Psravw,
+            Id::r5900_pmthi => InstrId::R5900Pmthi,
+            Id::r5900_pmtlo => InstrId::R5900Pmtlo,
+            Id::r5900_pinteh => InstrId::R5900Pinteh,
+            Id::r5900_pmultuw => InstrId::R5900Pmultuw,
+            Id::r5900_pdivuw => InstrId::R5900Pdivuw,
+            Id::r5900_pcpyud => InstrId::R5900Pcpyud,
+            Id::r5900_por => InstrId::R5900Por,
+            Id::r5900_pnor => InstrId::R5900Pnor,
+            Id::r5900_pexch => InstrId::R5900Pexch,
+            Id::r5900_pcpyh => InstrId::R5900Pcpyh,
+            Id::r5900_pexcw => InstrId::R5900Pexcw,
+            Id::r5900_pmfhl_lw => InstrId::R5900PmfhlLw,
+            Id::r5900_pmfhl_uw => InstrId::R5900PmfhlUw,
+            Id::r5900_pmfhl_slw => InstrId::R5900PmfhlSlw,
+            Id::r5900_pmfhl_lh => InstrId::R5900PmfhlLh,
+            Id::r5900_pmfhl_sh => InstrId::R5900PmfhlSh,
+            Id::r5900_pmthl_lw => InstrId::R5900PmthlLw,
+            Id::r5900_ei => InstrId::R5900Ei,
+            Id::r5900_di => InstrId::R5900Di,
+            Id::r5900_c1__sqrt_s => InstrId::R5900C1SqrtS,
+            Id::r5900_rsqrt_s => InstrId::R5900RsqrtS,
+            Id::r5900_adda_s => InstrId::R5900AddaS,
+            Id::r5900_suba_s => InstrId::R5900SubaS,
+            Id::r5900_mula_s => InstrId::R5900MulaS,
+            Id::r5900_madd_s => InstrId::R5900MaddS,
+            Id::r5900_msub_s => InstrId::R5900MsubS,
+            Id::r5900_madda_s => InstrId::R5900MaddaS,
+            Id::r5900_msuba_s => InstrId::R5900MsubaS,
+            Id::r5900_max_s => InstrId::R5900MaxS,
+            Id::r5900_min_s => InstrId::R5900MinS,
+            Id::r5900_c_lt_s => InstrId::R5900CLtS,
+            Id::r5900_c_le_s => InstrId::R5900CLeS,
+            Id::r5900_qmfc2_ni => InstrId::R5900Qmfc2Ni,
+            Id::r5900_cfc2_ni => InstrId::R5900Cfc2Ni,
+            Id::r5900_qmtc2_ni => InstrId::R5900Qmtc2Ni,
+            Id::r5900_ctc2_ni => InstrId::R5900Ctc2Ni,
+            Id::r5900_qmfc2_i => InstrId::R5900Qmfc2I,
+            Id::r5900_cfc2_i => InstrId::R5900Cfc2I,
+            Id::r5900_qmtc2_i => InstrId::R5900Qmtc2I,
+            Id::r5900_ctc2_i => InstrId::R5900Ctc2I,
+            Id::r5900_bc2f => InstrId::R5900Bc2f,
+            Id::r5900_bc2t => InstrId::R5900Bc2t,
+            Id::r5900_bc2fl => InstrId::R5900Bc2fl,
+            Id::r5900_bc2tl => InstrId::R5900Bc2tl,
+            Id::r5900_vaddx => InstrId::R5900Vaddx,
+            Id::r5900_vaddy => InstrId::R5900Vaddy,
+            Id::r5900_vaddz => InstrId::R5900Vaddz,
+            Id::r5900_vaddw => InstrId::R5900Vaddw,
+            Id::r5900_vsubx => InstrId::R5900Vsubx,
+            Id::r5900_vsuby => InstrId::R5900Vsuby,
+            Id::r5900_vsubz => InstrId::R5900Vsubz,
+            Id::r5900_vsubw => InstrId::R5900Vsubw,
+            Id::r5900_vmaddx => InstrId::R5900Vmaddx,
+            Id::r5900_vmaddy => InstrId::R5900Vmaddy,
+            Id::r5900_vmaddz => InstrId::R5900Vmaddz,
+            Id::r5900_vmaddw => InstrId::R5900Vmaddw,
+            Id::r5900_vmsubx => InstrId::R5900Vmsubx,
+            Id::r5900_vmsuby => InstrId::R5900Vmsuby,
+            Id::r5900_vmsubz => InstrId::R5900Vmsubz,
+            Id::r5900_vmsubw => InstrId::R5900Vmsubw,
+            Id::r5900_vmaxx => InstrId::R5900Vmaxx,
+            Id::r5900_vmaxy => InstrId::R5900Vmaxy,
+            Id::r5900_vmaxz => InstrId::R5900Vmaxz,
+            Id::r5900_vmaxw => InstrId::R5900Vmaxw,
+            Id::r5900_vminix => InstrId::R5900Vminix,
+            Id::r5900_vminiy => InstrId::R5900Vminiy,
+            Id::r5900_vminiz => InstrId::R5900Vminiz,
+            Id::r5900_vminiw => InstrId::R5900Vminiw,
+            Id::r5900_vmulx => InstrId::R5900Vmulx,
+            Id::r5900_vmuly => InstrId::R5900Vmuly,
+            Id::r5900_vmulz => InstrId::R5900Vmulz,
+            Id::r5900_vmulw => InstrId::R5900Vmulw,
+            Id::r5900_vmulq => InstrId::R5900Vmulq,
+            Id::r5900_vmaxi => InstrId::R5900Vmaxi,
+            Id::r5900_vmuli => InstrId::R5900Vmuli,
+            Id::r5900_vminii => InstrId::R5900Vminii,
+            Id::r5900_vaddq => InstrId::R5900Vaddq,
+            Id::r5900_vmaddq => InstrId::R5900Vmaddq,
+            Id::r5900_vaddi => InstrId::R5900Vaddi,
+            Id::r5900_vmaddi => InstrId::R5900Vmaddi,
+            Id::r5900_vsubq => InstrId::R5900Vsubq,
+            Id::r5900_vmsubq => InstrId::R5900Vmsubq,
+            Id::r5900_vsubi => InstrId::R5900Vsubi,
+            Id::r5900_vmsubi => InstrId::R5900Vmsubi,
+            Id::r5900_vadd => InstrId::R5900Vadd,
+            Id::r5900_vmadd => InstrId::R5900Vmadd,
+            Id::r5900_vmul => InstrId::R5900Vmul,
+            Id::r5900_vmax => InstrId::R5900Vmax,
+            Id::r5900_vsub => InstrId::R5900Vsub,
+            Id::r5900_vmsub => InstrId::R5900Vmsub,
+            Id::r5900_vopmsub => InstrId::R5900Vopmsub,
+            Id::r5900_vmini => InstrId::R5900Vmini,
+            Id::r5900_viadd => InstrId::R5900Viadd,
+            Id::r5900_visub => InstrId::R5900Visub,
+            Id::r5900_viaddi => InstrId::R5900Viaddi,
+            Id::r5900_viand => InstrId::R5900Viand,
+            Id::r5900_vior => InstrId::R5900Vior,
+            Id::r5900_vcallms => InstrId::R5900Vcallms,
+            Id::r5900_vcallmsr => InstrId::R5900Vcallmsr,
+            Id::r5900_vaddax => InstrId::R5900Vaddax,
+            Id::r5900_vadday => InstrId::R5900Vadday,
+            Id::r5900_vaddaz => InstrId::R5900Vaddaz,
+            Id::r5900_vaddaw => InstrId::R5900Vaddaw,
+            Id::r5900_vsubax => InstrId::R5900Vsubax,
+            Id::r5900_vsubay => InstrId::R5900Vsubay,
+            Id::r5900_vsubaz => InstrId::R5900Vsubaz,
+            Id::r5900_vsubaw => InstrId::R5900Vsubaw,
+            Id::r5900_vmaddax => InstrId::R5900Vmaddax,
+            Id::r5900_vmadday => InstrId::R5900Vmadday,
+            Id::r5900_vmaddaz => InstrId::R5900Vmaddaz,
+            Id::r5900_vmaddaw => InstrId::R5900Vmaddaw,
+            Id::r5900_vmsubax => InstrId::R5900Vmsubax,
+            Id::r5900_vmsubay => InstrId::R5900Vmsubay,
+            Id::r5900_vmsubaz => InstrId::R5900Vmsubaz,
+            Id::r5900_vmsubaw => InstrId::R5900Vmsubaw,
+            Id::r5900_vitof0 => InstrId::R5900Vitof0,
+            Id::r5900_vitof4 => InstrId::R5900Vitof4,
+            Id::r5900_vitof12 => InstrId::R5900Vitof12,
+            Id::r5900_vitof15 => InstrId::R5900Vitof15,
+            Id::r5900_vftoi0 => InstrId::R5900Vftoi0,
+            Id::r5900_vftoi4 => InstrId::R5900Vftoi4,
+            Id::r5900_vftoi12 => InstrId::R5900Vftoi12,
+            Id::r5900_vftoi15 => InstrId::R5900Vftoi15,
+            Id::r5900_vmulax => InstrId::R5900Vmulax,
+            Id::r5900_vmulay => InstrId::R5900Vmulay,
+            Id::r5900_vmulaz => InstrId::R5900Vmulaz,
+            Id::r5900_vmulaw => InstrId::R5900Vmulaw,
+            Id::r5900_vmulaq => InstrId::R5900Vmulaq,
+            Id::r5900_vabs => InstrId::R5900Vabs,
+            Id::r5900_vmulai => InstrId::R5900Vmulai,
+            Id::r5900_vclipw => InstrId::R5900Vclipw,
+            Id::r5900_vaddaq => InstrId::R5900Vaddaq,
+            Id::r5900_vmaddaq => InstrId::R5900Vmaddaq,
+            Id::r5900_vaddai => InstrId::R5900Vaddai,
+            Id::r5900_vmaddai => InstrId::R5900Vmaddai,
+            Id::r5900_vsubaq => InstrId::R5900Vsubaq,
+            Id::r5900_vmsubaq => InstrId::R5900Vmsubaq,
+            Id::r5900_vsubai => InstrId::R5900Vsubai,
+            Id::r5900_vmsubai => InstrId::R5900Vmsubai,
+            Id::r5900_vadda => InstrId::R5900Vadda,
+            Id::r5900_vmadda => InstrId::R5900Vmadda,
+            Id::r5900_vmula => InstrId::R5900Vmula,
+            Id::r5900_vsuba => InstrId::R5900Vsuba,
+            Id::r5900_vmsuba => InstrId::R5900Vmsuba,
+            Id::r5900_vopmula => InstrId::R5900Vopmula,
+            Id::r5900_vnop => InstrId::R5900Vnop,
+            Id::r5900_vmove => InstrId::R5900Vmove,
+            Id::r5900_vmr32 => InstrId::R5900Vmr32,
+            Id::r5900_vlqi => InstrId::R5900Vlqi,
+            Id::r5900_vsqi => InstrId::R5900Vsqi,
+            Id::r5900_vlqd => InstrId::R5900Vlqd,
+            Id::r5900_vsqd => InstrId::R5900Vsqd,
+            Id::r5900_vdiv => InstrId::R5900Vdiv,
+            Id::r5900_vsqrt => InstrId::R5900Vsqrt,
+            Id::r5900_vrsqrt => InstrId::R5900Vrsqrt,
+            Id::r5900_vwaitq => InstrId::R5900Vwaitq,
+            Id::r5900_vmtir => InstrId::R5900Vmtir,
+            Id::r5900_vmfir => InstrId::R5900Vmfir,
+            Id::r5900_vrnext => InstrId::R5900Vrnext,
+            Id::r5900_vrget => InstrId::R5900Vrget,
+            Id::r5900_vrinit => InstrId::R5900Vrinit,
+            Id::r5900_vrxor => InstrId::R5900Vrxor,
+            Id::r5900_vilwr_w => InstrId::R5900VilwrW,
+            Id::r5900_vilwr_z => InstrId::R5900VilwrZ,
+            Id::r5900_vilwr_y => InstrId::R5900VilwrY,
+            Id::r5900_vilwr_x => InstrId::R5900VilwrX,
+            Id::r5900_viswr_w => InstrId::R5900ViswrW,
+            Id::r5900_viswr_z => InstrId::R5900ViswrZ,
+            Id::r5900_viswr_y => InstrId::R5900ViswrY,
+            Id::r5900_viswr_x => InstrId::R5900ViswrX,
+            _ => return None,
+        })
+    }
+}
+
 /// Operand type enumeration
 #[napi]
 pub enum OperandType {
@@ -287,6 +2205,7 @@ pub enum OperandType {
     R5900Immediate15,
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<rabbitizer::InstrCategory> for InstrCategory {
     fn into(self) -> rabbitizer::InstrCategory {
         match self {
@@ -314,6 +2233,7 @@ impl From<rabbitizer::InstrCategory> for InstrCategory {
 
 /// ABI (Application Binary Interface) register naming
 #[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Abi {
     /// Numeric register names (e.g., $0, $1, $2)
     NUMERIC,
@@ -325,6 +2245,7 @@ pub enum Abi {
     N64,
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<rabbitizer::Abi> for Abi {
     fn into(self) -> rabbitizer::Abi {
         match self {
@@ -348,6 +2269,7 @@ impl From<rabbitizer::Abi> for Abi {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<rabbitizer::AccessType> for AccessType {
     fn into(self) -> rabbitizer::AccessType {
         match self {
@@ -387,6 +2309,7 @@ impl From<rabbitizer::AccessType> for AccessType {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<rabbitizer::InstrSuffix> for InstrSuffix {
     fn into(self) -> rabbitizer::InstrSuffix {
         match self {
@@ -483,5 +2406,116 @@ impl From<u32> for GprO32 {
     }
 }
 
-// Note: InstrIdType and OperandType are large enums with 100+ variants
-// They are exposed as enums for type checking but work with u32 values in practice
+impl InstrIdType {
+    /// The variant named like `instr_id_type_name` returns it, as rabbitizer
+    /// does not expose the type itself
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "ALL_INVALID" => InstrIdType::AllInvalid,
+            "CPU_INVALID" => InstrIdType::CpuInvalid,
+            "CPU_NORMAL" => InstrIdType::CpuNormal,
+            "CPU_SPECIAL" => InstrIdType::CpuSpecial,
+            "CPU_REGIMM" => InstrIdType::CpuRegimm,
+            "CPU_COP0" => InstrIdType::CpuCop0,
+            "CPU_COP0_BC0" => InstrIdType::CpuCop0Bc0,
+            "CPU_COP0_TLB" => InstrIdType::CpuCop0Tlb,
+            "CPU_COP1" => InstrIdType::CpuCop1,
+            "CPU_COP1_BC1" => InstrIdType::CpuCop1Bc1,
+            "CPU_COP1_FPUS" => InstrIdType::CpuCop1Fpus,
+            "CPU_COP1_FPUD" => InstrIdType::CpuCop1Fpud,
+            "CPU_COP1_FPUW" => InstrIdType::CpuCop1Fpuw,
+            "CPU_COP1_FPUL" => InstrIdType::CpuCop1Fpul,
+            "CPU_COP2" => InstrIdType::CpuCop2,
+            "RSP_INVALID" => InstrIdType::RspInvalid,
+            "RSP_NORMAL" => InstrIdType::RspNormal,
+            "RSP_NORMAL_LWC2" => InstrIdType::RspNormalLwc2,
+            "RSP_NORMAL_SWC2" => InstrIdType::RspNormalSwc2,
+            "RSP_SPECIAL" => InstrIdType::RspSpecial,
+            "RSP_REGIMM" => InstrIdType::RspRegimm,
+            "RSP_COP0" => InstrIdType::RspCop0,
+            "RSP_COP1" => InstrIdType::RspCop1,
+            "RSP_COP2" => InstrIdType::RspCop2,
+            "RSP_COP2_VU" => InstrIdType::RspCop2Vu,
+            "R3000GTE_INVALID" => InstrIdType::R3000gteInvalid,
+            "R3000GTE_NORMAL" => InstrIdType::R3000gteNormal,
+            "R3000GTE_SPECIAL" => InstrIdType::R3000gteSpecial,
+            "R3000GTE_REGIMM" => InstrIdType::R3000gteRegimm,
+            "R3000GTE_COP0" => InstrIdType::R3000gteCop0,
+            "R3000GTE_COP1" => InstrIdType::R3000gteCop1,
+            "R3000GTE_COP2" => InstrIdType::R3000gteCop2,
+            "R3000GTE_COP2_GTE" => InstrIdType::R3000gteCop2Gte,
+            "R4000ALLEGREX_INVALID" => InstrIdType::R4000allegrexInvalid,
+            "R4000ALLEGREX_NORMAL" => InstrIdType::R4000allegrexNormal,
+            "R4000ALLEGREX_SPECIAL" => InstrIdType::R4000allegrexSpecial,
+            "R4000ALLEGREX_SPECIAL_RS" => InstrIdType::R4000allegrexSpecialRs,
+            "R4000ALLEGREX_SPECIAL_SA" => InstrIdType::R4000allegrexSpecialSa,
+            "R4000ALLEGREX_REGIMM" => InstrIdType::R4000allegrexRegimm,
+            "R4000ALLEGREX_SPECIAL2" => InstrIdType::R4000allegrexSpecial2,
+            "R4000ALLEGREX_SPECIAL3" => InstrIdType::R4000allegrexSpecial3,
+            "R4000ALLEGREX_SPECIAL3_BSHFL" => InstrIdType::R4000allegrexSpecial3Bshfl,
+            "R4000ALLEGREX_COP0" => InstrIdType::R4000allegrexCop0,
+            "R4000ALLEGREX_COP0_BC0" => InstrIdType::R4000allegrexCop0Bc0,
+            "R4000ALLEGREX_COP0_TLB" => InstrIdType::R4000allegrexCop0Tlb,
+            "R4000ALLEGREX_COP1" => InstrIdType::R4000allegrexCop1,
+            "R4000ALLEGREX_COP1_BC1" => InstrIdType::R4000allegrexCop1Bc1,
+            "R4000ALLEGREX_COP1_FPUS" => InstrIdType::R4000allegrexCop1Fpus,
+            "R4000ALLEGREX_COP1_FPUW" => InstrIdType::R4000allegrexCop1Fpuw,
+            "R4000ALLEGREX_COP2" => InstrIdType::R4000allegrexCop2,
+            "R4000ALLEGREX_COP2_BC2" => InstrIdType::R4000allegrexCop2Bc2,
+            "R4000ALLEGREX_COP2_MFHC2" => InstrIdType::R4000allegrexCop2Mfhc2,
+            "R4000ALLEGREX_COP2_MFHC2_P" => InstrIdType::R4000allegrexCop2Mfhc2P,
+            "R4000ALLEGREX_COP2_MFHC2_P_S" => InstrIdType::R4000allegrexCop2Mfhc2PS,
+            "R4000ALLEGREX_COP2_MTHC2" => InstrIdType::R4000allegrexCop2Mthc2,
+            "R4000ALLEGREX_VFPU0" => InstrIdType::R4000allegrexVfpu0,
+            "R4000ALLEGREX_VFPU1" => InstrIdType::R4000allegrexVfpu1,
+            "R4000ALLEGREX_VFPU3" => InstrIdType::R4000allegrexVfpu3,
+            "R4000ALLEGREX_VFPU4" => InstrIdType::R4000allegrexVfpu4,
+            "R4000ALLEGREX_VFPU4_FMT0" => InstrIdType::R4000allegrexVfpu4Fmt0,
+            "R4000ALLEGREX_VFPU4_FMT0_FMT0" => InstrIdType::R4000allegrexVfpu4Fmt0Fmt0,
+            "R4000ALLEGREX_VFPU4_FMT0_FMT2" => InstrIdType::R4000allegrexVfpu4Fmt0Fmt2,
+            "R4000ALLEGREX_VFPU4_FMT0_FMT3" => InstrIdType::R4000allegrexVfpu4Fmt0Fmt3,
+            "R4000ALLEGREX_VFPU4_FMT0_RND" => InstrIdType::R4000allegrexVfpu4Fmt0Rnd,
+            "R4000ALLEGREX_VFPU4_FMT0_CVTFLT" => InstrIdType::R4000allegrexVfpu4Fmt0Cvtflt,
+            "R4000ALLEGREX_VFPU4_FMT0_CVTINT" => InstrIdType::R4000allegrexVfpu4Fmt0Cvtint,
+            "R4000ALLEGREX_VFPU4_FMT0_FMT8" => InstrIdType::R4000allegrexVfpu4Fmt0Fmt8,
+            "R4000ALLEGREX_VFPU4_FMT0_FMT9" => InstrIdType::R4000allegrexVfpu4Fmt0Fmt9,
+            "R4000ALLEGREX_VFPU4_FMT0_CONTROL" => InstrIdType::R4000allegrexVfpu4Fmt0Control,
+            "R4000ALLEGREX_VFPU4_FMT0_COLOR" => InstrIdType::R4000allegrexVfpu4Fmt0Color,
+            "R4000ALLEGREX_VFPU4_FMT0_CST" => InstrIdType::R4000allegrexVfpu4Fmt0Cst,
+            "R4000ALLEGREX_VFPU4_FMT2" => InstrIdType::R4000allegrexVfpu4Fmt2,
+            "R4000ALLEGREX_VFPU4_FMT2_CNDMOVE" => InstrIdType::R4000allegrexVfpu4Fmt2Cndmove,
+            "R4000ALLEGREX_LVL" => InstrIdType::R4000allegrexLvl,
+            "R4000ALLEGREX_VFPU5" => InstrIdType::R4000allegrexVfpu5,
+            "R4000ALLEGREX_VFPU6" => InstrIdType::R4000allegrexVfpu6,
+            "R4000ALLEGREX_VFPU6_FMT7" => InstrIdType::R4000allegrexVfpu6Fmt7,
+            "R4000ALLEGREX_VFPU6_FMT7_FMT0" => InstrIdType::R4000allegrexVfpu6Fmt7Fmt0,
+            "R4000ALLEGREX_VFPU7" => InstrIdType::R4000allegrexVfpu7,
+            "R4000ALLEGREX_QUADLR" => InstrIdType::R4000allegrexQuadlr,
+            "R5900_INVALID" => InstrIdType::R5900Invalid,
+            "R5900_NORMAL" => InstrIdType::R5900Normal,
+            "R5900_SPECIAL" => InstrIdType::R5900Special,
+            "R5900_REGIMM" => InstrIdType::R5900Regimm,
+            "R5900_COP0" => InstrIdType::R5900Cop0,
+            "R5900_COP0_TLB" => InstrIdType::R5900Cop0Tlb,
+            "R5900_COP1" => InstrIdType::R5900Cop1,
+            "R5900_COP1_FPUS" => InstrIdType::R5900Cop1Fpus,
+            "R5900_COP2" => InstrIdType::R5900Cop2,
+            "R5900_COP2_NOHIGHBIT" => InstrIdType::R5900Cop2Nohighbit,
+            "R5900_COP2_BC2" => InstrIdType::R5900Cop2Bc2,
+            "R5900_COP2_SPECIAL1" => InstrIdType::R5900Cop2Special1,
+            "R5900_COP2_SPECIAL2" => InstrIdType::R5900Cop2Special2,
+            "R5900_COP2_VIWR" => InstrIdType::R5900Cop2Viwr,
+            "R5900_MMI" => InstrIdType::R5900Mmi,
+            "R5900_MMI_0" => InstrIdType::R5900Mmi0,
+            "R5900_MMI_1" => InstrIdType::R5900Mmi1,
+            "R5900_MMI_2" => InstrIdType::R5900Mmi2,
+            "R5900_MMI_3" => InstrIdType::R5900Mmi3,
+            "R5900_MMI_PMFHL" => InstrIdType::R5900MmiPmfhl,
+            "R5900_MMI_PMTHL" => InstrIdType::R5900MmiPmthl,
+            _ => return None,
+        })
+    }
+}
+
+// Note: OperandType is a large enum with 100+ variants
+// It is exposed as an enum for type checking but works with u32 values in practice
@@ -0,0 +1,82 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! Raw bit field accessors for analysis code
+//!
+//! The `get_*` methods of `rabbitizer::Instruction` panic when the instruction
+//! does not have the requested operand. Analyses check the descriptor flags
+//! (`reads_rs`, `modifies_rt`, ...) first and then read the field directly.
+
+use rabbitizer::AccessType;
+use rabbitizer::Instruction as RustInstruction;
+
+pub(crate) fn rs(instr: &RustInstruction) -> u32 {
+    (instr.raw() >> 21) & 0x1F
+}
+
+pub(crate) fn rt(instr: &RustInstruction) -> u32 {
+    (instr.raw() >> 16) & 0x1F
+}
+
+pub(crate) fn rd(instr: &RustInstruction) -> u32 {
+    (instr.raw() >> 11) & 0x1F
+}
+
+pub(crate) fn immediate(instr: &RustInstruction) -> u32 {
+    instr.raw() & 0xFFFF
+}
+
+/// The `fs` field shares its bits with `rd`
+pub(crate) fn fs(instr: &RustInstruction) -> u32 {
+    rd(instr)
+}
+
+/// The `ft` field shares its bits with `rt`
+pub(crate) fn ft(instr: &RustInstruction) -> u32 {
+    rt(instr)
+}
+
+/// Shift amount of `sll`, `dsll32`, ...
+pub(crate) fn sa(instr: &RustInstruction) -> u32 {
+    (instr.raw() >> 6) & 0x1F
+}
+
+/// The `fmt` field of COP1 instructions shares its bits with `rs`
+pub(crate) fn fmt(instr: &RustInstruction) -> u32 {
+    rs(instr)
+}
+
+/// Checks if the instruction is a COP1 operation on doubles (`fmt` is `D`)
+pub(crate) fn is_cop1_double(instr: &RustInstruction) -> bool {
+    instr.raw() >> 26 == 0x11 && fmt(instr) == 17
+}
+
+/// Checks if the instruction reads its FPR operands as doubles
+pub(crate) fn reads_double(instr: &RustInstruction) -> bool {
+    instr.access_type() == AccessType::DOUBLEFLOAT || is_cop1_double(instr)
+}
+
+/// The `fd` field sits where `sa` is in R-type instructions
+pub(crate) fn fd(instr: &RustInstruction) -> u32 {
+    (instr.raw() >> 6) & 0x1F
+}
+
+/// Checks if a `j`/`jal` at `vram` can reach `target`, which must be in the
+/// 256 MiB region of its delay slot
+pub(crate) fn in_jump_region(vram: u32, target: u32) -> bool {
+    target & 0xF000_0000 == vram.wrapping_add(4) & 0xF000_0000
+}
+
+/// Returns the number of bytes touched by a memory access of the given type
+pub(crate) fn access_size(access_type: AccessType) -> u32 {
+    match access_type {
+        AccessType::BYTE => 1,
+        AccessType::SHORT => 2,
+        AccessType::WORD | AccessType::FLOAT => 4,
+        AccessType::WORD_LEFT | AccessType::WORD_RIGHT => 4,
+        AccessType::DOUBLEWORD | AccessType::DOUBLEFLOAT => 8,
+        AccessType::DOUBLEWORD_LEFT | AccessType::DOUBLEWORD_RIGHT => 8,
+        AccessType::QUADWORD => 16,
+        AccessType::INVALID | AccessType::MAX => 0,
+    }
+}
@@ -0,0 +1,229 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! Fingerprints of functions that survive being linked somewhere else
+
+use std::collections::{BTreeSet, HashMap};
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use rabbitizer::Instruction as RustInstruction;
+use rabbitizer::{InstrId, OperandType};
+
+use crate::fields;
+use crate::instruction::parse_category;
+use crate::relocate::hi_lo_pairs;
+use crate::section::{decode_instructions, decode_words};
+use crate::symbols;
+
+/// Options for `Function.fingerprint` and `matchFingerprints`
+#[napi(object)]
+#[derive(Clone, Copy)]
+pub struct FingerprintOptions {
+    /// Leave out `j`/`jal` targets, `%hi`/`%lo` immediates and branch offsets (defaults to true)
+    pub ignore_relocs: Option<bool>,
+    /// Leave out the GPR and FPR operands (defaults to false)
+    pub ignore_registers: Option<bool>,
+}
+
+/// Options for `matchFingerprints`
+#[napi(object)]
+pub struct MatchOptions {
+    /// Address of the first byte of the ROM (defaults to 0)
+    pub vram: Option<u32>,
+    /// "cpu", "rsp", "r3000gte", "r4000allegrex" or "r5900" (defaults to "cpu")
+    pub category: Option<String>,
+    /// Byte order of the words in the ROM, "big" or "little" (defaults to "big")
+    pub endianness: Option<String>,
+    pub ignore_relocs: Option<bool>,
+    pub ignore_registers: Option<bool>,
+}
+
+/// A known function found in a ROM
+#[napi(object)]
+pub struct FingerprintMatch {
+    pub name: String,
+    pub vram: u32,
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// Register fields cleared by `ignoreRegisters`
+const REGISTER_FIELDS: [(OperandType, u32); 6] = [
+    (OperandType::cpu_rs, 0x03E0_0000),
+    (OperandType::cpu_rt, 0x001F_0000),
+    (OperandType::cpu_rd, 0x0000_F800),
+    (OperandType::cpu_fs, 0x0000_F800),
+    (OperandType::cpu_ft, 0x001F_0000),
+    (OperandType::cpu_fd, 0x0000_07C0),
+];
+
+/// Bits of each instruction a fingerprint keeps
+pub(crate) fn masks(instrs: &[RustInstruction], options: FingerprintOptions) -> Vec<u32> {
+    let ignore_relocs = options.ignore_relocs.unwrap_or(true);
+    let ignore_registers = options.ignore_registers.unwrap_or(false);
+
+    let mut masks = vec![!0u32; instrs.len()];
+    if ignore_relocs {
+        for (hi, los) in hi_lo_pairs(instrs) {
+            for i in std::iter::once(hi).chain(los) {
+                masks[i] &= !0xFFFF;
+            }
+        }
+    }
+    for (instr, mask) in instrs.iter().zip(masks.iter_mut()) {
+        if ignore_relocs {
+            if instr.is_jump_with_address() {
+                *mask &= !0x03FF_FFFF;
+            } else if instr.is_branch() {
+                *mask &= !0xFFFF;
+            }
+        }
+        if ignore_registers {
+            for (operand, bits) in REGISTER_FIELDS {
+                if instr.has_operand_alias(operand) {
+                    *mask &= !bits;
+                }
+            }
+        }
+    }
+    masks
+}
+
+/// Hashes the masked words of a function with 64-bit FNV-1a
+pub(crate) fn fingerprint(instrs: &[RustInstruction], options: FingerprintOptions) -> String {
+    let hash = instrs
+        .iter()
+        .zip(masks(instrs, options))
+        .flat_map(|(instr, mask)| (instr.raw() & mask).to_be_bytes())
+        .fold(0xCBF2_9CE4_8422_2325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+        });
+    format!("{:016x}", hash)
+}
+
+/// An entry of a fingerprint database
+struct Entry {
+    fingerprint: String,
+    size: u32,
+    name: String,
+}
+
+/// Parses lines like `9c3f04d1b2a8e677 0x40 osWritebackDCache`
+fn parse_database(text: &str) -> napi::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.find("//").map_or(line, |position| &line[..position]);
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() {
+            continue;
+        }
+        let invalid = || {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                format!(
+                    "Invalid fingerprint on line {}: {}",
+                    number + 1,
+                    line.trim()
+                ),
+            )
+        };
+        let [fingerprint, size, name] = parts[..] else {
+            return Err(invalid());
+        };
+        let size = symbols::parse_number(size)
+            .filter(|size| *size > 0 && size.is_multiple_of(4))
+            .ok_or_else(invalid)?;
+        if fingerprint.len() != 16 || u64::from_str_radix(fingerprint, 16).is_err() {
+            return Err(invalid());
+        }
+        entries.push(Entry {
+            fingerprint: fingerprint.to_ascii_lowercase(),
+            size,
+            name: name.to_string(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Indices where a function may start: the first word, and the first non-zero
+/// word after each `jr $ra` and its delay slot
+pub(crate) fn function_starts(instrs: &[RustInstruction]) -> Vec<usize> {
+    let mut starts = vec![0];
+    let returns = instrs
+        .iter()
+        .enumerate()
+        .filter(|(_, instr)| instr.unique_id == InstrId::cpu_jr && fields::rs(instr) == 31);
+    for (i, _) in returns {
+        let start = (i + 2..instrs.len()).find(|&start| instrs[start].raw() != 0);
+        starts.extend(start);
+    }
+    starts.dedup();
+    starts
+}
+
+/// Finds the functions of a fingerprint database in a ROM
+///
+/// Only the places where a function may start are tried: the start of the ROM
+/// and the first non-zero word after each `jr $ra` and its delay slot. The
+/// database must have been made with the same `ignoreRelocs` and
+/// `ignoreRegisters` options.
+///
+/// # Arguments
+/// * `rom` - The binary to scan
+/// * `database` - Lines of `fingerprint size name`, as from `Function.fingerprint`; `//` starts a comment
+/// * `options` - The vram of the ROM, how to decode it and the fingerprint options (optional)
+#[napi]
+pub fn match_fingerprints(
+    rom: Buffer,
+    database: String,
+    options: Option<MatchOptions>,
+) -> napi::Result<Vec<FingerprintMatch>> {
+    let options = options.unwrap_or(MatchOptions {
+        vram: None,
+        category: None,
+        endianness: None,
+        ignore_relocs: None,
+        ignore_registers: None,
+    });
+    let fingerprint_options = FingerprintOptions {
+        ignore_relocs: options.ignore_relocs,
+        ignore_registers: options.ignore_registers,
+    };
+    let entries = parse_database(&database)?;
+    let words = decode_words(&rom, options.endianness.as_deref())?;
+    let vram = options.vram.unwrap_or(0);
+    let instrs = decode_instructions(&words, vram, parse_category(options.category.as_deref()));
+
+    let mut by_fingerprint: HashMap<(&str, u32), Vec<&str>> = HashMap::new();
+    for entry in &entries {
+        by_fingerprint
+            .entry((&entry.fingerprint, entry.size))
+            .or_default()
+            .push(&entry.name);
+    }
+    let sizes: BTreeSet<u32> = entries.iter().map(|entry| entry.size).collect();
+
+    let mut matches = Vec::new();
+    for start in function_starts(&instrs) {
+        for &size in &sizes {
+            let Some(window) = instrs.get(start..start + size as usize / 4) else {
+                break;
+            };
+            let fingerprint = fingerprint(window, fingerprint_options);
+            for name in by_fingerprint
+                .get(&(fingerprint.as_str(), size))
+                .into_iter()
+                .flatten()
+            {
+                matches.push(FingerprintMatch {
+                    name: name.to_string(),
+                    vram: vram.wrapping_add(start as u32 * 4),
+                    offset: start as u32 * 4,
+                    size,
+                });
+            }
+        }
+    }
+    Ok(matches)
+}
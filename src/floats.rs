@@ -0,0 +1,239 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::cfg::Cfg;
+use crate::constprop::{ConstantPropagation, Value};
+use crate::dataflow::Dataflow;
+use crate::enums::Abi;
+use crate::fields;
+use crate::registers;
+use crate::section::decode_words;
+
+/// A float literal loaded into an FPR
+#[napi(object)]
+pub struct FloatConstant {
+    /// Address of the instruction completing the load (`mtc1`, `lwc1`, `ldc1`, ...)
+    pub vram: u32,
+    /// Destination FPR, the even one for doubles
+    pub register: String,
+    pub value: f64,
+    pub is_double: bool,
+    /// Address the literal was read from, for loads from rodata
+    pub address: Option<u32>,
+}
+
+/// Where to look for literals loaded from memory
+#[napi(object)]
+pub struct FloatOptions {
+    /// Contents of the rodata section
+    pub rodata: Option<Buffer>,
+    /// Address of the first byte of `rodata`
+    pub rodata_vram: Option<u32>,
+    /// Byte order of `rodata`, "big" (default) or "little"
+    pub endianness: Option<String>,
+    /// Decides the registers clobbered by calls (defaults to O32)
+    pub abi: Option<Abi>,
+}
+
+/// Decoded rodata words
+pub(crate) struct Rodata {
+    vram: u32,
+    words: Vec<u32>,
+    little_endian: bool,
+}
+
+impl Rodata {
+    pub(crate) fn new(data: &[u8], vram: u32, endianness: Option<&str>) -> napi::Result<Self> {
+        Ok(Rodata {
+            vram,
+            words: decode_words(data, endianness)?,
+            little_endian: endianness == Some("little"),
+        })
+    }
+
+    fn word(&self, address: u32) -> Option<u32> {
+        let offset = address.wrapping_sub(self.vram);
+        if !offset.is_multiple_of(4) {
+            return None;
+        }
+        self.words.get((offset / 4) as usize).copied()
+    }
+
+    /// Reads a double, whose high word comes first in big endian data
+    fn doubleword(&self, address: u32) -> Option<u64> {
+        let first = self.word(address)? as u64;
+        let second = self.word(address.wrapping_add(4))? as u64;
+        Some(if self.little_endian {
+            (second << 32) | first
+        } else {
+            (first << 32) | second
+        })
+    }
+}
+
+/// Formats a literal the way it would appear in C source
+pub(crate) fn format_literal(constant: &FloatConstant) -> String {
+    if constant.is_double {
+        format!("{:?}", constant.value)
+    } else {
+        format!("{:?}f", constant.value as f32)
+    }
+}
+
+/// FPRs are named `$fN` under every ABI
+fn single(vram: u32, fpr: u32, word: u32, address: Option<u32>) -> FloatConstant {
+    FloatConstant {
+        vram,
+        register: registers::name(registers::fpr(fpr), Abi::O32),
+        value: f32::from_bits(word) as f64,
+        is_double: false,
+        address,
+    }
+}
+
+fn double(vram: u32, fpr: u32, bits: u64, address: Option<u32>) -> FloatConstant {
+    FloatConstant {
+        vram,
+        register: registers::name(registers::fpr(fpr), Abi::O32),
+        value: f64::from_bits(bits),
+        is_double: true,
+        address,
+    }
+}
+
+/// `fmt` of `dmtc1`, next to `MT` (4) for `mtc1`
+const DMT: u32 = 5;
+
+/// Checks if the GPR moved to an FPR at `index` holds a literal: `$zero`,
+/// or a value whose upper half comes from a `lui`, possibly completed by an
+/// `ori`/`addiu`
+///
+/// Integers moved to be converted, as in `li $t0, 5; mtc1 $t0, $f4`, are
+/// left out.
+fn is_literal(instrs: &[RustInstruction], dataflow: &Dataflow, index: usize, reg: u32) -> bool {
+    // Every definition reaching `index` must pass `check`
+    let all_defs = |index: usize, reg: u32, check: &dyn Fn(usize) -> bool| {
+        let defs = dataflow.reaching(index, reg as usize);
+        !defs.is_empty() && defs.iter().all(|def| def.is_some_and(check))
+    };
+    let from_hi = |index: usize, reg: u32| all_defs(index, reg, &|def| instrs[def].can_be_hi());
+    reg == 0
+        || all_defs(index, reg, &|def| {
+            let instr = &instrs[def];
+            instr.can_be_hi()
+                || (instr.can_be_lo()
+                    && !instr.does_dereference()
+                    && from_hi(def, fields::rs(instr)))
+        })
+}
+
+/// Finds the float literals materialized by a function
+///
+/// Recognizes constants moved from GPRs (`lui $at, 0x3F80; mtc1 $at, $f4`)
+/// when their upper half comes from a `lui`, doubles built from two `mtc1`
+/// into an even/odd FPR pair, `dmtc1`, and `lwc1`/`ldc1` loads from known
+/// addresses inside `rodata`. A pair of `mtc1`
+/// is only reported as a double when the even FPR is then used as one, in
+/// which case the halves are not reported as singles.
+pub(crate) fn find(
+    instrs: &[RustInstruction],
+    abi: Abi,
+    rodata: Option<&Rodata>,
+) -> Vec<FloatConstant> {
+    let propagation = ConstantPropagation::build(instrs, abi);
+    let dataflow = Dataflow::build(instrs, abi);
+    let gpr_value = |index: usize, reg: u32| {
+        propagation
+            .state_before(instrs, index)
+            .map(|state| state.get(reg))
+            .unwrap_or(Value::Unknown)
+    };
+
+    // Keyed by instruction index, so doubles can replace their halves
+    let mut found: BTreeMap<usize, FloatConstant> = BTreeMap::new();
+
+    for block in Cfg::build(instrs).blocks {
+        // FPRs last written by an `mtc1` of a known word in this block
+        let mut moved: BTreeMap<u32, (usize, u32)> = BTreeMap::new();
+
+        for index in block.start..block.end {
+            let instr = &instrs[index];
+
+            // Doubles are recognized when they are used
+            if fields::reads_double(instr) && !instr.does_load() {
+                let mut operands = Vec::new();
+                if instr.reads_fs() {
+                    operands.push(fields::fs(instr));
+                }
+                if instr.reads_ft() {
+                    operands.push(fields::ft(instr));
+                }
+                for fpr in operands.into_iter().filter(|fpr| fpr.is_multiple_of(2)) {
+                    if let (Some(&(low_index, low)), Some(&(high_index, high))) =
+                        (moved.get(&fpr), moved.get(&(fpr + 1)))
+                    {
+                        let last = low_index.max(high_index);
+                        let bits = ((high as u64) << 32) | low as u64;
+                        found.remove(&low_index);
+                        found.remove(&high_index);
+                        found.insert(last, double(instrs[last].vram, fpr, bits, None));
+                    }
+                }
+            }
+
+            // GPR to FPR moves, `mtc1` and `dmtc1`
+            let is_move = instr.is_float() && instr.reads_rt() && instr.modifies_fs();
+            // FPR loads, `lwc1` and `ldc1`
+            let is_load = instr.is_float() && instr.does_load();
+
+            if is_move && is_literal(instrs, &dataflow, index, fields::rt(instr)) {
+                if let Value::Const(value) = gpr_value(index, fields::rt(instr)) {
+                    let fpr = fields::fs(instr);
+                    if fields::fmt(instr) == DMT {
+                        found.insert(index, double(instr.vram, fpr, value, None));
+                    } else {
+                        moved.insert(fpr, (index, value as u32));
+                        found.insert(index, single(instr.vram, fpr, value as u32, None));
+                        continue;
+                    }
+                }
+            } else if is_load {
+                let base = gpr_value(index, fields::rs(instr));
+                let address = match (base, rodata) {
+                    (Value::Const(base), Some(rodata)) => Some((
+                        (base as u32).wrapping_add(instr.processed_immediate() as u32),
+                        rodata,
+                    )),
+                    _ => None,
+                };
+                if let Some((address, rodata)) = address {
+                    let fpr = fields::ft(instr);
+                    let constant = if instr.is_double() {
+                        rodata
+                            .doubleword(address)
+                            .map(|bits| double(instr.vram, fpr, bits, Some(address)))
+                    } else {
+                        rodata
+                            .word(address)
+                            .map(|word| single(instr.vram, fpr, word, Some(address)))
+                    };
+                    if let Some(constant) = constant {
+                        found.insert(index, constant);
+                    }
+                }
+            }
+
+            // Any other write to an FPR forgets what was moved there
+            let defs = registers::defs(instr);
+            moved.retain(|&fpr, _| !registers::contains(defs, registers::fpr(fpr)));
+        }
+    }
+
+    found.into_values().collect()
+}
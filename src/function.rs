@@ -0,0 +1,421 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use napi_derive::napi;
+use rabbitizer::InstrCategory;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::assembler;
+use crate::cfg::{BasicBlock, Cfg};
+use crate::compiler::{self, CompilerGuess};
+use crate::constprop::{ConstantPropagation, RegisterValue};
+use crate::cycles::{self, CycleEstimate};
+use crate::dataflow::{Dataflow, ReachingDefinitions};
+use crate::enums::Abi;
+use crate::fingerprint::{self, FingerprintOptions};
+use crate::floats::{self, FloatConstant, FloatOptions, Rodata};
+use crate::handwritten::{self, HandwrittenScore};
+use crate::instruction::{category_name, parse_category, Instruction};
+use crate::pseudoc;
+use crate::registers;
+use crate::section::decode_instructions;
+use crate::signature::{self, Signature};
+use crate::stack::{self, StackFrame};
+use crate::structs::{self, StructAccesses};
+
+/// A single function: a contiguous run of instructions starting at an entry point
+#[napi]
+pub struct Function {
+    vram: u32,
+    category: InstrCategory,
+    instructions: Vec<RustInstruction>,
+}
+
+impl Function {
+    pub(crate) fn from_instrs(instructions: Vec<RustInstruction>, vram: u32) -> Self {
+        let category = instructions
+            .first()
+            .map(|instr| instr.category)
+            .unwrap_or(InstrCategory::CPU);
+
+        Function {
+            vram,
+            category,
+            instructions,
+        }
+    }
+
+    pub(crate) fn instrs(&self) -> &[RustInstruction] {
+        &self.instructions
+    }
+
+    fn find_floats(&self, options: Option<FloatOptions>) -> napi::Result<Vec<FloatConstant>> {
+        let options = options.unwrap_or(FloatOptions {
+            rodata: None,
+            rodata_vram: None,
+            endianness: None,
+            abi: None,
+        });
+        let rodata = match &options.rodata {
+            Some(data) => Some(Rodata::new(
+                data,
+                options.rodata_vram.unwrap_or(0),
+                options.endianness.as_deref(),
+            )?),
+            None => None,
+        };
+
+        Ok(floats::find(
+            &self.instructions,
+            options.abi.unwrap_or(Abi::O32),
+            rodata.as_ref(),
+        ))
+    }
+
+    /// Returns the index of the instruction at `vram`
+    fn index_of(&self, vram: u32) -> napi::Result<usize> {
+        let offset = vram.wrapping_sub(self.vram);
+        let index = (offset / 4) as usize;
+        if !offset.is_multiple_of(4) || index >= self.instructions.len() {
+            return Err(napi::Error::new(
+                napi::Status::InvalidArg,
+                format!(
+                    "Address 0x{:08X} is not an instruction of this function",
+                    vram
+                ),
+            ));
+        }
+        Ok(index)
+    }
+}
+
+#[napi]
+impl Function {
+    /// Creates a Function from its instruction words
+    #[napi(factory)]
+    pub fn from_word_list(words: Vec<u32>, vram: u32, category: Option<String>) -> Self {
+        let category = parse_category(category.as_deref());
+        let instructions = decode_instructions(&words, vram, category);
+
+        Function {
+            vram,
+            category,
+            instructions,
+        }
+    }
+
+    /// Gets the entry point of the function
+    #[napi(getter)]
+    pub fn vram(&self) -> u32 {
+        self.vram
+    }
+
+    /// Gets the size of the function in bytes
+    #[napi(getter)]
+    pub fn size(&self) -> u32 {
+        self.instructions.len() as u32 * 4
+    }
+
+    /// Gets the instruction category as a string
+    #[napi(getter)]
+    pub fn category(&self) -> String {
+        category_name(self.category).to_string()
+    }
+
+    /// Gets the instructions of the function in address order
+    #[napi]
+    pub fn instructions(&self) -> Vec<Instruction> {
+        self.instructions
+            .iter()
+            .cloned()
+            .map(Instruction::from_inner)
+            .collect()
+    }
+
+    /// Analyzes the stack frame set up by the function prologue
+    ///
+    /// # Arguments
+    /// * `abi` - Decides which registers are callee-saved (optional, defaults to O32)
+    #[napi]
+    pub fn stack_frame(&self, abi: Option<Abi>) -> StackFrame {
+        stack::analyze(&self.instructions, abi.unwrap_or(Abi::O32))
+    }
+
+    /// Splits the function into basic blocks
+    ///
+    /// Delay slots belong to the block of their branch, except for
+    /// branch-likely instructions, whose delay slot is a block of its own.
+    #[napi]
+    pub fn basic_blocks(&self) -> Vec<BasicBlock> {
+        Cfg::build(&self.instructions).to_napi(&self.instructions)
+    }
+
+    /// Gets the registers live on entry to a basic block
+    ///
+    /// Calls are assumed to read the argument registers and clobber the
+    /// caller-saved ones, and returns to read the return value registers.
+    ///
+    /// # Arguments
+    /// * `block` - Address of the first instruction of the block
+    /// * `abi` - Decides the argument and caller-saved registers and their names (optional, defaults to O32)
+    #[napi]
+    pub fn live_in(&self, block: u32, abi: Option<Abi>) -> napi::Result<Vec<String>> {
+        let index = self.index_of(block)?;
+        let abi = abi.unwrap_or(Abi::O32);
+        let dataflow = Dataflow::build(&self.instructions, abi);
+        let b = dataflow.cfg.block_starting_at(index).ok_or_else(|| {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("No basic block starts at 0x{:08X}", block),
+            )
+        })?;
+
+        Ok(registers::iter(dataflow.live_in(b))
+            .map(|reg| registers::name(reg, abi))
+            .collect())
+    }
+
+    /// Estimates the cycles a basic block takes to run once
+    ///
+    /// Timings follow the function's category: "cpu" is the N64's VR4300
+    /// (R4300i), "r3000gte" the PlayStation's R3000A and GTE, "rsp" the RSP
+    /// with its scalar and vector units issuing in pairs, "r5900" and
+    /// "r4000allegrex" the PS2 and PSP CPUs. The tables are approximate, caches
+    /// are assumed warm.
+    ///
+    /// # Arguments
+    /// * `block` - Address of the first instruction of the block
+    #[napi]
+    pub fn estimate_cycles(&self, block: u32) -> napi::Result<CycleEstimate> {
+        let index = self.index_of(block)?;
+        let cfg = Cfg::build(&self.instructions);
+        let b = cfg.block_starting_at(index).ok_or_else(|| {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("No basic block starts at 0x{:08X}", block),
+            )
+        })?;
+        let block = &cfg.blocks[b];
+
+        Ok(cycles::estimate(
+            &self.instructions[block.start..block.end],
+            self.category,
+        ))
+    }
+
+    /// Gets the definitions of `reg` that may reach the instruction at `vram`
+    ///
+    /// # Arguments
+    /// * `reg` - Register name under `abi`, like `$a0`, `$f12`, `hi` or `lo`
+    /// * `abi` - Decides the argument and caller-saved registers and their names (optional, defaults to O32)
+    #[napi]
+    pub fn definitions_reaching(
+        &self,
+        vram: u32,
+        reg: String,
+        abi: Option<Abi>,
+    ) -> napi::Result<ReachingDefinitions> {
+        let index = self.index_of(vram)?;
+        let abi = abi.unwrap_or(Abi::O32);
+        let reg = registers::parse(&reg, abi)?;
+        let dataflow = Dataflow::build(&self.instructions, abi);
+        let reaching = dataflow.reaching(index, reg);
+
+        Ok(ReachingDefinitions {
+            definitions: reaching
+                .iter()
+                .flatten()
+                .map(|&i| self.instructions[i].vram)
+                .collect(),
+            from_entry: reaching.contains(&None),
+        })
+    }
+
+    /// Gets the addresses of the instructions that may read the value of `reg` written at `vram`
+    ///
+    /// # Arguments
+    /// * `reg` - Register name under `abi`, like `$v0`, `$f0`, `hi` or `lo`
+    /// * `abi` - Decides the argument and caller-saved registers and their names (optional, defaults to O32)
+    #[napi]
+    pub fn uses_of(&self, vram: u32, reg: String, abi: Option<Abi>) -> napi::Result<Vec<u32>> {
+        let index = self.index_of(vram)?;
+        let abi = abi.unwrap_or(Abi::O32);
+        let reg_index = registers::parse(&reg, abi)?;
+        let dataflow = Dataflow::build(&self.instructions, abi);
+        let uses = dataflow.uses_of(index, reg_index).ok_or_else(|| {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("Instruction at 0x{:08X} does not write {}", vram, reg),
+            )
+        })?;
+
+        Ok(uses.iter().map(|&i| self.instructions[i].vram).collect())
+    }
+
+    /// Guesses the parameters and return value of the function
+    ///
+    /// # Arguments
+    /// * `abi` - Decides the argument registers (optional, defaults to O32)
+    #[napi]
+    pub fn signature(&self, abi: Option<Abi>) -> Signature {
+        signature::infer(&self.instructions, abi.unwrap_or(Abi::O32))
+    }
+
+    /// Gets the value a GPR is known to hold right before the instruction at `vram` runs
+    ///
+    /// Constants are followed through arithmetic, logic and shift instructions
+    /// (including 64-bit `dsll32` sequences) and through stack spills. Stack
+    /// addresses are reported as offsets from `$sp` on function entry. Returns
+    /// `null` if the value is not known.
+    ///
+    /// # Arguments
+    /// * `register` - GPR name under `abi`, like `$a0` or `$4`
+    /// * `abi` - Decides the registers clobbered by calls and their names (optional, defaults to O32)
+    #[napi]
+    pub fn value_at(
+        &self,
+        vram: u32,
+        register: String,
+        abi: Option<Abi>,
+    ) -> napi::Result<Option<RegisterValue>> {
+        let index = self.index_of(vram)?;
+        let abi = abi.unwrap_or(Abi::O32);
+        let reg = registers::parse(&register, abi)?;
+        if reg >= 32 {
+            return Err(napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("Only GPR values are tracked, got '{}'", register),
+            ));
+        }
+
+        let propagation = ConstantPropagation::build(&self.instructions, abi);
+        Ok(propagation
+            .state_before(&self.instructions, index)
+            .and_then(|state| state.get(reg as u32).to_napi()))
+    }
+
+    /// Finds the float literals loaded into FPRs
+    ///
+    /// # Arguments
+    /// * `options` - Rodata to resolve `lwc1`/`ldc1` loads from, and the ABI (optional)
+    #[napi]
+    pub fn float_constants(
+        &self,
+        options: Option<FloatOptions>,
+    ) -> napi::Result<Vec<FloatConstant>> {
+        self.find_floats(options)
+    }
+
+    /// Disassembles the function, one instruction per line
+    ///
+    /// Lines look like `/* 80000000 3C013F80 */  lui         $at, 0x3F80`.
+    ///
+    /// # Arguments
+    /// * `annotate_floats` - Appends comments like `/* 1.0f */` to the instructions loading float literals (optional)
+    /// * `options` - Passed to `floatConstants` when annotating (optional)
+    #[napi]
+    pub fn disassemble(
+        &self,
+        annotate_floats: Option<bool>,
+        options: Option<FloatOptions>,
+    ) -> napi::Result<String> {
+        let floats = if annotate_floats.unwrap_or(false) {
+            self.find_floats(options)?
+        } else {
+            Vec::new()
+        };
+
+        let lines: Vec<String> = self
+            .instructions
+            .iter()
+            .map(|instr| {
+                let mut line = format!(
+                    "/* {:08X} {:08X} */  {}",
+                    instr.vram,
+                    instr.raw(),
+                    assembler::disassemble(instr, None)
+                );
+                if let Some(constant) = floats.iter().find(|constant| constant.vram == instr.vram) {
+                    line.push_str(&format!("  /* {} */", floats::format_literal(constant)));
+                }
+                line
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
+    /// Lifts the function to C-like pseudocode
+    ///
+    /// The output is a starting point for matching and is not meant to
+    /// compile: registers are used as variables, memory accesses look like
+    /// `*(s32*)(a0 + 0x10)`, calls get the argument registers set up before
+    /// them, and branches become `if`/`else` and `do`/`while` where the control
+    /// flow allows it, or `goto` otherwise.
+    ///
+    /// # Arguments
+    /// * `abi` - Decides the argument and return registers (optional, defaults to O32)
+    #[napi]
+    pub fn to_pseudo_c(&self, abi: Option<Abi>) -> String {
+        pseudoc::lift(&self.instructions, abi.unwrap_or(Abi::O32))
+    }
+
+    /// Groups the function's loads and stores by base pointer
+    ///
+    /// Each group lists the accessed offsets with their size, signedness and
+    /// whether they are floats, read or written, and comes with a starting
+    /// struct definition. Bases are argument registers, the stack frame,
+    /// fixed addresses and pointers loaded from memory; accesses through any
+    /// other pointer are left out.
+    ///
+    /// # Arguments
+    /// * `abi` - Decides the argument registers (optional, defaults to O32)
+    #[napi]
+    pub fn struct_accesses(&self, abi: Option<Abi>) -> Vec<StructAccesses> {
+        structs::collect(&self.instructions, abi.unwrap_or(Abi::O32))
+    }
+
+    /// Guesses which compiler produced the function
+    ///
+    /// Returns every supported compiler, most likely first, with a confidence
+    /// and the signals found in its favour. This is a heuristic over a single
+    /// function: short functions often carry no telling pattern at all, so
+    /// aggregate the guesses of several functions before trusting one.
+    ///
+    /// # Arguments
+    /// * `abi` - Decides the callee-saved registers (optional, defaults to O32)
+    #[napi]
+    pub fn guess_compiler(&self, abi: Option<Abi>) -> Vec<CompilerGuess> {
+        compiler::classify(&self.instructions, abi.unwrap_or(Abi::O32))
+    }
+
+    /// Scores how likely the function is to be handwritten assembly
+    ///
+    /// Unlike `Instruction.isLikelyHandwritten`, this also weighs signals that
+    /// only stand out over the whole function, such as `$at` used as a general
+    /// register or a frame set up in an unusual way. A score of 0.5 or more is
+    /// a good cue to keep the function in a `.s` file.
+    ///
+    /// # Arguments
+    /// * `abi` - Decides the callee-saved registers (optional, defaults to O32)
+    #[napi]
+    pub fn handwritten_score(&self, abi: Option<Abi>) -> HandwrittenScore {
+        handwritten::score(&self.instructions, abi.unwrap_or(Abi::O32))
+    }
+
+    /// Hashes the function into a fingerprint that stays the same wherever it is linked
+    ///
+    /// The hash covers the opcodes and, unless ignored, the registers. With
+    /// `ignoreRelocs`, `j`/`jal` targets, `%hi`/`%lo` immediates and branch
+    /// offsets are left out, so the same library function matches across games.
+    ///
+    /// # Arguments
+    /// * `options` - Which fields to leave out (optional)
+    #[napi]
+    pub fn fingerprint(&self, options: Option<FingerprintOptions>) -> String {
+        let options = options.unwrap_or(FingerprintOptions {
+            ignore_relocs: None,
+            ignore_registers: None,
+        });
+        fingerprint::fingerprint(&self.instructions, options)
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use napi_derive::napi;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::enums::Abi;
+use crate::registers;
+use crate::stack::{self, SP};
+
+const AT: usize = 1;
+const K0: usize = 26;
+const K1: usize = 27;
+
+/// How likely a function is to be handwritten assembly
+#[napi(object)]
+pub struct HandwrittenScore {
+    /// Between 0 (nothing unusual) and 1, 0.5 or more suggests routing the
+    /// function to a `.s` file
+    pub score: f64,
+    /// The signals found, each with the address of its first occurrence
+    pub reasons: Vec<String>,
+}
+
+struct Signals {
+    total: f64,
+    reasons: Vec<String>,
+}
+
+impl Signals {
+    /// Counts a signal once, however often it shows up
+    fn add(&mut self, weight: f64, reason: &str, vram: u32) {
+        if self.reasons.iter().any(|r| r.starts_with(reason)) {
+            return;
+        }
+        self.total += weight;
+        self.reasons.push(format!("{} at 0x{:08X}", reason, vram));
+    }
+}
+
+fn is_cop0(instr: &RustInstruction) -> bool {
+    instr.raw() >> 26 == 0x10 || instr.unique_id == InstrId::cpu_cache
+}
+
+/// Checks for the ways compilers write `$at`: `lui`, `li`, the address
+/// arithmetic of `%hi`/`%lo` pairs and the comparison of `blt`/`bge`
+fn compiler_writes_at(instr: &RustInstruction) -> bool {
+    matches!(
+        instr.unique_id,
+        InstrId::cpu_lui
+            | InstrId::cpu_addiu
+            | InstrId::cpu_ori
+            | InstrId::cpu_addu
+            | InstrId::cpu_slt
+            | InstrId::cpu_sltu
+            | InstrId::cpu_slti
+            | InstrId::cpu_sltiu
+    )
+}
+
+/// Checks for the ways compilers write `$sp`: adjusting it, `subu` included
+/// for `alloca`, and copying it back, as in `move $sp, $fp`
+fn compiler_writes_sp(instr: &RustInstruction) -> bool {
+    matches!(
+        instr.unique_id,
+        InstrId::cpu_addiu
+            | InstrId::cpu_daddiu
+            | InstrId::cpu_addu
+            | InstrId::cpu_daddu
+            | InstrId::cpu_subu
+            | InstrId::cpu_dsubu
+            | InstrId::cpu_move
+    )
+}
+
+/// Scores a function on signals that compilers do not produce
+///
+/// Per instruction: rabbitizer's `is_likely_handwritten` and
+/// `not_emitted_by_compilers`, `eret`, COP0 and `cache` instructions, `$k0`
+/// and `$k1`, and `$at` written by anything but the `lui`/`li`/address
+/// arithmetic/comparisons compilers use it for. Structurally: a stack frame
+/// not allocated by the first instruction or not 8-byte aligned, and `$sp`
+/// set to anything but an adjustment of itself (`subu` included, for
+/// `alloca`) or a `move` back from the frame pointer. Each kind of signal counts
+/// once, and the weights add up to a score that approaches 1.
+pub(crate) fn score(instrs: &[RustInstruction], abi: Abi) -> HandwrittenScore {
+    let mut signals = Signals {
+        total: 0.0,
+        reasons: Vec::new(),
+    };
+
+    for instr in instrs {
+        let vram = instr.vram;
+        let regs = registers::uses(instr) | registers::defs(instr);
+
+        let uses_k_registers = registers::contains(regs, K0) || registers::contains(regs, K1);
+        if instr.unique_id == InstrId::cpu_eret {
+            signals.add(2.0, "eret", vram);
+        } else if is_cop0(instr) {
+            signals.add(2.0, "COP0 access", vram);
+        } else if instr.not_emitted_by_compilers() {
+            signals.add(2.0, "instruction not emitted by compilers", vram);
+        } else if !uses_k_registers && instr.is_likely_handwritten() {
+            signals.add(1.0, "likely handwritten instruction", vram);
+        }
+        if uses_k_registers {
+            signals.add(2.0, "$k0/$k1 used", vram);
+        }
+        if registers::contains(registers::defs(instr), AT) && !compiler_writes_at(instr) {
+            signals.add(1.0, "$at used as a general register", vram);
+        }
+        if instr.destination_gpr() == Some(SP) && !compiler_writes_sp(instr) {
+            signals.add(2.0, "$sp set directly", vram);
+        }
+    }
+
+    let frame = stack::analyze(instrs, abi);
+    if let Some(allocation) = frame.allocation {
+        if instrs.first().is_some_and(|first| first.vram != allocation) {
+            signals.add(
+                1.0,
+                "stack frame allocated after the first instruction",
+                allocation,
+            );
+        }
+        if !frame.size.is_multiple_of(8) {
+            signals.add(1.0, "stack frame not 8-byte aligned", allocation);
+        }
+    }
+
+    HandwrittenScore {
+        score: 1.0 - (-signals.total / 2.0).exp(),
+        reasons: signals.reasons,
+    }
+}
@@ -3,13 +3,112 @@
 
 use napi_derive::napi;
 use rabbitizer::Instruction as RustInstruction;
-use rabbitizer::InstrCategory;
+use rabbitizer::{InstrCategory, OperandType};
+
+use crate::assembler;
+use crate::enums::InstrId;
+use crate::fields;
+
+/// Parses a category string as accepted by the JS API, defaulting to "cpu"
+pub(crate) fn parse_category(category: Option<&str>) -> InstrCategory {
+    match category.unwrap_or("cpu") {
+        "rsp" => InstrCategory::RSP,
+        "r3000gte" => InstrCategory::R3000GTE,
+        "r4000allegrex" => InstrCategory::R4000ALLEGREX,
+        "r5900" => InstrCategory::R5900,
+        _ => InstrCategory::CPU,
+    }
+}
+
+/// Returns the JS API name of a category
+pub(crate) fn category_name(category: InstrCategory) -> &'static str {
+    match category {
+        InstrCategory::CPU => "cpu",
+        InstrCategory::RSP => "rsp",
+        InstrCategory::R3000GTE => "r3000gte",
+        InstrCategory::R4000ALLEGREX => "r4000allegrex",
+        InstrCategory::R5900 => "r5900",
+        InstrCategory::MAX => "cpu",
+    }
+}
+
+/// The category an `InstrId` belongs to
+fn id_category(id: InstrId) -> InstrCategory {
+    use rabbitizer::InstrId as Id;
+
+    match id as u32 {
+        id if id < Id::cpu_MAX as u32 => InstrCategory::CPU,
+        id if id < Id::rsp_MAX as u32 => InstrCategory::RSP,
+        id if id < Id::r3000gte_MAX as u32 => InstrCategory::R3000GTE,
+        id if id < Id::r4000allegrex_MAX as u32 => InstrCategory::R4000ALLEGREX,
+        _ => InstrCategory::R5900,
+    }
+}
+
+fn invalid_arg(message: String) -> napi::Error {
+    napi::Error::new(napi::Status::InvalidArg, message)
+}
+
+/// Operand values for `Instruction.build`
+#[napi(object)]
+pub struct InstructionFields {
+    pub rs: Option<u32>,
+    pub rt: Option<u32>,
+    pub rd: Option<u32>,
+    pub sa: Option<u32>,
+    pub fs: Option<u32>,
+    pub ft: Option<u32>,
+    pub fd: Option<u32>,
+    /// The 16-bit immediate, either signed or unsigned
+    pub imm: Option<i32>,
+    /// Address a jump or branch goes to
+    pub target: Option<u32>,
+}
 
 #[napi]
 pub struct Instruction {
     inner: RustInstruction,
 }
 
+impl Instruction {
+    pub(crate) fn from_inner(inner: RustInstruction) -> Self {
+        Instruction { inner }
+    }
+
+    fn with_word(&self, word: u32) -> Self {
+        Instruction {
+            inner: RustInstruction::new(word, self.inner.vram, self.inner.category),
+        }
+    }
+
+    /// Replaces the `bits` wide field at `shift`, if the instruction has
+    /// `operand`
+    fn with_field(
+        &self,
+        operand: OperandType,
+        name: &str,
+        value: u32,
+        shift: u32,
+        bits: u32,
+    ) -> napi::Result<Self> {
+        if !self.inner.has_operand_alias(operand) {
+            return Err(invalid_arg(format!(
+                "'{}' has no {} operand",
+                self.inner.opcode_name(),
+                name
+            )));
+        }
+        if value >> bits != 0 {
+            return Err(invalid_arg(format!(
+                "Value {} does not fit in the {} bits of {}",
+                value, bits, name
+            )));
+        }
+        let mask = ((1 << bits) - 1) << shift;
+        Ok(self.with_word((self.inner.raw() & !mask) | (value << shift)))
+    }
+}
+
 #[napi]
 impl Instruction {
     /// Creates a new Instruction from a 32-bit word
@@ -21,13 +120,7 @@ impl Instruction {
     #[napi(constructor)]
     pub fn new(word: u32, vram: Option<u32>, category: Option<String>) -> napi::Result<Self> {
         let vram = vram.unwrap_or(0);
-        let category_enum = match category.as_deref().unwrap_or("cpu") {
-            "rsp" => InstrCategory::RSP,
-            "r3000gte" => InstrCategory::R3000GTE,
-            "r4000allegrex" => InstrCategory::R4000ALLEGREX,
-            "r5900" => InstrCategory::R5900,
-            "cpu" | _ => InstrCategory::CPU,
-        };
+        let category_enum = parse_category(category.as_deref());
 
         Ok(Instruction {
             inner: RustInstruction::new(word, vram, category_enum),
@@ -37,8 +130,11 @@ impl Instruction {
     /// Disassembles the instruction to assembly string
     #[napi]
     pub fn disassemble(&self, immediate_override: Option<String>, vram: Option<i32>) -> String {
-        let vram = vram.unwrap_or(self.inner.vram as i32);
-        self.inner.disassemble(immediate_override.as_deref(), vram)
+        // The second argument of rabbitizer's `disassemble` pads the mnemonic,
+        // so another vram is a copy decoded there
+        let vram = vram.map_or(self.inner.vram, |vram| vram as u32);
+        let instr = RustInstruction::new(self.inner.raw(), vram, self.inner.category);
+        instr.disassemble(immediate_override.as_deref(), 0)
     }
 
     /// Gets the instruction word
@@ -56,14 +152,7 @@ impl Instruction {
     /// Gets the instruction category as a string
     #[napi(getter)]
     pub fn category(&self) -> String {
-        match self.inner.category {
-            InstrCategory::CPU => "cpu".to_string(),
-            InstrCategory::RSP => "rsp".to_string(),
-            InstrCategory::R3000GTE => "r3000gte".to_string(),
-            InstrCategory::R4000ALLEGREX => "r4000allegrex".to_string(),
-            InstrCategory::R5900 => "r5900".to_string(),
-            InstrCategory::MAX => "cpu".to_string(),
-        }
+        category_name(self.inner.category).to_string()
     }
 
     // ==================== Bit field getters ====================
@@ -491,4 +580,134 @@ impl Instruction {
             rabbitizer::InstrSuffix::ALL_MAX => 0,
         }
     }
+
+    // ==================== Building and editing ====================
+
+    /// Creates an instruction from its ID and operands
+    ///
+    /// Operands not given are 0. Fails if the instruction has no such
+    /// operand or the value does not fit in it.
+    ///
+    /// # Arguments
+    /// * `id` - The instruction to build, which also gives the category
+    /// * `fields` - Values of the operands (optional)
+    /// * `vram` - The virtual address (optional, defaults to 0)
+    #[napi(factory)]
+    pub fn build(
+        id: InstrId,
+        fields: Option<InstructionFields>,
+        vram: Option<u32>,
+    ) -> napi::Result<Self> {
+        let category = id_category(id);
+        let word = assembler::template_word(category, id as u32)
+            .ok_or_else(|| invalid_arg(format!("InstrId {} has no encoding", id as u32)))?;
+        let mut instr = Instruction {
+            inner: RustInstruction::new(word, vram.unwrap_or(0), category),
+        };
+        let Some(fields) = fields else {
+            return Ok(instr);
+        };
+
+        let registers = [
+            (fields.rs, OperandType::cpu_rs, "rs", 21),
+            (fields.rt, OperandType::cpu_rt, "rt", 16),
+            (fields.rd, OperandType::cpu_rd, "rd", 11),
+            (fields.sa, OperandType::cpu_sa, "sa", 6),
+            (fields.fs, OperandType::cpu_fs, "fs", 11),
+            (fields.ft, OperandType::cpu_ft, "ft", 16),
+            (fields.fd, OperandType::cpu_fd, "fd", 6),
+        ];
+        for (value, operand, name, shift) in registers {
+            if let Some(value) = value {
+                instr = instr.with_field(operand, name, value, shift, 5)?;
+            }
+        }
+        if let Some(imm) = fields.imm {
+            instr = instr.with_immediate(imm)?;
+        }
+        if let Some(target) = fields.target {
+            instr = if instr.inner.is_jump_with_address() {
+                instr.with_target(target)?
+            } else {
+                instr.with_branch_target(target)?
+            };
+        }
+        Ok(instr)
+    }
+
+    /// Returns a copy with the `rs` register replaced
+    #[napi]
+    pub fn with_rs(&self, rs: u32) -> napi::Result<Instruction> {
+        self.with_field(OperandType::cpu_rs, "rs", rs, 21, 5)
+    }
+
+    /// Returns a copy with the `rt` register replaced
+    #[napi]
+    pub fn with_rt(&self, rt: u32) -> napi::Result<Instruction> {
+        self.with_field(OperandType::cpu_rt, "rt", rt, 16, 5)
+    }
+
+    /// Returns a copy with the 16-bit immediate replaced
+    ///
+    /// # Arguments
+    /// * `imm` - The new immediate, from -0x8000 to 0xFFFF
+    #[napi]
+    pub fn with_immediate(&self, imm: i32) -> napi::Result<Instruction> {
+        if !(-0x8000..=0xFFFF).contains(&imm) {
+            return Err(invalid_arg(format!(
+                "Immediate {} does not fit in 16 bits",
+                imm
+            )));
+        }
+        self.with_field(
+            OperandType::cpu_immediate,
+            "immediate",
+            imm as u32 & 0xFFFF,
+            0,
+            16,
+        )
+    }
+
+    /// Returns a copy of a `j` or `jal` going to `target`
+    ///
+    /// The target must be in the same 256 MiB region as the delay slot.
+    #[napi]
+    pub fn with_target(&self, target: u32) -> napi::Result<Instruction> {
+        if !self.inner.is_jump_with_address() {
+            return Err(invalid_arg(format!(
+                "'{}' is not a jump with a target",
+                self.inner.opcode_name()
+            )));
+        }
+        if !target.is_multiple_of(4) || !fields::in_jump_region(self.inner.vram, target) {
+            return Err(invalid_arg(format!(
+                "Jump target 0x{:08X} is not reachable from 0x{:08X}",
+                target, self.inner.vram
+            )));
+        }
+        let word = (self.inner.raw() & !0x03FF_FFFF) | ((target >> 2) & 0x03FF_FFFF);
+        Ok(self.with_word(word))
+    }
+
+    /// Returns a copy of a branch going to `target`
+    ///
+    /// The target must be within 128 KiB of the delay slot.
+    #[napi]
+    pub fn with_branch_target(&self, target: u32) -> napi::Result<Instruction> {
+        if !self.inner.is_branch() {
+            return Err(invalid_arg(format!(
+                "'{}' is not a branch",
+                self.inner.opcode_name()
+            )));
+        }
+        let offset = target.wrapping_sub(self.inner.vram.wrapping_add(4)) as i32;
+        if offset % 4 != 0 || !(-0x20000..0x20000).contains(&offset) {
+            return Err(invalid_arg(format!(
+                "Branch target 0x{:08X} is not reachable from 0x{:08X}",
+                target, self.inner.vram
+            )));
+        }
+        let word = (self.inner.raw() & !0xFFFF) | ((offset >> 2) as u32 & 0xFFFF);
+        Ok(self.with_word(word))
+    }
 }
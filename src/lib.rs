@@ -9,11 +9,68 @@ mod instruction;
 mod enums;
 mod config;
 mod utils;
+mod assembler;
+mod block;
+mod callgraph;
+mod cfg;
+mod compiler;
+mod constprop;
+mod cycles;
+mod dataflow;
+mod diff;
+mod fields;
+mod fingerprint;
+mod floats;
+mod function;
+mod handwritten;
+mod lint;
+mod patch;
+mod pseudoc;
+mod registers;
+mod relocate;
+mod rsp;
+mod search;
+mod section;
+mod sigdb;
+mod signature;
+mod stack;
+mod statistics;
+mod structs;
+mod symbols;
+mod tracker;
+mod traversal;
+mod xref;
 
-pub use instruction::Instruction;
+pub use instruction::{Instruction, InstructionFields};
 pub use enums::*;
 pub use config::*;
 pub use utils::*;
+pub use assembler::{assemble, AssembleOptions};
+pub use block::{assemble_block, AssembleBlockOptions, AssembledBlock, Relocation, RelocationType};
+pub use callgraph::{CallGraph, CallSite};
+pub use cfg::BasicBlock;
+pub use compiler::{Compiler, CompilerGuess};
+pub use constprop::RegisterValue;
+pub use cycles::{CycleEstimate, InstructionCycles};
+pub use dataflow::ReachingDefinitions;
+pub use diff::{diff_sections, DiffKind, DiffOptions, DiffRow};
+pub use fingerprint::{match_fingerprints, FingerprintMatch, FingerprintOptions, MatchOptions};
+pub use floats::{FloatConstant, FloatOptions};
+pub use function::Function;
+pub use handwritten::HandwrittenScore;
+pub use lint::{lint_section, LintIssue, LintSeverity};
+pub use patch::{PatchBuilder, PatchOptions};
+pub use relocate::{relocate, RelocateIssue, RelocateIssueKind, RelocatedCode};
+pub use rsp::{DmemAccess, RspSection};
+pub use search::{search, SearchOptions};
+pub use section::CodeSection;
+pub use sigdb::{identify_functions, IdentifiedFunction, IdentifyOptions, SignatureDatabase};
+pub use signature::{Parameter, Signature, ValueKind};
+pub use stack::{SavedRegister, StackFrame, StackRange};
+pub use statistics::{statistics, AccessTypeCount, InstrIdCount, InstrIdTypeCount, SectionStatistics};
+pub use structs::{BaseKind, FieldAccess, StructAccesses};
+pub use traversal::{AddressRange, TraversalResult};
+pub use xref::{Xref, XrefIndex, XrefKind};
 
 #[napi]
 pub fn get_version() -> String {
@@ -0,0 +1,223 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use napi_derive::napi;
+use rabbitizer::InstrCategory;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::enums::Abi;
+use crate::instruction::parse_category;
+use crate::registers;
+use crate::section::CodeSection;
+
+/// How bad a lint finding is
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LintSeverity {
+    /// The behaviour is undefined on every MIPS CPU
+    Error,
+    /// The result depends on pipeline timing of the target CPU
+    Warning,
+}
+
+/// A hazard found by `lint`
+#[napi(object)]
+pub struct LintIssue {
+    /// Address of the instruction causing the hazard
+    pub vram: u32,
+    pub severity: LintSeverity,
+    /// Short identifier of the rule: "branch-in-delay-slot", "missing-delay-slot",
+    /// "load-delay", "hilo" or "cop0"
+    pub rule: String,
+    pub message: String,
+}
+
+fn issue(
+    instr: &RustInstruction,
+    severity: LintSeverity,
+    rule: &str,
+    message: String,
+) -> LintIssue {
+    LintIssue {
+        vram: instr.vram,
+        severity,
+        rule: rule.to_string(),
+        message,
+    }
+}
+
+/// Checks for `mfc0`/`mfc1`/`cfc1`/`mfc2`/`cfc2`, which have a delay slot
+/// like loads on the R3000
+fn is_coprocessor_move_from(instr: &RustInstruction) -> bool {
+    matches!(instr.raw() >> 26, 0x10..=0x12) && matches!((instr.raw() >> 21) & 0x1F, 0 | 2)
+}
+
+fn is_mtc0(instr: &RustInstruction) -> bool {
+    instr.raw() >> 26 == 0x10 && matches!((instr.raw() >> 21) & 0x1F, 4 | 5)
+}
+
+/// Checks for the COP0 instructions that read registers written by `mtc0`
+fn reads_cop0_state(instr: &RustInstruction) -> bool {
+    matches!(
+        instr.unique_id,
+        InstrId::cpu_tlbr
+            | InstrId::cpu_tlbwi
+            | InstrId::cpu_tlbwr
+            | InstrId::cpu_tlbp
+            | InstrId::cpu_eret
+            | InstrId::cpu_mfc0
+            | InstrId::cpu_dmfc0
+            | InstrId::cpu_cache
+    )
+}
+
+/// Checks if the instruction after `instr` may not be the next one executed
+fn ends_straight_line(instr: &RustInstruction) -> bool {
+    instr.is_unconditional_branch() || instr.is_jump()
+}
+
+/// Returns the indices of up to `count` instructions that run right after `i`
+fn following(instrs: &[RustInstruction], i: usize, count: usize) -> Vec<usize> {
+    // Whatever comes after the delay slot of a jump is not known to run next
+    if i > 0 && ends_straight_line(&instrs[i - 1]) {
+        return Vec::new();
+    }
+    let mut next = Vec::new();
+    let mut j = i;
+    while next.len() < count && j + 1 < instrs.len() {
+        j += 1;
+        next.push(j);
+        if ends_straight_line(&instrs[j - 1]) {
+            break;
+        }
+    }
+    next
+}
+
+/// Reports pipeline hazards in straight-line code
+///
+/// Every category is checked for branches and jumps in delay slots, and for a
+/// delay slot falling past the end of the section. The other rules depend on
+/// the target CPU:
+/// * "r3000gte" (R3000, MIPS I): a loaded or coprocessor-moved GPR read by
+///   the very next instruction, and `mfhi`/`mflo` followed within two
+///   instructions by an instruction overwriting the same register
+/// * "cpu" (VR4300): the `mfhi`/`mflo` hazard, and `mtc0` followed within two
+///   instructions by a TLB instruction, `eret`, `mfc0` or `cache`
+/// * "rsp", "r4000allegrex" and "r5900" interlock, only the delay slot rules
+///   apply
+///
+/// A hazard window stops at the delay slot of an unconditional branch or jump,
+/// as what follows it in memory is not what runs next.
+pub(crate) fn lint(instrs: &[RustInstruction], category: InstrCategory) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mips1 = category == InstrCategory::R3000GTE;
+    let hilo_hazard = matches!(category, InstrCategory::CPU | InstrCategory::R3000GTE);
+    let cop0_hazard = category == InstrCategory::CPU;
+
+    for (i, instr) in instrs.iter().enumerate() {
+        if instr.has_delay_slot() {
+            match instrs.get(i + 1) {
+                Some(slot) if slot.has_delay_slot() => issues.push(issue(
+                    slot,
+                    LintSeverity::Error,
+                    "branch-in-delay-slot",
+                    format!(
+                        "{} is in the delay slot of {} at 0x{:08X}",
+                        slot.opcode_name(),
+                        instr.opcode_name(),
+                        instr.vram
+                    ),
+                )),
+                Some(_) => {}
+                None => issues.push(issue(
+                    instr,
+                    LintSeverity::Error,
+                    "missing-delay-slot",
+                    format!(
+                        "the delay slot of {} is past the end of the section",
+                        instr.opcode_name()
+                    ),
+                )),
+            }
+        }
+
+        if mips1 && (instr.does_load() || is_coprocessor_move_from(instr)) {
+            let loaded = instr.destination_gpr().filter(|&reg| reg != 0);
+            if let (Some(reg), Some(&next)) = (loaded, following(instrs, i, 1).first()) {
+                if registers::contains(registers::uses(&instrs[next]), reg as usize) {
+                    issues.push(issue(
+                        &instrs[next],
+                        LintSeverity::Warning,
+                        "load-delay",
+                        format!(
+                            "{} is read in the load delay slot of {} at 0x{:08X}",
+                            registers::name(reg as usize, Abi::O32),
+                            instr.opcode_name(),
+                            instr.vram
+                        ),
+                    ));
+                }
+            }
+        }
+
+        if hilo_hazard && (instr.reads_hi() || instr.reads_lo()) {
+            for next in following(instrs, i, 2) {
+                let overwrites = &instrs[next];
+                if (instr.reads_hi() && overwrites.modifies_hi())
+                    || (instr.reads_lo() && overwrites.modifies_lo())
+                {
+                    issues.push(issue(
+                        overwrites,
+                        LintSeverity::Warning,
+                        "hilo",
+                        format!(
+                            "{} overwrites the register read by {} at 0x{:08X}",
+                            overwrites.opcode_name(),
+                            instr.opcode_name(),
+                            instr.vram
+                        ),
+                    ));
+                    break;
+                }
+            }
+        }
+
+        if cop0_hazard && is_mtc0(instr) {
+            for next in following(instrs, i, 2) {
+                let reader = &instrs[next];
+                if reads_cop0_state(reader) {
+                    issues.push(issue(
+                        reader,
+                        LintSeverity::Warning,
+                        "cop0",
+                        format!(
+                            "{} may not see the write of {} at 0x{:08X}",
+                            reader.opcode_name(),
+                            instr.opcode_name(),
+                            instr.vram
+                        ),
+                    ));
+                    break;
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Reports pipeline hazards and delay slot misuse in a code section
+///
+/// # Arguments
+/// * `section` - The code to check
+/// * `category` - The CPU the code runs on, decides which hazards apply: "cpu" (VR4300), "rsp", "r3000gte" (R3000), "r4000allegrex" or "r5900" (optional, defaults to the section's category)
+#[napi(js_name = "lint")]
+pub fn lint_section(section: &CodeSection, category: Option<String>) -> Vec<LintIssue> {
+    let category = match category {
+        Some(category) => parse_category(Some(&category)),
+        None => section.instr_category(),
+    };
+    lint(section.instrs(), category)
+}
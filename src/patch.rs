@@ -0,0 +1,458 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! IPS and BPS patches from instruction edits
+
+use std::collections::BTreeMap;
+
+use napi::bindgen_prelude::{Buffer, ClassInstance, Either};
+use napi_derive::napi;
+use rabbitizer::InstrCategory;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::assembler::{self, assemble_line, Context};
+use crate::enums::Abi;
+use crate::instruction::{parse_category, Instruction};
+use crate::section::is_little_endian;
+
+/// Largest offset an IPS record can start at
+const IPS_MAX_OFFSET: usize = 0xFF_FFFF;
+/// Largest size of an IPS record
+const IPS_MAX_SIZE: usize = 0xFFFF;
+/// An IPS record starting here would read as the end of the patch
+const IPS_EOF: usize = 0x45_4F46;
+
+/// First word of a big-endian (`.z64`) N64 ROM
+const N64_MAGIC: u32 = 0x8037_1240;
+/// The boot code the CIC chip is matched against
+const N64_BOOT_CODE: std::ops::Range<usize> = 0x40..0x1000;
+/// Where the checksummed area starts, and its length
+const N64_CHECKSUM_START: usize = 0x1000;
+const N64_CHECKSUM_LENGTH: usize = 0x10_0000;
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+fn read_be(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// The N64 CIC chips, told apart by the CRC32 of their boot code
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cic {
+    Cic6101,
+    Cic6102,
+    Cic6103,
+    Cic6105,
+    Cic6106,
+}
+
+impl Cic {
+    fn detect(rom: &[u8]) -> Option<Self> {
+        match crc32(&rom[N64_BOOT_CODE]) {
+            0x6170_A4A1 => Some(Cic::Cic6101),
+            0x90BB_6CB5 => Some(Cic::Cic6102),
+            0x0B05_0EE0 => Some(Cic::Cic6103),
+            0x98BC_2C86 => Some(Cic::Cic6105),
+            0xACC8_580A => Some(Cic::Cic6106),
+            _ => None,
+        }
+    }
+
+    fn seed(self) -> u32 {
+        match self {
+            Cic::Cic6101 | Cic::Cic6102 => 0xF8CA_4DDC,
+            Cic::Cic6103 => 0xA388_6759,
+            Cic::Cic6105 => 0xDF26_F436,
+            Cic::Cic6106 => 0x1FEA_617A,
+        }
+    }
+}
+
+/// Recomputes the two checksums in the header of a big-endian N64 ROM
+fn fix_n64_crc(rom: &mut [u8]) -> napi::Result<()> {
+    let invalid = |message: &str| napi::Error::new(napi::Status::InvalidArg, message.to_string());
+    if rom.len() < N64_CHECKSUM_START + N64_CHECKSUM_LENGTH || read_be(rom, 0) != N64_MAGIC {
+        return Err(invalid("Not a big-endian N64 ROM of at least 1 MiB"));
+    }
+    let cic = Cic::detect(rom).ok_or_else(|| invalid("Unknown CIC boot code"))?;
+
+    let seed = cic.seed();
+    let (mut t1, mut t2, mut t3, mut t4, mut t5, mut t6) = (seed, seed, seed, seed, seed, seed);
+    for i in (N64_CHECKSUM_START..N64_CHECKSUM_START + N64_CHECKSUM_LENGTH).step_by(4) {
+        let d = read_be(rom, i);
+        if t6.wrapping_add(d) < t6 {
+            t4 = t4.wrapping_add(1);
+        }
+        t6 = t6.wrapping_add(d);
+        t3 ^= d;
+        let r = d.rotate_left(d & 0x1F);
+        t5 = t5.wrapping_add(r);
+        if t2 > d {
+            t2 ^= r;
+        } else {
+            t2 ^= t6 ^ d;
+        }
+        t1 = if cic == Cic::Cic6105 {
+            t1.wrapping_add(read_be(rom, N64_BOOT_CODE.start + 0x710 + (i & 0xFF)) ^ d)
+        } else {
+            t1.wrapping_add(t5 ^ d)
+        };
+    }
+    let (crc1, crc2) = match cic {
+        Cic::Cic6103 => ((t6 ^ t4).wrapping_add(t3), (t5 ^ t2).wrapping_add(t1)),
+        Cic::Cic6106 => (
+            t6.wrapping_mul(t4).wrapping_add(t3),
+            t5.wrapping_mul(t2).wrapping_add(t1),
+        ),
+        _ => (t6 ^ t4 ^ t3, t5 ^ t2 ^ t1),
+    };
+    rom[0x10..0x14].copy_from_slice(&crc1.to_be_bytes());
+    rom[0x14..0x18].copy_from_slice(&crc2.to_be_bytes());
+    Ok(())
+}
+
+/// The `[start, end)` byte ranges where two buffers of the same size differ
+fn changed_ranges(before: &[u8], after: &[u8]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (i, (a, b)) in before.iter().zip(after).enumerate() {
+        if a == b {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some((_, end)) if *end == i => *end = i + 1,
+            _ => ranges.push((i, i + 1)),
+        }
+    }
+    ranges
+}
+
+/// Writes a number the way BPS does, 7 bits at a time
+fn bps_number(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let bits = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(0x80 | bits);
+            return;
+        }
+        out.push(bits);
+        value -= 1;
+    }
+}
+
+/// Options for `PatchBuilder`
+#[napi(object)]
+pub struct PatchOptions {
+    /// Address the byte at `romOffset` is loaded at (defaults to 0)
+    pub vram: Option<u32>,
+    /// ROM offset loaded at `vram` (defaults to 0)
+    pub rom_offset: Option<u32>,
+    /// "cpu", "rsp", "r3000gte", "r4000allegrex" or "r5900" (defaults to "cpu")
+    pub category: Option<String>,
+    /// Byte order of the words in the ROM, "big" or "little" (defaults to "big")
+    pub endianness: Option<String>,
+}
+
+/// Collects instruction edits to a ROM and writes them as patches
+#[napi]
+pub struct PatchBuilder {
+    rom: Vec<u8>,
+    vram: u32,
+    rom_offset: u32,
+    category: InstrCategory,
+    little_endian: bool,
+    /// New words by ROM offset
+    edits: BTreeMap<u32, u32>,
+}
+
+impl PatchBuilder {
+    fn vram_of(&self, offset: u32) -> u32 {
+        offset.wrapping_sub(self.rom_offset).wrapping_add(self.vram)
+    }
+
+    fn word_at(&self, offset: u32) -> u32 {
+        let offset = offset as usize;
+        let bytes = [
+            self.rom[offset],
+            self.rom[offset + 1],
+            self.rom[offset + 2],
+            self.rom[offset + 3],
+        ];
+        if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        }
+    }
+
+    fn assemble(&self, text: &str, vram: u32) -> napi::Result<Vec<u32>> {
+        let context = Context {
+            vram,
+            category: self.category,
+            abi: Abi::O32,
+            address_labels: true,
+        };
+        assemble_line(text, &context).map_err(|error| {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                format!(
+                    "Cannot assemble '{}': column {}: {}",
+                    text, error.column, error.message
+                ),
+            )
+        })
+    }
+
+    /// Checks the words at `offset` against the instructions they should be
+    fn check(&self, offset: u32, expected: Either<u32, String>) -> napi::Result<()> {
+        let (words, is_text) = match &expected {
+            Either::A(word) => (vec![*word], false),
+            Either::B(text) => (self.assemble(text, self.vram_of(offset))?, true),
+        };
+        // The first word that differs, and what is found there instead
+        let mismatch = words.iter().enumerate().find_map(|(i, &word)| {
+            let offset = offset + i as u32 * 4;
+            let vram = self.vram_of(offset);
+            if offset as usize + 4 > self.rom.len() {
+                return Some((vram, "the end of the ROM".to_string()));
+            }
+            let found = RustInstruction::new(self.word_at(offset), vram, self.category);
+            let found_text = assembler::disassemble(&found, None);
+            // Encodings rabbitizer prints the same way are the same edit
+            let assembled = RustInstruction::new(word, vram, self.category);
+            let matches = word == found.raw()
+                || (is_text && assembler::disassemble(&assembled, None) == found_text);
+            (!matches).then(|| (vram, format!("'{}'", found_text)))
+        });
+        let Some((vram, found)) = mismatch else {
+            return Ok(());
+        };
+        let expected = match expected {
+            Either::A(word) => format!("0x{:08X}", word),
+            Either::B(text) => format!("'{}'", text.trim()),
+        };
+        Err(napi::Error::new(
+            napi::Status::InvalidArg,
+            format!("Expected {} at 0x{:08X}, found {}", expected, vram, found),
+        ))
+    }
+
+    fn edit(
+        &mut self,
+        offset: u32,
+        replacement: Either<ClassInstance<Instruction>, String>,
+        expected: Option<Either<u32, String>>,
+    ) -> napi::Result<()> {
+        let words = match replacement {
+            Either::A(instruction) => vec![instruction.raw()],
+            Either::B(text) => self.assemble(&text, self.vram_of(offset))?,
+        };
+        let size = words.len() as u64 * 4;
+        if !offset.is_multiple_of(4) || offset as u64 + size > self.rom.len() as u64 {
+            return Err(napi::Error::new(
+                napi::Status::InvalidArg,
+                format!(
+                    "Offset 0x{:X} is not a word inside the ROM of 0x{:X} bytes",
+                    offset,
+                    self.rom.len()
+                ),
+            ));
+        }
+        if let Some(expected) = expected {
+            self.check(offset, expected)?;
+        }
+        for (i, word) in words.into_iter().enumerate() {
+            self.edits.insert(offset + i as u32 * 4, word);
+        }
+        Ok(())
+    }
+
+    fn patched(&self, fix_crc: Option<bool>) -> napi::Result<Vec<u8>> {
+        let mut rom = self.rom.clone();
+        for (&offset, &word) in &self.edits {
+            let bytes = if self.little_endian {
+                word.to_le_bytes()
+            } else {
+                word.to_be_bytes()
+            };
+            let offset = offset as usize;
+            rom[offset..offset + 4].copy_from_slice(&bytes);
+        }
+        if fix_crc.unwrap_or(false) {
+            fix_n64_crc(&mut rom)?;
+        }
+        Ok(rom)
+    }
+}
+
+#[napi]
+impl PatchBuilder {
+    /// Creates a PatchBuilder for a ROM
+    ///
+    /// # Arguments
+    /// * `rom` - The unmodified ROM
+    /// * `options` - How vram maps to ROM offsets, the category and the byte order (optional)
+    #[napi(constructor)]
+    pub fn new(rom: Buffer, options: Option<PatchOptions>) -> napi::Result<Self> {
+        let options = options.unwrap_or(PatchOptions {
+            vram: None,
+            rom_offset: None,
+            category: None,
+            endianness: None,
+        });
+        Ok(PatchBuilder {
+            rom: rom.to_vec(),
+            vram: options.vram.unwrap_or(0),
+            rom_offset: options.rom_offset.unwrap_or(0),
+            category: parse_category(options.category.as_deref()),
+            little_endian: is_little_endian(options.endianness.as_deref())?,
+            edits: BTreeMap::new(),
+        })
+    }
+
+    /// Replaces the instruction at a ROM offset
+    ///
+    /// Assembly text may give several words, like `li`. A later edit of the
+    /// same word replaces the earlier one.
+    ///
+    /// # Arguments
+    /// * `offset` - ROM offset of the word
+    /// * `replacement` - An `Instruction` or a line of assembly
+    /// * `expected` - The original word or its assembly, checked before editing (optional).
+    ///   Assembly giving several words, like `li`, checks as many words
+    #[napi]
+    pub fn edit_at_offset(
+        &mut self,
+        offset: u32,
+        replacement: Either<ClassInstance<Instruction>, String>,
+        expected: Option<Either<u32, String>>,
+    ) -> napi::Result<()> {
+        self.edit(offset, replacement, expected)
+    }
+
+    /// Replaces the instruction at a vram address, see `editAtOffset`
+    #[napi]
+    pub fn edit_at_vram(
+        &mut self,
+        vram: u32,
+        replacement: Either<ClassInstance<Instruction>, String>,
+        expected: Option<Either<u32, String>>,
+    ) -> napi::Result<()> {
+        let offset = vram.wrapping_sub(self.vram).wrapping_add(self.rom_offset);
+        self.edit(offset, replacement, expected)
+    }
+
+    /// Gets the number of edited words
+    #[napi(getter)]
+    pub fn edit_count(&self) -> u32 {
+        self.edits.len() as u32
+    }
+
+    /// Gets the ROM with every edit applied
+    ///
+    /// # Arguments
+    /// * `fix_crc` - Recompute the checksums of an N64 ROM header (optional, defaults to false)
+    #[napi]
+    pub fn apply(&self, fix_crc: Option<bool>) -> napi::Result<Buffer> {
+        Ok(self.patched(fix_crc)?.into())
+    }
+
+    /// Writes the edits as an IPS patch
+    ///
+    /// IPS only reaches the first 16 MiB of a ROM.
+    ///
+    /// # Arguments
+    /// * `fix_crc` - Also patch the recomputed checksums of an N64 ROM header (optional, defaults to false)
+    #[napi]
+    pub fn ips(&self, fix_crc: Option<bool>) -> napi::Result<Buffer> {
+        let patched = self.patched(fix_crc)?;
+        let mut out = b"PATCH".to_vec();
+        for (start, end) in changed_ranges(&self.rom, &patched) {
+            // A record at "EOF" would end the patch, so it starts a byte early
+            let mut start = if start == IPS_EOF { start - 1 } else { start };
+            while start < end {
+                if start > IPS_MAX_OFFSET {
+                    return Err(napi::Error::new(
+                        napi::Status::InvalidArg,
+                        format!("IPS cannot patch offset 0x{:X}, past 16 MiB", start),
+                    ));
+                }
+                let size = (end - start).min(IPS_MAX_SIZE);
+                out.extend_from_slice(&(start as u32).to_be_bytes()[1..]);
+                out.extend_from_slice(&(size as u16).to_be_bytes());
+                out.extend_from_slice(&patched[start..start + size]);
+                start += size;
+            }
+        }
+        out.extend_from_slice(b"EOF");
+        Ok(out.into())
+    }
+
+    /// Writes the edits as a BPS patch
+    ///
+    /// # Arguments
+    /// * `fix_crc` - Also patch the recomputed checksums of an N64 ROM header (optional, defaults to false)
+    #[napi]
+    pub fn bps(&self, fix_crc: Option<bool>) -> napi::Result<Buffer> {
+        const SOURCE_READ: u64 = 0;
+        const TARGET_READ: u64 = 1;
+
+        let patched = self.patched(fix_crc)?;
+        let mut out = b"BPS1".to_vec();
+        bps_number(&mut out, self.rom.len() as u64);
+        bps_number(&mut out, patched.len() as u64);
+        // No metadata
+        bps_number(&mut out, 0);
+
+        let mut position = 0;
+        for (start, end) in changed_ranges(&self.rom, &patched) {
+            if start > position {
+                bps_number(&mut out, ((start - position - 1) as u64) << 2 | SOURCE_READ);
+            }
+            bps_number(&mut out, ((end - start - 1) as u64) << 2 | TARGET_READ);
+            out.extend_from_slice(&patched[start..end]);
+            position = end;
+        }
+        if patched.len() > position {
+            bps_number(
+                &mut out,
+                ((patched.len() - position - 1) as u64) << 2 | SOURCE_READ,
+            );
+        }
+
+        out.extend_from_slice(&crc32(&self.rom).to_le_bytes());
+        out.extend_from_slice(&crc32(&patched).to_le_bytes());
+        let patch_crc = crc32(&out);
+        out.extend_from_slice(&patch_crc.to_le_bytes());
+        Ok(out.into())
+    }
+}
@@ -0,0 +1,724 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! Lifting of a function to C-like pseudocode
+//!
+//! The output is meant as a starting point for manual matching, not to be
+//! compiled. Each instruction becomes a statement on register variables;
+//! forward branches over a contiguous run of blocks become `if`/`else` and
+//! backward branches become `do`/`while` loops. Anything else falls back to
+//! labels and `goto`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rabbitizer::AccessType;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::assembler;
+use crate::cfg::Cfg;
+use crate::dataflow::Dataflow;
+use crate::enums::Abi;
+use crate::fields;
+use crate::floats::{self, FloatConstant};
+use crate::registers;
+use crate::signature::{self, Signature, ValueKind};
+use crate::traversal;
+
+/// How control leaves a block
+#[derive(Clone, Copy)]
+enum Terminator {
+    /// Falls through to the next block
+    None,
+    /// A branch or jump to an address inside the function (`target` is a block)
+    /// or outside of it
+    Branch {
+        index: usize,
+        target: Option<usize>,
+        conditional: bool,
+        likely: bool,
+    },
+    /// `jr $ra`
+    Return { index: usize },
+    /// Jumps through a register or to another function
+    Indirect { index: usize },
+}
+
+enum Line {
+    Label(usize),
+    Code(usize, String),
+}
+
+/// Name of a GPR as a variable, `$zero` reads as `0`
+fn gpr(reg: u32, abi: Abi) -> String {
+    if reg == 0 {
+        "0".to_string()
+    } else {
+        registers::name(reg as usize, abi)[1..].to_string()
+    }
+}
+
+fn fpr(reg: u32) -> String {
+    format!("f{}", reg)
+}
+
+fn hex(value: i64) -> String {
+    if (-10..10).contains(&value) {
+        value.to_string()
+    } else if value < 0 {
+        format!("-0x{:X}", -value)
+    } else {
+        format!("0x{:X}", value)
+    }
+}
+
+/// Formats `base + offset`, folding away a zero offset
+fn plus(base: &str, offset: i64) -> String {
+    match offset {
+        0 => base.to_string(),
+        offset if offset < 0 => format!("{} - {}", base, hex(-offset)),
+        offset => format!("{} + {}", base, hex(offset)),
+    }
+}
+
+fn c_type(kind: ValueKind) -> &'static str {
+    match kind {
+        ValueKind::Int => "s32",
+        ValueKind::Float => "f32",
+        ValueKind::Double => "f64",
+    }
+}
+
+/// C type of a memory access
+fn access_type_name(instr: &RustInstruction) -> Option<&'static str> {
+    let unsigned = instr.does_unsigned_memory_access();
+    Some(match instr.access_type() {
+        AccessType::BYTE if unsigned => "u8",
+        AccessType::BYTE => "s8",
+        AccessType::SHORT if unsigned => "u16",
+        AccessType::SHORT => "s16",
+        AccessType::WORD if unsigned => "u32",
+        AccessType::WORD => "s32",
+        AccessType::DOUBLEWORD => "s64",
+        AccessType::FLOAT => "f32",
+        AccessType::DOUBLEFLOAT => "f64",
+        _ => return None,
+    })
+}
+
+/// C type named by a float format suffix
+fn format_type(format: &str) -> &'static str {
+    match format {
+        "s" => "f32",
+        "d" => "f64",
+        "w" => "s32",
+        _ => "s64",
+    }
+}
+
+/// Returns the condition of a conditional branch and its negation
+fn condition(instr: &RustInstruction, abi: Abi) -> Option<(String, String)> {
+    let name = instr.opcode_name();
+    let name = if instr.is_branch_likely() {
+        name.strip_suffix('l').unwrap_or(name)
+    } else {
+        name
+    };
+    let rs = gpr(fields::rs(instr), abi);
+    let rt = gpr(fields::rt(instr), abi);
+
+    let compare = |op: &str, negated: &str, rhs: &str| {
+        (
+            format!("{} {} {}", rs, op, rhs),
+            format!("{} {} {}", rs, negated, rhs),
+        )
+    };
+    Some(match name {
+        "beq" => compare("==", "!=", &rt),
+        "bne" => compare("!=", "==", &rt),
+        "beqz" => compare("==", "!=", "0"),
+        "bnez" => compare("!=", "==", "0"),
+        "blez" => compare("<=", ">", "0"),
+        "bgtz" => compare(">", "<=", "0"),
+        "bltz" => compare("<", ">=", "0"),
+        "bgez" => compare(">=", "<", "0"),
+        "bc1t" => ("cop1_cond".to_string(), "!cop1_cond".to_string()),
+        "bc1f" => ("!cop1_cond".to_string(), "cop1_cond".to_string()),
+        _ => return None,
+    })
+}
+
+/// Lifts an instruction that does not transfer control
+fn statement(instr: &RustInstruction, floats: &[FloatConstant], abi: Abi) -> Option<String> {
+    let name = instr.opcode_name();
+    let (rs, rt, rd) = (
+        gpr(fields::rs(instr), abi),
+        gpr(fields::rt(instr), abi),
+        gpr(fields::rd(instr), abi),
+    );
+    let (fs, ft, fd) = (
+        fpr(fields::fs(instr)),
+        fpr(fields::ft(instr)),
+        fpr(fields::fd(instr)),
+    );
+    let sa = fields::sa(instr);
+    let simm = fields::immediate(instr) as u16 as i16 as i64;
+    let uimm = fields::immediate(instr);
+
+    if instr.does_load() || instr.does_store() {
+        if let Some(ty) = access_type_name(instr) {
+            let address = plus(&rs, instr.processed_immediate() as i64);
+            let address = if address == rs {
+                address
+            } else {
+                format!("({})", address)
+            };
+            let value = if instr.is_float() || ty.starts_with('f') {
+                ft.clone()
+            } else {
+                rt.clone()
+            };
+            return Some(if instr.does_load() {
+                format!("{} = *({}*){};", value, ty, address)
+            } else {
+                format!("*({}*){} = {};", ty, address, value)
+            });
+        }
+    }
+
+    let binary = |dest: &str, op: &str| format!("{} = {} {} {};", dest, rs, op, rt);
+    let shift = |op: &str, cast: &str, amount: String| {
+        format!("{} = {}{} {} {};", rd, cast, rt, op, amount)
+    };
+    let float_binary = |op: &str| format!("{} = {} {} {};", fd, fs, op, ft);
+
+    let statement = match name {
+        "nop" => return None,
+        "lui" => format!("{} = 0x{:X};", rt, uimm << 16),
+        "addiu" | "addi" | "daddiu" | "daddi" if fields::rs(instr) == 0 => {
+            format!("{} = {};", rt, hex(simm))
+        }
+        "addiu" | "addi" | "daddiu" | "daddi" => format!("{} = {};", rt, plus(&rs, simm)),
+        "ori" | "xori" if fields::rs(instr) == 0 => format!("{} = 0x{:X};", rt, uimm),
+        "ori" => format!("{} = {} | 0x{:X};", rt, rs, uimm),
+        "andi" => format!("{} = {} & 0x{:X};", rt, rs, uimm),
+        "xori" => format!("{} = {} ^ 0x{:X};", rt, rs, uimm),
+        "slti" => format!("{} = {} < {};", rt, rs, hex(simm)),
+        "sltiu" => format!("{} = (u32){} < {};", rt, rs, hex(simm)),
+
+        "move" => format!("{} = {};", rd, rs),
+        "addu" | "add" | "daddu" | "dadd" | "or" if fields::rt(instr) == 0 => {
+            format!("{} = {};", rd, rs)
+        }
+        "addu" | "add" | "daddu" | "dadd" => binary(&rd, "+"),
+        "subu" | "sub" | "dsubu" | "dsub" => binary(&rd, "-"),
+        "negu" | "neg" | "dnegu" => format!("{} = -{};", rd, rt),
+        "and" => binary(&rd, "&"),
+        "or" => binary(&rd, "|"),
+        "xor" => binary(&rd, "^"),
+        "nor" => format!("{} = ~({} | {});", rd, rs, rt),
+        "not" => format!("{} = ~{};", rd, rs),
+        "slt" => binary(&rd, "<"),
+        "sltu" => format!("{} = (u32){} < (u32){};", rd, rs, rt),
+
+        "sll" | "dsll" => shift("<<", "", sa.to_string()),
+        "srl" => shift(">>", "(u32)", sa.to_string()),
+        "dsrl" => shift(">>", "(u64)", sa.to_string()),
+        "sra" => shift(">>", "(s32)", sa.to_string()),
+        "dsra" => shift(">>", "(s64)", sa.to_string()),
+        "dsll32" => shift("<<", "", (sa + 32).to_string()),
+        "dsrl32" => shift(">>", "(u64)", (sa + 32).to_string()),
+        "dsra32" => shift(">>", "(s64)", (sa + 32).to_string()),
+        "sllv" | "dsllv" => shift("<<", "", rs.clone()),
+        "srlv" => shift(">>", "(u32)", rs.clone()),
+        "dsrlv" => shift(">>", "(u64)", rs.clone()),
+        "srav" => shift(">>", "(s32)", rs.clone()),
+        "dsrav" => shift(">>", "(s64)", rs.clone()),
+
+        "mult" | "dmult" => format!("lo = {} * {}; hi = MULT_HI({}, {});", rs, rt, rs, rt),
+        "multu" | "dmultu" => format!(
+            "lo = (u32){} * (u32){}; hi = MULTU_HI({}, {});",
+            rs, rt, rs, rt
+        ),
+        "div" | "ddiv" => format!("lo = {} / {}; hi = {} % {};", rs, rt, rs, rt),
+        "divu" | "ddivu" => format!(
+            "lo = (u32){} / (u32){}; hi = (u32){} % (u32){};",
+            rs, rt, rs, rt
+        ),
+        "mfhi" => format!("{} = hi;", rd),
+        "mflo" => format!("{} = lo;", rd),
+        "mthi" => format!("hi = {};", rs),
+        "mtlo" => format!("lo = {};", rs),
+
+        "mtc1" | "dmtc1" => {
+            match floats.iter().find(|constant| constant.vram == instr.vram) {
+                // Halves of a double are reported on the second `mtc1` only
+                Some(constant) => format!(
+                    "{} = {};",
+                    &constant.register[1..],
+                    floats::format_literal(constant)
+                ),
+                None if name == "mtc1" => format!("{} = *(f32*)&{};", fs, rt),
+                None => format!("{} = *(f64*)&{};", fs, rt),
+            }
+        }
+        "mfc1" => format!("{} = *(s32*)&{};", rt, fs),
+        "dmfc1" => format!("{} = *(s64*)&{};", rt, fs),
+
+        _ => {
+            let parts: Vec<&str> = name.split('.').collect();
+            match parts.as_slice() {
+                ["add", _] => float_binary("+"),
+                ["sub", _] => float_binary("-"),
+                ["mul", _] => float_binary("*"),
+                ["div", _] => float_binary("/"),
+                ["mov", _] => format!("{} = {};", fd, fs),
+                ["neg", _] => format!("{} = -{};", fd, fs),
+                ["abs", "s"] => format!("{} = fabsf({});", fd, fs),
+                ["abs", _] => format!("{} = fabs({});", fd, fs),
+                ["sqrt", "s"] => format!("{} = sqrtf({});", fd, fs),
+                ["sqrt", _] => format!("{} = sqrt({});", fd, fs),
+                ["cvt", to, _] | ["trunc", to, _] => {
+                    format!("{} = ({}){};", fd, format_type(to), fs)
+                }
+                [op @ ("round" | "ceil" | "floor"), to, _] => {
+                    format!("{} = ({}){}({});", fd, format_type(to), op, fs)
+                }
+                ["c", cond, _] => {
+                    let op = match *cond {
+                        "eq" | "ueq" | "seq" => "==",
+                        "lt" | "olt" | "ult" | "nge" => "<",
+                        "le" | "ole" | "ule" | "ngt" => "<=",
+                        _ => return Some(format!("cop1_cond = {}({}, {});", cond, fs, ft)),
+                    };
+                    format!("cop1_cond = {} {} {};", fs, op, ft)
+                }
+                _ => format!("/* {} */", assembler::disassemble(instr, None)),
+            }
+        }
+    };
+    Some(statement)
+}
+
+struct Lifter<'a> {
+    instrs: &'a [RustInstruction],
+    cfg: Cfg,
+    dataflow: Dataflow,
+    floats: Vec<FloatConstant>,
+    abi: Abi,
+    signature: Signature,
+    lines: Vec<Line>,
+    gotos: BTreeSet<usize>,
+    /// Blocks emitted as part of another one (delay slots of likely branches)
+    consumed: Vec<bool>,
+    /// Blocks whose terminator is turned into structured control flow
+    suppressed: BTreeSet<usize>,
+    /// Conditions of suppressed conditional terminators, for `do`/`while`
+    conditions: BTreeMap<usize, (String, String)>,
+}
+
+impl<'a> Lifter<'a> {
+    fn new(instrs: &'a [RustInstruction], abi: Abi) -> Self {
+        let cfg = Cfg::build(instrs);
+        let blocks = cfg.blocks.len();
+        Lifter {
+            instrs,
+            cfg,
+            dataflow: Dataflow::build(instrs, abi),
+            floats: floats::find(instrs, abi, None),
+            abi,
+            signature: signature::infer(instrs, abi),
+            lines: Vec::new(),
+            gotos: BTreeSet::new(),
+            consumed: vec![false; blocks],
+            suppressed: BTreeSet::new(),
+            conditions: BTreeMap::new(),
+        }
+    }
+
+    fn push(&mut self, depth: usize, code: String) {
+        self.lines.push(Line::Code(depth, code));
+    }
+
+    fn terminator(&self, b: usize) -> Terminator {
+        let block = &self.cfg.blocks[b];
+        let branch = (block.start..block.end).rev().take(2).find(|&i| {
+            let instr = &self.instrs[i];
+            !instr.is_function_call()
+                && (traversal::branch_target(instr).is_some() || instr.is_jump())
+                && (i + 1 == block.end || (i + 2 == block.end && instr.has_delay_slot()))
+        });
+        let Some(index) = branch else {
+            return Terminator::None;
+        };
+        let instr = &self.instrs[index];
+
+        if instr.is_return() {
+            return Terminator::Return { index };
+        }
+        let Some(target) = traversal::branch_target(instr) else {
+            return Terminator::Indirect { index };
+        };
+        let target = self
+            .instrs
+            .iter()
+            .position(|other| other.vram == target)
+            .and_then(|i| self.cfg.block_starting_at(i));
+        let conditional = !instr.is_unconditional_branch() && !instr.is_jump();
+        if target.is_none() && !conditional {
+            return Terminator::Indirect { index };
+        }
+        Terminator::Branch {
+            index,
+            target,
+            conditional,
+            likely: instr.is_branch_likely(),
+        }
+    }
+
+    fn label(&self, b: usize) -> String {
+        format!("label_{:08X}", self.instrs[self.cfg.blocks[b].start].vram)
+    }
+
+    fn goto(&mut self, target: Option<usize>, index: usize) -> String {
+        match target {
+            Some(b) => {
+                self.gotos.insert(b);
+                format!("goto {};", self.label(b))
+            }
+            None => format!(
+                "goto 0x{:08X};",
+                traversal::branch_target(&self.instrs[index]).unwrap_or(0)
+            ),
+        }
+    }
+
+    /// Lists the arguments set up for the call at `index`
+    ///
+    /// An argument register counts if the function itself wrote the value the
+    /// callee sees, or passes on its own parameter unchanged.
+    fn call_arguments(&self, index: usize) -> Vec<String> {
+        let own_parameter = |reg: usize| {
+            let name = registers::name(reg, self.abi);
+            self.signature
+                .parameters
+                .iter()
+                .any(|parameter| parameter.register == name)
+        };
+        let passed = |reg: usize| {
+            self.dataflow
+                .reaching_after(index, reg)
+                .iter()
+                .any(|def| match def {
+                    Some(def) => !self.instrs[*def].is_function_call(),
+                    None => own_parameter(reg),
+                })
+        };
+
+        let positions = registers::argument_gprs(self.abi).len();
+        let mut arguments: Vec<Option<usize>> = Vec::new();
+        for position in 0..positions {
+            let (gpr, fpr) = signature::parameter_registers(self.abi, position);
+            arguments.push(match fpr {
+                Some(fpr) if passed(fpr) => Some(fpr),
+                _ if passed(gpr) => Some(gpr),
+                _ => None,
+            });
+        }
+
+        let count = arguments
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(0, |last| last + 1);
+        arguments
+            .into_iter()
+            .take(count)
+            .enumerate()
+            .map(|(position, reg)| {
+                let reg = reg.unwrap_or(signature::parameter_registers(self.abi, position).0);
+                registers::name(reg, self.abi)[1..].to_string()
+            })
+            .collect()
+    }
+
+    fn call(&self, index: usize) -> String {
+        let instr = &self.instrs[index];
+        let callee = match traversal::branch_target(instr) {
+            Some(target) => format!("func_{:08X}", target),
+            None => format!("(*{})", gpr(fields::rs(instr), self.abi)),
+        };
+        let call = format!("{}({})", callee, self.call_arguments(index).join(", "));
+
+        let is_used = |reg: usize| {
+            self.dataflow
+                .uses_of(index, reg)
+                .is_some_and(|uses| !uses.is_empty())
+        };
+        if is_used(2) {
+            format!("v0 = {};", call)
+        } else if is_used(registers::fpr(0)) {
+            format!("f0 = {};", call)
+        } else {
+            format!("{};", call)
+        }
+    }
+
+    fn return_statement(&self) -> String {
+        match self.signature.return_kind {
+            Some(ValueKind::Int) => "return v0;".to_string(),
+            Some(_) => "return f0;".to_string(),
+            None => "return;".to_string(),
+        }
+    }
+
+    fn emit_instruction(&mut self, depth: usize, index: usize) {
+        let instr = &self.instrs[index];
+        let code = if instr.is_function_call() {
+            Some(self.call(index))
+        } else {
+            statement(instr, &self.floats, self.abi)
+        };
+        if let Some(code) = code {
+            self.push(depth, code);
+        }
+    }
+
+    /// Emits a block
+    ///
+    /// Returns the condition of its terminator and its negation if the
+    /// terminator is suppressed.
+    fn emit_block(&mut self, b: usize, depth: usize) -> Option<(String, String)> {
+        let (start, end) = (self.cfg.blocks[b].start, self.cfg.blocks[b].end);
+        let terminator = self.terminator(b);
+        let branch = match terminator {
+            Terminator::None => None,
+            Terminator::Branch { index, .. }
+            | Terminator::Return { index }
+            | Terminator::Indirect { index } => Some(index),
+        };
+        let slot = branch.filter(|&i| i + 2 == end).map(|i| i + 1);
+
+        let mut i = start;
+        while i < end {
+            if Some(i) == branch || Some(i) == slot {
+                i += 1;
+                continue;
+            }
+            let instr = &self.instrs[i];
+            if instr.is_function_call() && instr.has_delay_slot() && i + 1 < end {
+                // Arguments are often set up in the delay slot
+                self.emit_instruction(depth, i + 1);
+                self.emit_instruction(depth, i);
+                i += 2;
+                continue;
+            }
+            self.emit_instruction(depth, i);
+            i += 1;
+        }
+
+        let index = branch?;
+        let instr = &self.instrs[index];
+        let mut condition = match terminator {
+            Terminator::Branch {
+                conditional: true, ..
+            } => Some(condition(instr, self.abi).unwrap_or_else(|| {
+                let text = format!("/* {} */", assembler::disassemble(instr, None));
+                (text.clone(), format!("!{}", text))
+            })),
+            _ => None,
+        };
+
+        if let Some(slot) = slot {
+            // The condition is evaluated before the delay slot runs
+            let clobbered = registers::defs(&self.instrs[slot]) & registers::uses(instr) != 0;
+            if let (true, Some((positive, _))) = (clobbered, &condition) {
+                self.push(depth, format!("cond = {};", positive));
+                condition = Some(("cond".to_string(), "!cond".to_string()));
+            }
+            self.emit_instruction(depth, slot);
+        }
+
+        if self.suppressed.contains(&b) {
+            return condition;
+        }
+
+        match terminator {
+            Terminator::None => {}
+            Terminator::Branch {
+                target,
+                likely: true,
+                ..
+            } => {
+                let (positive, _) = condition.unwrap_or(("1".to_string(), "0".to_string()));
+                self.push(depth, format!("if ({}) {{", positive));
+                if index + 1 < self.instrs.len() {
+                    self.emit_instruction(depth + 1, index + 1);
+                    if let Some(slot_block) = self.cfg.block_of(index + 1) {
+                        self.consumed[slot_block] = true;
+                    }
+                }
+                let goto = self.goto(target, index);
+                self.push(depth + 1, goto);
+                self.push(depth, "}".to_string());
+            }
+            Terminator::Branch { target, .. } => {
+                let goto = self.goto(target, index);
+                match condition {
+                    Some((positive, _)) => self.push(depth, format!("if ({}) {}", positive, goto)),
+                    None if target == Some(b + 1) => {}
+                    None => self.push(depth, goto),
+                }
+            }
+            Terminator::Return { .. } => {
+                let code = self.return_statement();
+                self.push(depth, code);
+            }
+            Terminator::Indirect { .. } => {
+                let code = match traversal::branch_target(instr) {
+                    Some(target) => format!("func_{:08X}(); /* tail call */", target),
+                    None => format!("goto *{};", gpr(fields::rs(instr), self.abi)),
+                };
+                self.push(depth, code);
+            }
+        }
+        None
+    }
+
+    fn branch_target_of(&self, b: usize) -> Option<(usize, bool)> {
+        match self.terminator(b) {
+            Terminator::Branch {
+                target: Some(target),
+                conditional,
+                likely: false,
+                ..
+            } => Some((target, conditional)),
+            _ => None,
+        }
+    }
+
+    /// Emits blocks `[lo, hi)`
+    ///
+    /// `falls_to_hi` tells whether running off the end of the region reaches
+    /// block `hi`, which makes branches to `hi` structurable.
+    fn structure(
+        &mut self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        falls_to_hi: bool,
+        header: Option<usize>,
+    ) {
+        let reaches = |target: usize| target < hi || (target == hi && falls_to_hi);
+
+        let mut b = lo;
+        while b < hi {
+            if self.consumed[b] {
+                b += 1;
+                continue;
+            }
+            self.lines.push(Line::Label(b));
+
+            // Loops: the last block in the region branching back here
+            let tail = (b..hi).rev().find(|&e| {
+                header != Some(b)
+                    && self
+                        .branch_target_of(e)
+                        .is_some_and(|(target, _)| target == b)
+            });
+            if let Some(tail) = tail {
+                let (_, conditional) = self.branch_target_of(tail).unwrap_or((b, false));
+                self.suppressed.insert(tail);
+                self.push(
+                    depth,
+                    if conditional { "do {" } else { "while (1) {" }.to_string(),
+                );
+                self.structure(b, tail + 1, depth + 1, false, Some(b));
+                let code = match self.conditions.remove(&tail) {
+                    Some((condition, _)) if conditional => format!("}} while ({});", condition),
+                    _ => "}".to_string(),
+                };
+                self.push(depth, code);
+                b = tail + 1;
+                continue;
+            }
+
+            // Forward conditional branches over the next blocks
+            if let Some((target, true)) = self.branch_target_of(b) {
+                if b + 1 < target && reaches(target) {
+                    self.suppressed.insert(b);
+                    // The blocks in between run when the branch is not taken
+                    let (_, negated) = self.emit_block(b, depth).unwrap_or_default();
+
+                    let else_end = match self.branch_target_of(target - 1) {
+                        Some((join, false)) if target < join && reaches(join) => Some(join),
+                        _ => None,
+                    };
+                    self.push(depth, format!("if ({}) {{", negated));
+                    match else_end {
+                        Some(join) => {
+                            self.suppressed.insert(target - 1);
+                            self.structure(b + 1, target, depth + 1, false, None);
+                            self.push(depth, "} else {".to_string());
+                            self.structure(target, join, depth + 1, true, None);
+                            self.push(depth, "}".to_string());
+                            b = join;
+                        }
+                        None => {
+                            self.structure(b + 1, target, depth + 1, true, None);
+                            self.push(depth, "}".to_string());
+                            b = target;
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            if let Some(condition) = self.emit_block(b, depth) {
+                self.conditions.insert(b, condition);
+            }
+            b += 1;
+        }
+    }
+
+    fn render(mut self) -> String {
+        let blocks = self.cfg.blocks.len();
+        self.structure(0, blocks, 1, true, None);
+
+        let parameters: Vec<String> = self
+            .signature
+            .parameters
+            .iter()
+            .map(|parameter| format!("{} {}", c_type(parameter.kind), &parameter.register[1..]))
+            .collect();
+        let parameters = if parameters.is_empty() {
+            "void".to_string()
+        } else {
+            parameters.join(", ")
+        };
+        let return_type = self.signature.return_kind.map_or("void", c_type);
+
+        let mut out = format!(
+            "{} func_{:08X}({}) {{\n",
+            return_type, self.signature.vram, parameters
+        );
+        for line in &self.lines {
+            match line {
+                Line::Label(b) if self.gotos.contains(b) => {
+                    out.push_str(&format!("{}:\n", self.label(*b)));
+                }
+                Line::Label(_) => {}
+                Line::Code(depth, code) => {
+                    out.push_str(&"    ".repeat(*depth));
+                    out.push_str(code);
+                    out.push('\n');
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Lifts a function to C-like pseudocode
+pub(crate) fn lift(instrs: &[RustInstruction], abi: Abi) -> String {
+    Lifter::new(instrs, abi).render()
+}
@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! Register numbering shared by the dataflow analyses
+//!
+//! GPRs are 0-31, FPRs 32-63, followed by HI and LO. A set of registers fits
+//! in a `u128`.
+
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::enums::Abi;
+use crate::fields;
+use crate::utils::Utils;
+
+pub(crate) type RegSet = u128;
+
+pub(crate) const RA: usize = 31;
+pub(crate) const HI: usize = 64;
+pub(crate) const LO: usize = 65;
+pub(crate) const COUNT: usize = 66;
+
+/// GPR names of the N32 and N64 ABIs, where `$8`-`$11` pass arguments
+pub(crate) const N32_GPRS: [&str; 32] = [
+    "zero", "at", "v0", "v1", "a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7", "t0", "t1", "t2",
+    "t3", "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "t8", "t9", "k0", "k1", "gp", "sp", "fp",
+    "ra",
+];
+
+pub(crate) const fn fpr(index: u32) -> usize {
+    32 + index as usize
+}
+
+pub(crate) const fn bit(reg: usize) -> RegSet {
+    1 << reg
+}
+
+pub(crate) fn contains(set: RegSet, reg: usize) -> bool {
+    set & bit(reg) != 0
+}
+
+/// Iterates over the registers in a set, in numbering order
+pub(crate) fn iter(set: RegSet) -> impl Iterator<Item = usize> {
+    (0..COUNT).filter(move |&reg| contains(set, reg))
+}
+
+/// Finds the GPR a name without `$` stands for under `abi`, or a number
+pub(crate) fn gpr_index(name: &str, abi: Abi) -> Option<u32> {
+    if let Ok(index) = name.parse::<u32>() {
+        return (index < 32).then_some(index);
+    }
+    let named = match abi {
+        Abi::N32 | Abi::N64 => N32_GPRS
+            .iter()
+            .position(|&gpr| gpr == name)
+            .map(|index| index as u32),
+        Abi::O32 | Abi::NUMERIC => {
+            (0..32).find(|&index| Utils::get_register_name_o32(index)[1..] == *name)
+        }
+    };
+    named.or((name == "s8").then_some(30))
+}
+
+/// Parses a register name as accepted by the JS API
+///
+/// Accepts GPRs named after `abi` (`$a0`) or by number (`$4`), FPRs
+/// (`$f12`), `hi` and `lo`. The leading `$` is optional.
+pub(crate) fn parse(name: &str, abi: Abi) -> napi::Result<usize> {
+    let bare = name.trim().trim_start_matches('$').to_ascii_lowercase();
+
+    let reg = match bare.as_str() {
+        "hi" => Some(HI),
+        "lo" => Some(LO),
+        _ => match bare.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+            Some(index) => (index < 32).then(|| fpr(index)),
+            None => gpr_index(&bare, abi).map(|index| index as usize),
+        },
+    };
+
+    reg.ok_or_else(|| {
+        napi::Error::new(
+            napi::Status::InvalidArg,
+            format!("Unknown register '{}'", name),
+        )
+    })
+}
+
+/// Returns the name of a register, using the GPR names of `abi`
+///
+/// Numeric names are not valid identifiers, so `NUMERIC` gets O32 names.
+pub(crate) fn name(reg: usize, abi: Abi) -> String {
+    match (reg, abi) {
+        (0..=31, Abi::N32 | Abi::N64) => format!("${}", N32_GPRS[reg]),
+        (0..=31, Abi::O32 | Abi::NUMERIC) => Utils::get_register_name_o32(reg as u32),
+        (32..=63, _) => format!("$f{}", reg - 32),
+        (HI, _) => "hi".to_string(),
+        (LO, _) => "lo".to_string(),
+        _ => format!("$invalid_{}", reg),
+    }
+}
+
+/// Registers used to pass arguments, in order
+pub(crate) fn argument_gprs(abi: Abi) -> &'static [usize] {
+    match abi {
+        Abi::N32 | Abi::N64 => &[4, 5, 6, 7, 8, 9, 10, 11],
+        Abi::O32 | Abi::NUMERIC => &[4, 5, 6, 7],
+    }
+}
+
+/// Floating point registers used to pass arguments, in order
+pub(crate) fn argument_fprs(abi: Abi) -> Vec<usize> {
+    match abi {
+        Abi::N32 | Abi::N64 => (12..20).map(fpr).collect(),
+        Abi::O32 | Abi::NUMERIC => vec![fpr(12), fpr(14)],
+    }
+}
+
+/// Registers holding return values: `$v0`, `$v1`, `$f0` and `$f2`
+pub(crate) fn return_values() -> RegSet {
+    bit(2) | bit(3) | bit(fpr(0)) | bit(fpr(2))
+}
+
+/// Registers a function call may overwrite, including the return values
+pub(crate) fn call_clobbers(abi: Abi) -> RegSet {
+    let mut set = bit(HI) | bit(LO) | bit(RA);
+    // $at, $v0-$v1, $a0-$a3, $t0-$t9
+    for reg in (1..=15).chain(24..=25) {
+        set |= bit(reg);
+    }
+    let fprs = match abi {
+        Abi::N64 => 0..24,
+        Abi::N32 | Abi::O32 | Abi::NUMERIC => 0..20,
+    };
+    for index in fprs {
+        set |= bit(fpr(index));
+    }
+    set
+}
+
+/// Registers read by an instruction, excluding `$zero`
+pub(crate) fn uses(instr: &RustInstruction) -> RegSet {
+    let mut set = 0;
+    if instr.reads_rs() {
+        set |= bit(fields::rs(instr) as usize);
+    }
+    if instr.reads_rt() {
+        set |= bit(fields::rt(instr) as usize);
+    }
+    if instr.reads_rd() {
+        set |= bit(fields::rd(instr) as usize);
+    }
+    if instr.reads_hi() {
+        set |= bit(HI);
+    }
+    if instr.reads_lo() {
+        set |= bit(LO);
+    }
+    if instr.reads_fs() {
+        set |= bit(fpr(fields::fs(instr)));
+    }
+    if instr.reads_ft() {
+        set |= bit(fpr(fields::ft(instr)));
+    }
+    if instr.reads_fd() {
+        set |= bit(fpr(fields::fd(instr)));
+    }
+    set & !bit(0)
+}
+
+/// Registers written by an instruction, excluding `$zero`
+pub(crate) fn defs(instr: &RustInstruction) -> RegSet {
+    let mut set = 0;
+    if instr.modifies_rs() {
+        set |= bit(fields::rs(instr) as usize);
+    }
+    if instr.modifies_rt() {
+        set |= bit(fields::rt(instr) as usize);
+    }
+    if instr.modifies_rd() {
+        set |= bit(fields::rd(instr) as usize);
+    }
+    if instr.does_link() && !instr.modifies_rd() {
+        set |= bit(RA);
+    }
+    if instr.modifies_hi() {
+        set |= bit(HI);
+    }
+    if instr.modifies_lo() {
+        set |= bit(LO);
+    }
+    if instr.modifies_fs() {
+        set |= bit(fpr(fields::fs(instr)));
+    }
+    if instr.modifies_ft() {
+        set |= bit(fpr(fields::ft(instr)));
+    }
+    if instr.modifies_fd() {
+        set |= bit(fpr(fields::fd(instr)));
+    }
+    set & !bit(0)
+}
@@ -0,0 +1,252 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use napi_derive::napi;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::fields;
+use crate::section::{decode_instructions, CodeSection};
+use crate::symbols::{self, Symbol};
+
+/// Why a reference could not be kept when moving code
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RelocateIssueKind {
+    /// A branch out of the moved code would be more than 128 KiB away
+    BranchOutOfRange,
+    /// A `j` or `jal` target would be in another 256 MiB region
+    JumpOutOfRegion,
+    /// The `lo` halves sharing a `lui` no longer fit under one `%hi`
+    HiLoOutOfRange,
+}
+
+/// A reference `relocate` left unchanged
+#[napi(object)]
+pub struct RelocateIssue {
+    /// Address of the instruction after the move
+    pub vram: u32,
+    pub kind: RelocateIssueKind,
+    /// Address the instruction refers to after the move
+    pub target: u32,
+    /// Symbol covering the target, when symbols were given
+    pub symbol: Option<String>,
+    pub message: String,
+}
+
+/// The words of moved code
+#[napi(object)]
+pub struct RelocatedCode {
+    pub words: Vec<u32>,
+    pub issues: Vec<RelocateIssue>,
+}
+
+/// Checks for the `addiu` or load/store taking the `%lo` half of an address
+fn is_lo(instr: &RustInstruction) -> bool {
+    matches!(
+        instr.unique_id,
+        InstrId::cpu_addiu | InstrId::cpu_daddiu | InstrId::rsp_addiu
+    ) || ((instr.does_load() || instr.does_store())
+        && instr.has_operand_alias(rabbitizer::OperandType::cpu_immediate))
+}
+
+/// Pairs every `lui` with the `%lo` instructions based on its register
+///
+/// Like `RegisterTracker`, this follows straight lines of code: a `lui` is
+/// forgotten once its register is written again or after the delay slot of
+/// an unconditional jump.
+pub(crate) fn hi_lo_pairs(instrs: &[RustInstruction]) -> BTreeMap<usize, Vec<usize>> {
+    let mut pairs: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut his: [Option<usize>; 32] = [None; 32];
+    let mut ends_after = false;
+    for (i, instr) in instrs.iter().enumerate() {
+        if is_lo(instr) {
+            if let Some(hi) = his[fields::rs(instr) as usize] {
+                pairs.entry(hi).or_default().push(i);
+            }
+        }
+        if matches!(instr.unique_id, InstrId::cpu_lui | InstrId::rsp_lui) {
+            his[fields::rt(instr) as usize] = Some(i);
+        } else if let Some(reg) = instr.destination_gpr() {
+            his[reg as usize] = None;
+        }
+
+        if ends_after {
+            his = [None; 32];
+        }
+        ends_after = (instr.is_jump() && !instr.does_link()) || instr.is_unconditional_branch();
+    }
+    pairs
+}
+
+/// Replaces the low 16 bits of a word
+fn with_immediate(word: u32, imm: u32) -> u32 {
+    (word & !0xFFFF) | (imm & 0xFFFF)
+}
+
+struct Relocator<'a> {
+    from: u32,
+    size: u32,
+    to: u32,
+    symbols: BTreeMap<u32, &'a Symbol>,
+    issues: Vec<RelocateIssue>,
+}
+
+impl Relocator<'_> {
+    fn is_moved(&self, address: u32) -> bool {
+        address.wrapping_sub(self.from) < self.size
+    }
+
+    /// Where an address ends up after the move
+    fn moved(&self, address: u32) -> u32 {
+        if self.is_moved(address) {
+            address.wrapping_sub(self.from).wrapping_add(self.to)
+        } else {
+            address
+        }
+    }
+
+    fn symbol(&self, address: u32) -> Option<String> {
+        let (&start, symbol) = self.symbols.range(..=address).next_back()?;
+        let inside = address == start || symbol.size.is_some_and(|size| address - start < size);
+        inside.then(|| symbols::with_offset(&symbol.name, address - start))
+    }
+
+    fn issue(&mut self, vram: u32, kind: RelocateIssueKind, target: u32, message: String) {
+        self.issues.push(RelocateIssue {
+            vram,
+            kind,
+            target,
+            symbol: self.symbol(target),
+            message,
+        });
+    }
+
+    /// Rewrites a jump or branch, if it needs to change
+    fn control_flow(&mut self, instr: &RustInstruction, vram: u32) -> Option<u32> {
+        let word = instr.raw();
+        if instr.is_jump_with_address() {
+            let target = self.moved(instr.instr_index_as_vram());
+            if !fields::in_jump_region(vram, target) {
+                let message = format!(
+                    "Jump to 0x{:08X} is outside the 256 MiB region of 0x{:08X}",
+                    target, vram
+                );
+                self.issue(vram, RelocateIssueKind::JumpOutOfRegion, target, message);
+                return None;
+            }
+            return Some((word & !0x03FF_FFFF) | ((target >> 2) & 0x03FF_FFFF));
+        }
+
+        if !instr.is_branch() {
+            return None;
+        }
+        let target = instr.vram.wrapping_add(instr.branch_offset() as u32);
+        // Branches within the moved code keep their offset
+        if self.is_moved(target) {
+            return None;
+        }
+        let offset = target.wrapping_sub(vram.wrapping_add(4)) as i32;
+        if !(-0x20000..0x20000).contains(&offset) {
+            let message = format!(
+                "Branch to 0x{:08X} is out of range from 0x{:08X}",
+                target, vram
+            );
+            self.issue(vram, RelocateIssueKind::BranchOutOfRange, target, message);
+            return None;
+        }
+        Some(with_immediate(word, (offset >> 2) as u32))
+    }
+
+    /// Rewrites a `lui` and its `%lo` instructions for moved addresses
+    fn hi_lo(&mut self, instrs: &[RustInstruction], words: &mut [u32], hi: usize, los: &[usize]) {
+        let upper = fields::immediate(&instrs[hi]) << 16;
+        let addresses: Vec<u32> = los
+            .iter()
+            .map(|&lo| upper.wrapping_add(instrs[lo].processed_immediate() as u32))
+            .collect();
+        if !addresses.iter().any(|&address| self.is_moved(address)) {
+            return;
+        }
+
+        let moved: Vec<u32> = addresses
+            .iter()
+            .map(|&address| self.moved(address))
+            .collect();
+        // %hi is adjusted for the sign extension of %lo
+        let new_upper = moved[0].wrapping_add(0x8000) & 0xFFFF_0000;
+        for (&lo, &address) in los.iter().zip(&moved) {
+            let offset = address.wrapping_sub(new_upper) as i32;
+            if !(-0x8000..0x8000).contains(&offset) {
+                let vram = self.to.wrapping_add(lo as u32 * 4);
+                let message = format!(
+                    "%lo of 0x{:08X} at 0x{:08X} does not share the %hi of 0x{:08X}",
+                    address, vram, moved[0]
+                );
+                self.issue(vram, RelocateIssueKind::HiLoOutOfRange, address, message);
+                return;
+            }
+        }
+
+        words[hi] = with_immediate(words[hi], new_upper >> 16);
+        for (&lo, &address) in los.iter().zip(&moved) {
+            words[lo] = with_immediate(words[lo], address.wrapping_sub(new_upper));
+        }
+    }
+}
+
+/// Moves the code of a section to another address
+///
+/// Rewrites `j`/`jal` targets and `%hi`/`%lo` pairs pointing into the moved
+/// code, and branches leaving it. References that cannot follow, like a
+/// branch now more than 128 KiB away from its target, are left as they were
+/// and reported. `%hi`/`%lo` pairs are found along straight lines of code,
+/// `lui`/`ori` constants are never changed.
+///
+/// # Arguments
+/// * `section` - The code to move
+/// * `from_vram` - Address the code was linked at, usually `section.vram`
+/// * `to_vram` - Address to move it to
+/// * `symbols` - Contents of a symbol file to name the targets of issues with (optional)
+#[napi]
+pub fn relocate(
+    section: &CodeSection,
+    from_vram: u32,
+    to_vram: u32,
+    symbols: Option<String>,
+) -> napi::Result<RelocatedCode> {
+    let symbols = match symbols {
+        Some(text) => symbols::parse(&text)?,
+        None => Vec::new(),
+    };
+    let mut words: Vec<u32> = section.instrs().iter().map(|instr| instr.raw()).collect();
+    let instrs = decode_instructions(&words, from_vram, section.instr_category());
+
+    let mut relocator = Relocator {
+        from: from_vram,
+        size: words.len() as u32 * 4,
+        to: to_vram,
+        symbols: symbols
+            .iter()
+            .map(|symbol| (symbol.address, symbol))
+            .collect(),
+        issues: Vec::new(),
+    };
+    for (i, instr) in instrs.iter().enumerate() {
+        let vram = to_vram.wrapping_add(i as u32 * 4);
+        if let Some(word) = relocator.control_flow(instr, vram) {
+            words[i] = word;
+        }
+    }
+    for (hi, los) in hi_lo_pairs(&instrs) {
+        relocator.hi_lo(&instrs, &mut words, hi, &los);
+    }
+    relocator.issues.sort_by_key(|issue| issue.vram);
+
+    Ok(RelocatedCode {
+        words,
+        issues: relocator.issues,
+    })
+}
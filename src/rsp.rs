@@ -0,0 +1,315 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use rabbitizer::InstrCategory;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::assembler;
+use crate::fields;
+use crate::instruction::Instruction;
+use crate::section::decode_words;
+use crate::symbols::{self, Symbol};
+use crate::tracker::RegisterTracker;
+
+/// Address of the first byte of RSP data memory
+const DMEM_START: u32 = 0x0400_0000;
+/// Address of the first byte of RSP instruction memory
+const IMEM_START: u32 = 0x0400_1000;
+/// Both memories are 4 KiB, and the RSP only keeps 12 bits of its addresses
+const MEMORY_MASK: u32 = 0xFFF;
+
+/// A load or store whose DMEM address is known
+#[napi(object)]
+pub struct DmemAccess {
+    /// Address of the instruction
+    pub vram: u32,
+    /// Offset into DMEM
+    pub offset: u32,
+    pub is_write: bool,
+    /// Symbol covering the offset, like `table + 0x8`, when symbols were given
+    pub symbol: Option<String>,
+}
+
+/// Where a symbol file address points to on the RSP
+enum Memory {
+    Dmem(u32),
+    Imem(u32),
+}
+
+fn memory_of(address: u32) -> Option<Memory> {
+    match address {
+        0..=0xFFF => Some(Memory::Dmem(address)),
+        0x1000..=0x1FFF => Some(Memory::Imem(address & MEMORY_MASK)),
+        DMEM_START..=0x0400_0FFF => Some(Memory::Dmem(address & MEMORY_MASK)),
+        IMEM_START..=0x0400_1FFF => Some(Memory::Imem(address & MEMORY_MASK)),
+        _ => None,
+    }
+}
+
+/// Wraps an address into IMEM
+fn imem(address: u32) -> u32 {
+    IMEM_START | (address & MEMORY_MASK)
+}
+
+/// Symbols split by memory, keyed by 12-bit offset
+struct RspSymbols {
+    dmem: BTreeMap<u32, Symbol>,
+    imem: BTreeMap<u32, Symbol>,
+}
+
+impl RspSymbols {
+    fn new(symbols: Vec<Symbol>) -> Self {
+        let mut dmem = BTreeMap::new();
+        let mut imem = BTreeMap::new();
+        for symbol in symbols {
+            match memory_of(symbol.address) {
+                Some(Memory::Dmem(offset)) => dmem.insert(offset, symbol),
+                Some(Memory::Imem(offset)) => imem.insert(offset, symbol),
+                None => None,
+            };
+        }
+        RspSymbols { dmem, imem }
+    }
+
+    /// Names a DMEM offset by the symbol starting at or covering it
+    fn dmem(&self, offset: u32) -> Option<String> {
+        let (&start, symbol) = self.dmem.range(..=offset).next_back()?;
+        let inside = offset == start || symbol.size.is_some_and(|size| offset - start < size);
+        inside.then(|| symbols::with_offset(&symbol.name, offset - start))
+    }
+
+    fn imem(&self, address: u32) -> Option<String> {
+        self.imem
+            .get(&(address & MEMORY_MASK))
+            .map(|symbol| symbol.name.clone())
+    }
+}
+
+/// Checks for the RSP vector loads and stores (`lqv`, `sdv`, ...)
+fn is_vector_memory_access(instr: &RustInstruction) -> bool {
+    matches!(instr.raw() >> 26, 0x32 | 0x3A)
+}
+
+/// The offset of a load or store, scaled by the element size for vector ones
+fn memory_offset(instr: &RustInstruction) -> u32 {
+    if !is_vector_memory_access(instr) {
+        return instr.processed_immediate() as u32;
+    }
+    // The 7-bit offset is counted in elements of the access size
+    let offset = ((instr.raw() << 25) as i32 >> 25) as u32;
+    let shift = match (instr.raw() >> 11) & 0x1F {
+        0 => 0,         // lbv
+        1 => 1,         // lsv
+        2 => 2,         // llv
+        3 | 6 | 7 => 3, // ldv, lpv, luv
+        _ => 4,         // lqv, lrv, lhv, lfv, lwv, ltv
+    };
+    offset << shift
+}
+
+/// The IMEM address a branch or jump goes to, wrapped into IMEM
+fn branch_target(instr: &RustInstruction) -> Option<u32> {
+    if instr.is_jump_with_address() {
+        Some(imem((instr.raw() & 0x03FF_FFFF) << 2))
+    } else if instr.is_branch() {
+        let offset = (fields::immediate(instr) as u16 as i16 as i32) << 2;
+        Some(imem(instr.vram.wrapping_add(4).wrapping_add(offset as u32)))
+    } else {
+        None
+    }
+}
+
+/// RSP microcode loaded into IMEM
+///
+/// Instruction addresses, branch and jump targets wrap around within the
+/// 4 KiB of IMEM at `0x04001000`, and loads and stores with a known base are
+/// resolved to DMEM offsets.
+#[napi]
+pub struct RspSection {
+    vram: u32,
+    instructions: Vec<RustInstruction>,
+}
+
+impl RspSection {
+    fn from_words(words: &[u32], vram: u32) -> Self {
+        let vram = imem(vram);
+        let instructions = words
+            .iter()
+            .enumerate()
+            .map(|(i, &word)| {
+                RustInstruction::new(
+                    word,
+                    imem(vram.wrapping_add(i as u32 * 4)),
+                    InstrCategory::RSP,
+                )
+            })
+            .collect();
+        RspSection { vram, instructions }
+    }
+
+    /// Finds the loads and stores with a known DMEM address
+    ///
+    /// Base registers are tracked along straight lines of code, starting
+    /// over at every branch target.
+    fn accesses(&self, symbols: Option<&RspSymbols>) -> Vec<DmemAccess> {
+        let targets = self.targets();
+        let mut tracker = RegisterTracker::new();
+        let mut accesses = Vec::new();
+        for instr in &self.instructions {
+            if targets.contains(&instr.vram) {
+                tracker = RegisterTracker::new();
+            }
+            let is_write = instr.does_store() || instr.raw() >> 26 == 0x3A;
+            if instr.does_load() || is_write || is_vector_memory_access(instr) {
+                if let Some(base) = tracker.get(fields::rs(instr)) {
+                    let offset = base.wrapping_add(memory_offset(instr)) & MEMORY_MASK;
+                    accesses.push(DmemAccess {
+                        vram: instr.vram,
+                        offset,
+                        is_write,
+                        symbol: symbols.and_then(|symbols| symbols.dmem(offset)),
+                    });
+                }
+            }
+            tracker.step(instr);
+        }
+        accesses
+    }
+
+    fn targets(&self) -> BTreeSet<u32> {
+        self.instructions.iter().filter_map(branch_target).collect()
+    }
+}
+
+#[napi]
+impl RspSection {
+    /// Creates an RspSection from raw bytes
+    ///
+    /// # Arguments
+    /// * `data` - The microcode, its length must be a multiple of 4
+    /// * `vram` - Address of the first instruction, wrapped into IMEM (optional, defaults to 0x04001000)
+    /// * `endianness` - Either "big" or "little" (optional, defaults to "big")
+    #[napi(constructor)]
+    pub fn new(data: Buffer, vram: Option<u32>, endianness: Option<String>) -> napi::Result<Self> {
+        let words = decode_words(&data, endianness.as_deref())?;
+        Ok(Self::from_words(&words, vram.unwrap_or(IMEM_START)))
+    }
+
+    /// Creates an RspSection from already decoded instruction words
+    #[napi(factory)]
+    pub fn from_word_list(words: Vec<u32>, vram: Option<u32>) -> Self {
+        Self::from_words(&words, vram.unwrap_or(IMEM_START))
+    }
+
+    /// Gets the IMEM address of the first instruction
+    #[napi(getter)]
+    pub fn vram(&self) -> u32 {
+        self.vram
+    }
+
+    /// Gets the size of the section in bytes
+    #[napi(getter)]
+    pub fn size(&self) -> u32 {
+        self.instructions.len() as u32 * 4
+    }
+
+    /// Gets every instruction, with addresses wrapped into IMEM
+    #[napi]
+    pub fn instructions(&self) -> Vec<Instruction> {
+        self.instructions
+            .iter()
+            .cloned()
+            .map(Instruction::from_inner)
+            .collect()
+    }
+
+    /// Gets the IMEM address a branch or jump goes to, wrapped into IMEM
+    ///
+    /// Returns null if `vram` is not a branch or jump with a target.
+    #[napi]
+    pub fn branch_target(&self, vram: u32) -> Option<u32> {
+        let index = (imem(vram).wrapping_sub(self.vram) & MEMORY_MASK) / 4;
+        self.instructions
+            .get(index as usize)
+            .and_then(branch_target)
+    }
+
+    /// Lists the loads and stores whose DMEM address is known
+    ///
+    /// # Arguments
+    /// * `symbols` - Contents of a symbol file to name the offsets with (optional)
+    #[napi]
+    pub fn dmem_accesses(&self, symbols: Option<String>) -> napi::Result<Vec<DmemAccess>> {
+        let symbols = match symbols {
+            Some(text) => Some(RspSymbols::new(symbols::parse(&text)?)),
+            None => None,
+        };
+        Ok(self.accesses(symbols.as_ref()))
+    }
+
+    /// Disassembles the microcode, one instruction per line
+    ///
+    /// Branch and jump targets get labels, named after the IMEM symbols or
+    /// `.L04001040` otherwise. Scalar loads and stores based on `$zero` have
+    /// their offset replaced by the DMEM symbol, other resolved accesses get
+    /// it in a trailing comment.
+    ///
+    /// # Arguments
+    /// * `symbols` - Contents of a symbol file, with lines like `name = 0x04000010; // size:0x40`. DMEM addresses may be given as `0x04000xxx` or as 12-bit offsets (optional)
+    #[napi]
+    pub fn disassemble(&self, symbols: Option<String>) -> napi::Result<String> {
+        let symbols = RspSymbols::new(match symbols {
+            Some(text) => symbols::parse(&text)?,
+            None => Vec::new(),
+        });
+        let label = |address: u32| {
+            symbols
+                .imem(address)
+                .unwrap_or_else(|| format!(".L{:08X}", address))
+        };
+
+        let targets = self.targets();
+        let accesses: BTreeMap<u32, DmemAccess> = self
+            .accesses(Some(&symbols))
+            .into_iter()
+            .map(|access| (access.vram, access))
+            .collect();
+
+        let mut lines = Vec::new();
+        for instr in &self.instructions {
+            if targets.contains(&instr.vram) || symbols.imem(instr.vram).is_some() {
+                lines.push(format!("{}:", label(instr.vram)));
+            }
+
+            let access = accesses.get(&instr.vram);
+            let name = access.and_then(|access| access.symbol.clone());
+            // rabbitizer only lets the offset of scalar accesses be replaced
+            let on_zero = fields::rs(instr) == 0 && !is_vector_memory_access(instr);
+            let operand = match branch_target(instr) {
+                Some(target) => Some(label(target)),
+                None if on_zero => name.clone(),
+                None => None,
+            };
+
+            let mut line = format!(
+                "/* {:08X} {:08X} */  {}",
+                instr.vram,
+                instr.raw(),
+                assembler::disassemble(instr, operand.as_deref())
+            );
+            match (name, access) {
+                (Some(name), _) if !on_zero => line.push_str(&format!("  /* {} */", name)),
+                (None, Some(access)) => {
+                    line.push_str(&format!("  /* DMEM 0x{:03X} */", access.offset))
+                }
+                _ => {}
+            }
+            lines.push(line);
+        }
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,391 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! Searching binaries for instruction idioms
+//!
+//! A pattern is a list of instructions separated by `;` or new lines, matched
+//! against consecutive words:
+//!
+//! - `?` matches any word
+//! - `0x3C01????` matches a raw word, each `?` standing for any nibble, and
+//!   `0x3C010000/0xFFFF0000` compares only the bits set in the mask
+//! - `mnemonic operands` matches a valid instruction. `*` and `?` in the
+//!   mnemonic are wildcards (`b*`). Without operands any are accepted;
+//!   otherwise there must be as many as the instruction has, each one being
+//!   `?`, a number (`0x10`, `-8`), an inclusive range (`0x10..0x20`), a
+//!   register (`$a0`, `$4`, `$f12`) or a class of registers: `$v?`, `$a?`,
+//!   `$t?`, `$s?` (`$s0`-`$s8`), `$k?`, `$f*` for any FPR and `$*` for any
+//!   GPR. `?($at)` matches the offset and base of loads and stores
+//!   separately. Jump and branch operands are compared as target addresses.
+//!   Other operands, like COP0 and vector registers, are matched as printed,
+//!   `*` and `?` being wildcards.
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use rabbitizer::Instruction as RustInstruction;
+use rabbitizer::OperandType;
+
+use crate::assembler::{parse_number, print_operand};
+use crate::enums::Abi;
+use crate::fields;
+use crate::instruction::parse_category;
+use crate::registers::{self, RegSet};
+use crate::section::{decode_instructions, decode_words};
+
+/// Options for `search`
+#[napi(object)]
+pub struct SearchOptions {
+    /// Address of the first byte of the buffer (defaults to 0)
+    pub vram: Option<u32>,
+    /// "cpu", "rsp", "r3000gte", "r4000allegrex" or "r5900" (defaults to "cpu")
+    pub category: Option<String>,
+    /// Byte order of the words in the buffer, "big" or "little" (defaults to "big")
+    pub endianness: Option<String>,
+}
+
+/// Matches one operand, or one half of `offset(base)`
+enum Value {
+    Any,
+    Number(i64),
+    Range(i64, i64),
+    /// GPRs and FPRs, numbered as in `registers`, and the pattern they were
+    /// given as for the other kinds of registers
+    Registers(RegSet, String),
+    Wildcard(String),
+}
+
+/// A decoded operand, or one half of `offset(base)`
+enum Field {
+    Register(usize),
+    Number(i64),
+}
+
+struct Operand {
+    outer: Value,
+    /// The part in parentheses
+    inner: Option<Value>,
+}
+
+/// Matches one word
+enum Step {
+    Any,
+    Word {
+        value: u32,
+        mask: u32,
+    },
+    Instruction {
+        mnemonic: String,
+        operands: Option<Vec<Operand>>,
+    },
+}
+
+fn invalid(step: &str, reason: &str) -> napi::Error {
+    napi::Error::new(
+        napi::Status::InvalidArg,
+        format!("Invalid pattern '{}': {}", step, reason),
+    )
+}
+
+/// Matches `text` against a pattern where `*` is any run of characters and
+/// `?` any one character
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| wildcard_match(rest, &text[skip..])),
+        Some((&c, rest)) => match text.split_first() {
+            Some((&t, text)) if c == b'?' || c.eq_ignore_ascii_case(&t) => {
+                wildcard_match(rest, text)
+            }
+            _ => false,
+        },
+    }
+}
+
+/// Splits `0x10($sp)` into `0x10` and `$sp`
+fn split_base(text: &str) -> (&str, Option<&str>) {
+    match text.strip_suffix(')').and_then(|text| text.split_once('(')) {
+        Some((outer, inner)) => (outer.trim(), Some(inner.trim())),
+        None => (text.trim(), None),
+    }
+}
+
+/// The registers a register pattern stands for, or none if it is not one
+fn register_set(text: &str) -> RegSet {
+    let gprs = |indices: &[usize]| {
+        indices
+            .iter()
+            .fold(0, |set, &reg| set | registers::bit(reg))
+    };
+    let Some(name) = text.strip_prefix('$') else {
+        return 0;
+    };
+    let (class, wildcards) = name.split_at(name.find(['?', '*']).unwrap_or(name.len()));
+    if wildcards.is_empty() {
+        return registers::parse(name, Abi::O32).map_or(0, registers::bit);
+    }
+    if !wildcards.bytes().all(|c| c == b'?' || c == b'*') {
+        return 0;
+    }
+    match class.to_ascii_lowercase().as_str() {
+        "" => gprs(&(0..32).collect::<Vec<_>>()),
+        "v" => gprs(&[2, 3]),
+        "a" => gprs(&[4, 5, 6, 7]),
+        "t" => gprs(&[8, 9, 10, 11, 12, 13, 14, 15, 24, 25]),
+        "s" => gprs(&[16, 17, 18, 19, 20, 21, 22, 23, 30]),
+        "k" => gprs(&[26, 27]),
+        "f" => (0..32).fold(0, |set, index| set | registers::bit(registers::fpr(index))),
+        _ => 0,
+    }
+}
+
+impl Value {
+    fn parse(text: &str, step: &str) -> napi::Result<Self> {
+        if text == "?" || text == "*" {
+            return Ok(Value::Any);
+        }
+        if let Some((low, high)) = text.split_once("..") {
+            let low = parse_number(low.trim()).ok_or_else(|| invalid(step, "bad range"))?;
+            let high = parse_number(high.trim()).ok_or_else(|| invalid(step, "bad range"))?;
+            return Ok(Value::Range(low, high));
+        }
+        if let Some(number) = parse_number(text) {
+            return Ok(Value::Number(number));
+        }
+        if text.is_empty() {
+            return Err(invalid(step, "empty operand"));
+        }
+        match register_set(text) {
+            0 => Ok(Value::Wildcard(text.to_string())),
+            set => Ok(Value::Registers(set, text.to_string())),
+        }
+    }
+
+    fn matches_field(&self, field: &Field) -> bool {
+        match (self, field) {
+            (Value::Any, _) => true,
+            (Value::Number(number), Field::Number(value)) => number == value,
+            (Value::Range(low, high), Field::Number(value)) => (*low..=*high).contains(value),
+            (Value::Registers(set, _), Field::Register(reg)) => registers::contains(*set, *reg),
+            _ => false,
+        }
+    }
+
+    fn matches_text(&self, text: &str) -> bool {
+        match self {
+            Value::Any => true,
+            Value::Number(number) => parse_number(text) == Some(*number),
+            Value::Range(low, high) => {
+                parse_number(text).is_some_and(|n| (*low..=*high).contains(&n))
+            }
+            Value::Registers(_, pattern) | Value::Wildcard(pattern) => {
+                wildcard_match(pattern.as_bytes(), text.as_bytes())
+            }
+        }
+    }
+}
+
+/// Decodes an operand as a register or number, with the base of `offset(base)`
+///
+/// Jump and branch targets are addresses. Other kinds of operands are left to
+/// their printed text.
+fn decode(kind: OperandType, instr: &RustInstruction) -> Option<(Field, Option<Field>)> {
+    let gpr = |reg: u32| Field::Register(reg as usize);
+    let fpr = |reg: u32| Field::Register(registers::fpr(reg));
+    let field = match kind {
+        OperandType::cpu_rs | OperandType::rsp_rs => gpr(fields::rs(instr)),
+        OperandType::cpu_rt | OperandType::rsp_rt => gpr(fields::rt(instr)),
+        OperandType::cpu_rd | OperandType::rsp_rd => gpr(fields::rd(instr)),
+        OperandType::cpu_fs => fpr(fields::fs(instr)),
+        OperandType::cpu_ft => fpr(fields::ft(instr)),
+        OperandType::cpu_fd => fpr(fields::fd(instr)),
+        OperandType::cpu_sa => Field::Number(fields::sa(instr) as i64),
+        OperandType::cpu_immediate => Field::Number(instr.processed_immediate() as i64),
+        OperandType::cpu_immediate_base | OperandType::rsp_immediate_base => {
+            let offset = Field::Number(instr.processed_immediate() as i64);
+            return Some((offset, Some(gpr(fields::rs(instr)))));
+        }
+        OperandType::cpu_label => Field::Number(instr.instr_index_as_vram() as i64),
+        OperandType::cpu_branch_target_label => {
+            Field::Number(instr.vram.wrapping_add(instr.branch_offset() as u32) as i64)
+        }
+        _ => return None,
+    };
+    Some((field, None))
+}
+
+impl Operand {
+    fn matches(&self, kind: OperandType, instr: &RustInstruction) -> bool {
+        if matches!((&self.outer, &self.inner), (Value::Any, None)) {
+            return true;
+        }
+        if let Some((outer, inner)) = decode(kind, instr) {
+            let inner_matches = match (&self.inner, &inner) {
+                (None, None) => true,
+                (Some(pattern), Some(inner)) => pattern.matches_field(inner),
+                _ => false,
+            };
+            return inner_matches && self.outer.matches_field(&outer);
+        }
+        let Some(text) = print_operand(kind, instr) else {
+            return false;
+        };
+        let (outer, inner) = split_base(&text);
+        let inner_matches = match (&self.inner, inner) {
+            (None, None) => true,
+            (Some(pattern), Some(inner)) => pattern.matches_text(inner),
+            _ => false,
+        };
+        inner_matches && self.outer.matches_text(outer)
+    }
+}
+
+/// Parses a raw word pattern like `0x3C01????` or `0x3C010000/0xFFFF0000`
+fn parse_word(step: &str) -> napi::Result<Step> {
+    let hex = |text: &str| -> napi::Result<(u32, u32)> {
+        let digits = text
+            .strip_prefix("0x")
+            .or_else(|| text.strip_prefix("0X"))
+            .filter(|digits| !digits.is_empty() && digits.len() <= 8)
+            .ok_or_else(|| invalid(step, "expected up to 8 hex digits after 0x"))?;
+        let mut value = 0u32;
+        let mut mask = 0u32;
+        for c in digits.chars() {
+            value <<= 4;
+            mask <<= 4;
+            if c != '?' {
+                value |= c
+                    .to_digit(16)
+                    .ok_or_else(|| invalid(step, "expected hex digits"))?;
+                mask |= 0xF;
+            }
+        }
+        Ok((value, mask))
+    };
+    match step.split_once('/') {
+        Some((value, mask)) => {
+            let (value, _) = hex(value.trim())?;
+            let (mask, _) = hex(mask.trim())?;
+            Ok(Step::Word {
+                value: value & mask,
+                mask,
+            })
+        }
+        None => {
+            let (value, mask) = hex(step)?;
+            Ok(Step::Word { value, mask })
+        }
+    }
+}
+
+fn parse_step(step: &str) -> napi::Result<Step> {
+    if step == "?" {
+        return Ok(Step::Any);
+    }
+    if step.starts_with("0x") || step.starts_with("0X") {
+        return parse_word(step);
+    }
+
+    let (mnemonic, rest) = match step.split_once(char::is_whitespace) {
+        Some((mnemonic, rest)) => (mnemonic, rest.trim()),
+        None => (step, ""),
+    };
+    let operands = if rest.is_empty() {
+        None
+    } else {
+        let operands = rest
+            .split(',')
+            .map(|operand| {
+                let (outer, inner) = split_base(operand);
+                let outer = if outer.is_empty() && inner.is_some() {
+                    Value::Any
+                } else {
+                    Value::parse(outer, step)?
+                };
+                Ok(Operand {
+                    outer,
+                    inner: inner.map(|inner| Value::parse(inner, step)).transpose()?,
+                })
+            })
+            .collect::<napi::Result<Vec<_>>>()?;
+        Some(operands)
+    };
+    Ok(Step::Instruction {
+        mnemonic: mnemonic.to_string(),
+        operands,
+    })
+}
+
+impl Step {
+    fn matches(&self, instr: &RustInstruction) -> bool {
+        match self {
+            Step::Any => true,
+            Step::Word { value, mask } => instr.raw() & mask == *value,
+            Step::Instruction { mnemonic, operands } => {
+                if !instr.is_valid()
+                    || !wildcard_match(mnemonic.as_bytes(), instr.opcode_name().as_bytes())
+                {
+                    return false;
+                }
+                let Some(operands) = operands else {
+                    return true;
+                };
+                let kinds = instr.get_operands_slice();
+                kinds.len() == operands.len()
+                    && kinds
+                        .iter()
+                        .zip(operands)
+                        .all(|(&kind, operand)| operand.matches(kind, instr))
+            }
+        }
+    }
+}
+
+/// Finds the places in a binary matching an instruction pattern
+///
+/// Patterns are instructions separated by `;` or new lines, like
+/// `lui $at, ?; lw $t?, ?($at)` or `jal 0x80001234`; see the README for the
+/// whole syntax. Pseudo-instructions are matched under the name rabbitizer
+/// prints (`move`, `beqz`, `b`, `nop`, ...).
+///
+/// # Arguments
+/// * `buffer` - The binary to search
+/// * `pattern` - The instructions to look for
+/// * `options` - The vram of the buffer and how to decode it (optional)
+#[napi]
+pub fn search(
+    buffer: Buffer,
+    pattern: String,
+    options: Option<SearchOptions>,
+) -> napi::Result<Vec<u32>> {
+    let options = options.unwrap_or(SearchOptions {
+        vram: None,
+        category: None,
+        endianness: None,
+    });
+    let steps = pattern
+        .split([';', '\n'])
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .map(parse_step)
+        .collect::<napi::Result<Vec<_>>>()?;
+    if steps.is_empty() {
+        return Err(invalid(&pattern, "no instructions"));
+    }
+
+    let words = decode_words(&buffer, options.endianness.as_deref())?;
+    let instrs = decode_instructions(
+        &words,
+        options.vram.unwrap_or(0),
+        parse_category(options.category.as_deref()),
+    );
+    Ok(instrs
+        .windows(steps.len())
+        .filter(|window| {
+            window
+                .iter()
+                .zip(&steps)
+                .all(|(instr, step)| step.matches(instr))
+        })
+        .map(|window| window[0].vram)
+        .collect())
+}
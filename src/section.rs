@@ -0,0 +1,242 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use rabbitizer::InstrCategory;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::callgraph::CallGraph;
+use crate::enums::Abi;
+use crate::function::Function;
+use crate::instruction::{category_name, parse_category, Instruction};
+use crate::signature::{self, Signature};
+use crate::traversal::{self, TraversalResult};
+use crate::xref::XrefIndex;
+
+/// Reads an `endianness` option, "big" unless given
+pub(crate) fn is_little_endian(endianness: Option<&str>) -> napi::Result<bool> {
+    match endianness.unwrap_or("big") {
+        "big" => Ok(false),
+        "little" => Ok(true),
+        other => Err(napi::Error::new(
+            napi::Status::InvalidArg,
+            format!(
+                "Unknown endianness '{}', expected \"big\" or \"little\"",
+                other
+            ),
+        )),
+    }
+}
+
+/// Decodes raw bytes into 32-bit words
+///
+/// `endianness` is either "big" (the default, used by N64 binaries) or "little"
+/// (PSX, PS2 and PSP binaries).
+pub(crate) fn decode_words(data: &[u8], endianness: Option<&str>) -> napi::Result<Vec<u32>> {
+    if !data.len().is_multiple_of(4) {
+        return Err(napi::Error::new(
+            napi::Status::InvalidArg,
+            format!("Buffer length {} is not a multiple of 4", data.len()),
+        ));
+    }
+
+    let little_endian = is_little_endian(endianness)?;
+    Ok(data
+        .chunks_exact(4)
+        .map(|chunk| {
+            let bytes = [chunk[0], chunk[1], chunk[2], chunk[3]];
+            if little_endian {
+                u32::from_le_bytes(bytes)
+            } else {
+                u32::from_be_bytes(bytes)
+            }
+        })
+        .collect())
+}
+
+/// Decodes consecutive instruction words starting at `vram`
+pub(crate) fn decode_instructions(
+    words: &[u32],
+    vram: u32,
+    category: InstrCategory,
+) -> Vec<RustInstruction> {
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, &word)| RustInstruction::new(word, vram.wrapping_add(i as u32 * 4), category))
+        .collect()
+}
+
+/// A contiguous block of MIPS code loaded at a given virtual address
+#[napi]
+pub struct CodeSection {
+    vram: u32,
+    category: InstrCategory,
+    instructions: Vec<RustInstruction>,
+}
+
+impl CodeSection {
+    pub(crate) fn from_words(words: &[u32], vram: u32, category: InstrCategory) -> Self {
+        let instructions = decode_instructions(words, vram, category);
+
+        CodeSection {
+            vram,
+            category,
+            instructions,
+        }
+    }
+
+    pub(crate) fn instrs(&self) -> &[RustInstruction] {
+        &self.instructions
+    }
+
+    pub(crate) fn instr_category(&self) -> InstrCategory {
+        self.category
+    }
+
+    pub(crate) fn start(&self) -> u32 {
+        self.vram
+    }
+
+    /// Returns the index of the instruction at `vram`, if it lies inside this section
+    pub(crate) fn index_of(&self, vram: u32) -> Option<usize> {
+        let offset = vram.wrapping_sub(self.vram);
+        if !offset.is_multiple_of(4) {
+            return None;
+        }
+        let index = (offset / 4) as usize;
+        (index < self.instructions.len()).then_some(index)
+    }
+}
+
+#[napi]
+impl CodeSection {
+    /// Creates a new CodeSection from raw bytes
+    ///
+    /// # Arguments
+    /// * `data` - The section contents, its length must be a multiple of 4
+    /// * `vram` - The virtual address of the first instruction
+    /// * `category` - The instruction category as a string: "cpu", "rsp", "r3000gte", "r4000allegrex", "r5900" (optional)
+    /// * `endianness` - Either "big" or "little" (optional, defaults to "big")
+    #[napi(constructor)]
+    pub fn new(
+        data: Buffer,
+        vram: u32,
+        category: Option<String>,
+        endianness: Option<String>,
+    ) -> napi::Result<Self> {
+        let words = decode_words(&data, endianness.as_deref())?;
+        Ok(Self::from_words(
+            &words,
+            vram,
+            parse_category(category.as_deref()),
+        ))
+    }
+
+    /// Creates a new CodeSection from already decoded instruction words
+    #[napi(factory)]
+    pub fn from_word_list(words: Vec<u32>, vram: u32, category: Option<String>) -> Self {
+        Self::from_words(&words, vram, parse_category(category.as_deref()))
+    }
+
+    /// Gets the virtual address of the first instruction
+    #[napi(getter)]
+    pub fn vram(&self) -> u32 {
+        self.vram
+    }
+
+    /// Gets the size of the section in bytes
+    #[napi(getter)]
+    pub fn size(&self) -> u32 {
+        self.instructions.len() as u32 * 4
+    }
+
+    /// Gets the instruction category as a string
+    #[napi(getter)]
+    pub fn category(&self) -> String {
+        category_name(self.category).to_string()
+    }
+
+    /// Decodes every word of the section in order (linear sweep)
+    #[napi]
+    pub fn instructions(&self) -> Vec<Instruction> {
+        self.instructions
+            .iter()
+            .cloned()
+            .map(Instruction::from_inner)
+            .collect()
+    }
+
+    /// Gets the instruction at the given virtual address
+    #[napi]
+    pub fn instruction_at(&self, vram: u32) -> Option<Instruction> {
+        self.index_of(vram)
+            .map(|index| Instruction::from_inner(self.instructions[index].clone()))
+    }
+
+    /// Follows control flow from the given entry points (recursive descent)
+    ///
+    /// Every entry point and every `jal`/`bal` target found along the way is
+    /// treated as a function. Words that are never reached are reported as
+    /// probable data.
+    #[napi]
+    pub fn recursive_descent(&self, entry_points: Vec<u32>) -> TraversalResult {
+        let traversal = traversal::traverse(self, &entry_points);
+        traversal.to_result(self)
+    }
+
+    /// Builds the call graph of the functions reachable from the given entry points
+    ///
+    /// Indirect `jalr` calls are resolved when their target register was set
+    /// by a `lui`/`addiu` (or `ori`) pair earlier in the same function.
+    #[napi]
+    pub fn call_graph(&self, entry_points: Vec<u32>) -> CallGraph {
+        let traversal = traversal::traverse(self, &entry_points);
+        CallGraph::build(self, &traversal)
+    }
+
+    /// Indexes every address referenced by the code reachable from the given entry points
+    ///
+    /// Covers calls, branches, loads and stores through a known base register,
+    /// and addresses materialized with `lui`/`addiu` pairs.
+    #[napi]
+    pub fn xrefs(&self, entry_points: Vec<u32>) -> XrefIndex {
+        let traversal = traversal::traverse(self, &entry_points);
+        XrefIndex::build(self, &traversal)
+    }
+
+    /// Splits the code reachable from the given entry points into functions
+    ///
+    /// Each function runs from its entry point up to the next one, minus any
+    /// trailing words that were never reached.
+    #[napi]
+    pub fn functions(&self, entry_points: Vec<u32>) -> Vec<Function> {
+        let traversal = traversal::traverse(self, &entry_points);
+        traversal
+            .function_ranges(self)
+            .into_iter()
+            .map(|(start, end)| {
+                Function::from_instrs(
+                    self.instructions[start..end].to_vec(),
+                    self.instructions[start].vram,
+                )
+            })
+            .collect()
+    }
+
+    /// Guesses the signature of each function reachable from the given entry points
+    ///
+    /// # Arguments
+    /// * `abi` - Decides the argument registers (optional, defaults to O32)
+    #[napi]
+    pub fn signatures(&self, entry_points: Vec<u32>, abi: Option<Abi>) -> Vec<Signature> {
+        let abi = abi.unwrap_or(Abi::O32);
+        let traversal = traversal::traverse(self, &entry_points);
+        traversal
+            .function_ranges(self)
+            .into_iter()
+            .map(|(start, end)| signature::infer(&self.instructions[start..end], abi))
+            .collect()
+    }
+}
@@ -0,0 +1,339 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+//! Databases of known SDK functions stored as masked words
+//!
+//! The binary format is little-endian:
+//!
+//! ```text
+//! "RSDB" version:u8 count:u32
+//! count * { name:str sdk:str words:u32 words * { word:u32 mask:u32 } }
+//! ```
+//!
+//! where `str` is a `u16` byte length followed by UTF-8.
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+
+use crate::fingerprint::{self, function_starts, FingerprintOptions};
+use crate::function::Function;
+use crate::section::CodeSection;
+
+const MAGIC: &[u8; 4] = b"RSDB";
+const VERSION: u8 = 1;
+
+/// A known function, with the bits that are left out cleared in `mask`
+struct Entry {
+    name: String,
+    sdk: String,
+    words: Vec<u32>,
+    masks: Vec<u32>,
+}
+
+impl Entry {
+    /// Counts the words of `instrs` that match, giving up after `allowed` misses
+    fn matching(&self, instrs: &[u32], allowed: usize) -> Option<usize> {
+        let mut misses = 0;
+        for ((&word, &mask), &raw) in self.words.iter().zip(&self.masks).zip(instrs) {
+            if raw & mask != word {
+                misses += 1;
+                if misses > allowed {
+                    return None;
+                }
+            }
+        }
+        Some(self.words.len() - misses)
+    }
+}
+
+/// Reads the binary format
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn bytes(&mut self, count: usize) -> napi::Result<&[u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + count)
+            .ok_or_else(|| {
+                napi::Error::new(
+                    napi::Status::InvalidArg,
+                    "Signature database is truncated".to_string(),
+                )
+            })?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> napi::Result<u16> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> napi::Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self) -> napi::Result<String> {
+        let length = self.u16()? as usize;
+        String::from_utf8(self.bytes(length)?.to_vec()).map_err(|_| {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                "Signature database has a name that is not UTF-8".to_string(),
+            )
+        })
+    }
+}
+
+fn write_string(out: &mut Vec<u8>, text: &str) {
+    out.extend_from_slice(&(text.len() as u16).to_le_bytes());
+    out.extend_from_slice(text.as_bytes());
+}
+
+/// Options for `identifyFunctions`
+#[napi(object)]
+pub struct IdentifyOptions {
+    /// Lowest confidence reported, from 0 to 1 (defaults to 0.9)
+    pub min_confidence: Option<f64>,
+    /// Only use the functions of this SDK version
+    pub sdk: Option<String>,
+}
+
+/// A known function found in a section
+#[napi(object)]
+pub struct IdentifiedFunction {
+    pub name: String,
+    /// SDK versions with this exact function
+    pub sdks: Vec<String>,
+    pub vram: u32,
+    pub size: u32,
+    /// Share of matching words, divided among functions of other names that match as well
+    pub confidence: f64,
+}
+
+/// Known SDK functions (libultra, PsyQ, PS2 SDK, PSP SDK, ...) to identify
+/// functions with
+#[napi]
+pub struct SignatureDatabase {
+    entries: Vec<Entry>,
+}
+
+#[napi]
+impl SignatureDatabase {
+    /// Creates an empty database
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        SignatureDatabase {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Loads a database saved with `toBuffer`
+    #[napi(factory)]
+    pub fn from_buffer(data: Buffer) -> napi::Result<Self> {
+        let mut reader = Reader {
+            data: &data,
+            position: 0,
+        };
+        if reader.bytes(4).ok() != Some(MAGIC.as_slice()) || reader.bytes(1)?[0] != VERSION {
+            return Err(napi::Error::new(
+                napi::Status::InvalidArg,
+                "Not a version 1 signature database".to_string(),
+            ));
+        }
+
+        let count = reader.u32()?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let name = reader.string()?;
+            let sdk = reader.string()?;
+            let length = reader.u32()?;
+            let mut words = Vec::new();
+            let mut masks = Vec::new();
+            for _ in 0..length {
+                let word = reader.u32()?;
+                let mask = reader.u32()?;
+                words.push(word & mask);
+                masks.push(mask);
+            }
+            entries.push(Entry {
+                name,
+                sdk,
+                words,
+                masks,
+            });
+        }
+        Ok(SignatureDatabase { entries })
+    }
+
+    /// Saves the database in its compact binary format
+    #[napi]
+    pub fn to_buffer(&self) -> Buffer {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for entry in &self.entries {
+            write_string(&mut out, &entry.name);
+            write_string(&mut out, &entry.sdk);
+            out.extend_from_slice(&(entry.words.len() as u32).to_le_bytes());
+            for (word, mask) in entry.words.iter().zip(&entry.masks) {
+                out.extend_from_slice(&word.to_le_bytes());
+                out.extend_from_slice(&mask.to_le_bytes());
+            }
+        }
+        out.into()
+    }
+
+    /// Adds a known function, masked like `Function.fingerprint`
+    ///
+    /// # Arguments
+    /// * `name` - Symbol name of the function
+    /// * `sdk` - The SDK version it comes from, like "libultra 2.0L"
+    /// * `function` - Its code
+    /// * `options` - Which fields to mask out (optional, defaults to relocated fields only)
+    #[napi]
+    pub fn add(
+        &mut self,
+        name: String,
+        sdk: String,
+        function: &Function,
+        options: Option<FingerprintOptions>,
+    ) -> napi::Result<()> {
+        if name.len() > u16::MAX as usize || sdk.len() > u16::MAX as usize {
+            return Err(napi::Error::new(
+                napi::Status::InvalidArg,
+                "Name and SDK must be shorter than 64 KiB".to_string(),
+            ));
+        }
+        let options = options.unwrap_or(FingerprintOptions {
+            ignore_relocs: None,
+            ignore_registers: None,
+        });
+        let masks = fingerprint::masks(function.instrs(), options);
+        let words = function
+            .instrs()
+            .iter()
+            .zip(&masks)
+            .map(|(instr, mask)| instr.raw() & mask)
+            .collect();
+        self.entries.push(Entry {
+            name,
+            sdk,
+            words,
+            masks,
+        });
+        Ok(())
+    }
+
+    /// Gets the number of functions in the database
+    #[napi(getter)]
+    pub fn length(&self) -> u32 {
+        self.entries.len() as u32
+    }
+
+    /// Gets the SDK versions in the database, in the order they were added
+    #[napi]
+    pub fn sdks(&self) -> Vec<String> {
+        let mut sdks: Vec<String> = Vec::new();
+        for entry in &self.entries {
+            if !sdks.contains(&entry.sdk) {
+                sdks.push(entry.sdk.clone());
+            }
+        }
+        sdks
+    }
+}
+
+impl Default for SignatureDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Names the functions of a section found in a signature database
+///
+/// Like `matchFingerprints`, only likely function starts are tried. At each of
+/// them the function with the most matching words wins, the longest on ties;
+/// short functions are easy to match by chance, so a low `minConfidence` is
+/// best used with long ones.
+///
+/// # Arguments
+/// * `section` - The code to search
+/// * `db` - The known functions
+/// * `options` - The lowest confidence to report and the SDK version to use (optional)
+#[napi]
+pub fn identify_functions(
+    section: &CodeSection,
+    db: &SignatureDatabase,
+    options: Option<IdentifyOptions>,
+) -> Vec<IdentifiedFunction> {
+    let (min_confidence, sdk) = match options {
+        Some(options) => (options.min_confidence.unwrap_or(0.9), options.sdk),
+        None => (0.9, None),
+    };
+    let entries: Vec<&Entry> = db
+        .entries
+        .iter()
+        .filter(|entry| !entry.words.is_empty())
+        .filter(|entry| sdk.as_ref().is_none_or(|sdk| entry.sdk == *sdk))
+        .collect();
+    let instrs = section.instrs();
+    let words: Vec<u32> = instrs.iter().map(|instr| instr.raw()).collect();
+
+    let mut found = Vec::new();
+    for start in function_starts(instrs) {
+        // (score, size, entries) of the best matches
+        let mut best: Option<(f64, usize, Vec<&Entry>)> = None;
+        for &entry in &entries {
+            let Some(window) = words.get(start..start + entry.words.len()) else {
+                continue;
+            };
+            // The epsilon keeps 0.9 of 10 words from rounding down to 8
+            let allowed = ((1.0 - min_confidence) * entry.words.len() as f64 + 1e-9) as usize;
+            let Some(matching) = entry.matching(window, allowed) else {
+                continue;
+            };
+            let score = matching as f64 / entry.words.len() as f64;
+            let size = entry.words.len();
+            match &mut best {
+                Some((best_score, best_size, best_entries))
+                    if (score, size) == (*best_score, *best_size) =>
+                {
+                    best_entries.push(entry);
+                }
+                Some((best_score, best_size, _)) if (score, size) < (*best_score, *best_size) => {}
+                _ => best = Some((score, size, vec![entry])),
+            }
+        }
+
+        let Some((score, size, best)) = best else {
+            continue;
+        };
+        let mut names: Vec<&str> = Vec::new();
+        for entry in &best {
+            if !names.contains(&entry.name.as_str()) {
+                names.push(&entry.name);
+            }
+        }
+        let name = names[0];
+        let confidence = score / names.len() as f64;
+        if confidence < min_confidence {
+            continue;
+        }
+        found.push(IdentifiedFunction {
+            name: name.to_string(),
+            sdks: best
+                .iter()
+                .filter(|entry| entry.name == name)
+                .map(|entry| entry.sdk.clone())
+                .collect(),
+            vram: instrs[start].vram,
+            size: size as u32 * 4,
+            confidence,
+        });
+    }
+    found
+}
@@ -0,0 +1,169 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use napi_derive::napi;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::dataflow::Dataflow;
+use crate::enums::Abi;
+use crate::fields;
+use crate::registers;
+
+/// Kind of value passed in an argument or return register
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Integer or pointer, passed in a GPR
+    Int,
+    /// Single precision float
+    Float,
+    /// Double precision float
+    Double,
+}
+
+/// A guessed function parameter
+#[napi(object)]
+pub struct Parameter {
+    /// Register the parameter is passed in
+    pub register: String,
+    pub kind: ValueKind,
+}
+
+/// Guessed signature of a function
+#[napi(object)]
+pub struct Signature {
+    /// Entry point of the function
+    pub vram: u32,
+    /// Parameters in order. Unused parameters before a used one are reported as `Int`
+    pub parameters: Vec<Parameter>,
+    /// Registers holding the return value (`$v0`, `$v1` or `$f0`)
+    pub return_registers: Vec<String>,
+    /// Kind of the return value, if the function returns one
+    pub return_kind: Option<ValueKind>,
+}
+
+/// Checks if the instruction writes a double to its FPR destination
+fn writes_double(instr: &RustInstruction) -> bool {
+    match instr.unique_id {
+        InstrId::cpu_cvt_d_s | InstrId::cpu_cvt_d_w | InstrId::cpu_cvt_d_l => true,
+        // Conversions from doubles produce other formats
+        InstrId::cpu_cvt_s_d
+        | InstrId::cpu_cvt_w_d
+        | InstrId::cpu_cvt_l_d
+        | InstrId::cpu_round_w_d
+        | InstrId::cpu_round_l_d
+        | InstrId::cpu_trunc_w_d
+        | InstrId::cpu_trunc_l_d
+        | InstrId::cpu_ceil_w_d
+        | InstrId::cpu_ceil_l_d
+        | InstrId::cpu_floor_w_d
+        | InstrId::cpu_floor_l_d => false,
+        _ => fields::reads_double(instr),
+    }
+}
+
+fn float_kind(is_double: bool) -> ValueKind {
+    if is_double {
+        ValueKind::Double
+    } else {
+        ValueKind::Float
+    }
+}
+
+/// Candidate registers for the `position`-th parameter, GPR first
+pub(crate) fn parameter_registers(abi: Abi, position: usize) -> (usize, Option<usize>) {
+    let gpr = registers::argument_gprs(abi)[position];
+    let fpr = match abi {
+        Abi::N32 | Abi::N64 => Some(registers::fpr(12 + position as u32)),
+        // Only the first two O32 parameters can be passed in FPRs
+        Abi::O32 | Abi::NUMERIC => registers::argument_fprs(abi).get(position).copied(),
+    };
+    (gpr, fpr)
+}
+
+/// Guesses the signature of a function from the registers live on entry and
+/// written before returning
+///
+/// Callees are assumed to take no arguments and to return nothing, so only the
+/// function's own instructions decide its signature. Values only written by a
+/// call and passed through to the caller are not reported.
+pub(crate) fn infer(instrs: &[RustInstruction], abi: Abi) -> Signature {
+    let vram = instrs.first().map(|instr| instr.vram).unwrap_or(0);
+    let dataflow = Dataflow::build_with_call_arguments(instrs, abi, 0);
+    let entry_live = if dataflow.cfg.blocks.is_empty() {
+        0
+    } else {
+        dataflow.live_in(0)
+    };
+
+    let mut slots: Vec<Option<Parameter>> = Vec::new();
+    for position in 0..registers::argument_gprs(abi).len() {
+        let (gpr, fpr) = parameter_registers(abi, position);
+        let parameter = if let Some(fpr) = fpr.filter(|&fpr| registers::contains(entry_live, fpr)) {
+            let uses = dataflow.entry_uses(fpr);
+            Some(Parameter {
+                register: registers::name(fpr, abi),
+                kind: float_kind(uses.iter().any(|&i| fields::reads_double(&instrs[i]))),
+            })
+        } else if registers::contains(entry_live, gpr) {
+            Some(Parameter {
+                register: registers::name(gpr, abi),
+                kind: ValueKind::Int,
+            })
+        } else {
+            None
+        };
+        slots.push(parameter);
+    }
+
+    let count = slots
+        .iter()
+        .rposition(Option::is_some)
+        .map_or(0, |last| last + 1);
+    let parameters = slots
+        .into_iter()
+        .take(count)
+        .enumerate()
+        .map(|(position, parameter)| {
+            parameter.unwrap_or_else(|| Parameter {
+                register: registers::name(parameter_registers(abi, position).0, abi),
+                kind: ValueKind::Int,
+            })
+        })
+        .collect();
+
+    // A return register counts when one of the function's own instructions
+    // writes the value it holds at a return
+    let own_definitions = |reg: usize| -> Vec<usize> {
+        dataflow
+            .reaching_returns(instrs, reg)
+            .into_iter()
+            .flatten()
+            .filter(|&i| !instrs[i].is_function_call())
+            .collect()
+    };
+
+    let mut return_registers = Vec::new();
+    let mut return_kind = None;
+    let float_definitions = own_definitions(registers::fpr(0));
+    if !float_definitions.is_empty() {
+        return_registers.push(registers::name(registers::fpr(0), abi));
+        return_kind = Some(float_kind(
+            float_definitions.iter().any(|&i| writes_double(&instrs[i])),
+        ));
+    } else if !own_definitions(2).is_empty() {
+        return_registers.push(registers::name(2, abi));
+        if !own_definitions(3).is_empty() {
+            return_registers.push(registers::name(3, abi));
+        }
+        return_kind = Some(ValueKind::Int);
+    }
+
+    Signature {
+        vram,
+        parameters,
+        return_registers,
+        return_kind,
+    }
+}
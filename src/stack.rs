@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use napi_derive::napi;
+use rabbitizer::AccessType;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::enums::Abi;
+use crate::fields;
+use crate::tracker::RegisterTracker;
+
+pub(crate) const SP: u32 = 29;
+pub(crate) const FP: u32 = 30;
+
+/// A callee-saved register stored by the prologue
+#[napi(object)]
+#[derive(Clone)]
+pub struct SavedRegister {
+    /// Register index (GPR or FPR, depending on the list it is in)
+    pub register: u32,
+    /// Offset of the slot from `$sp` once the frame is allocated
+    pub offset: i32,
+    /// Size of the slot in bytes
+    pub size: u32,
+    /// Address of the store instruction
+    pub vram: u32,
+}
+
+/// A `[start, end)` range of `$sp`-relative offsets
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StackRange {
+    pub start: i32,
+    pub end: i32,
+}
+
+/// Stack frame layout of a function
+#[napi(object)]
+pub struct StackFrame {
+    /// Number of bytes allocated by the prologue, 0 for leaf functions without a frame
+    pub size: u32,
+    pub saved_gprs: Vec<SavedRegister>,
+    pub saved_fprs: Vec<SavedRegister>,
+    /// Offsets accessed through `$sp` (or `$fp`) that are not register save slots
+    pub locals: Vec<StackRange>,
+    /// Whether `$fp` is set up as a frame pointer
+    pub uses_frame_pointer: bool,
+    /// Address of the instruction allocating the frame
+    pub allocation: Option<u32>,
+    /// Addresses of the instructions releasing the frame
+    pub deallocations: Vec<u32>,
+}
+
+fn is_callee_saved_gpr(reg: u32, abi: Abi) -> bool {
+    match reg {
+        16..=23 | 30 | 31 => true,
+        28 => matches!(abi, Abi::N32 | Abi::N64),
+        _ => false,
+    }
+}
+
+fn is_callee_saved_fpr(reg: u32, abi: Abi) -> bool {
+    match abi {
+        Abi::N32 => (20..=30).contains(&reg) && reg.is_multiple_of(2),
+        Abi::N64 => (24..=31).contains(&reg),
+        Abi::O32 | Abi::NUMERIC => (20..=31).contains(&reg),
+    }
+}
+
+fn is_fpr_access(instr: &RustInstruction) -> bool {
+    matches!(
+        instr.access_type(),
+        AccessType::FLOAT | AccessType::DOUBLEFLOAT
+    )
+}
+
+/// Returns the signed change to `$sp` made by `instr`, if it adjusts `$sp` by a known amount
+fn sp_adjustment(instr: &RustInstruction, tracker: &RegisterTracker) -> Option<i32> {
+    if instr.destination_gpr() != Some(SP) || fields::rs(instr) != SP {
+        return None;
+    }
+    match instr.unique_id {
+        InstrId::cpu_addiu | InstrId::cpu_daddiu | InstrId::cpu_addi | InstrId::cpu_daddi => {
+            Some(instr.processed_immediate())
+        }
+        InstrId::cpu_subu | InstrId::cpu_dsubu | InstrId::cpu_sub => tracker
+            .get(fields::rt(instr))
+            .map(|amount| -(amount as i32)),
+        InstrId::cpu_addu | InstrId::cpu_daddu | InstrId::cpu_add => {
+            tracker.get(fields::rt(instr)).map(|amount| amount as i32)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the offset from `$sp` that `$fp` is set to, if `instr` sets up a frame pointer
+fn frame_pointer_setup(instr: &RustInstruction) -> Option<i32> {
+    if instr.destination_gpr() != Some(FP) || fields::rs(instr) != SP {
+        return None;
+    }
+    match instr.unique_id {
+        InstrId::cpu_move | InstrId::cpu_addu | InstrId::cpu_daddu | InstrId::cpu_or
+            if fields::rt(instr) == 0 =>
+        {
+            Some(0)
+        }
+        InstrId::cpu_addiu | InstrId::cpu_daddiu => Some(instr.processed_immediate()),
+        _ => None,
+    }
+}
+
+/// Marks the registers `instr` writes to
+fn mark_written(instr: &RustInstruction, gprs: &mut [bool; 32], fprs: &mut [bool; 32]) {
+    if let Some(reg) = instr.destination_gpr() {
+        gprs[reg as usize] = true;
+    }
+    if instr.modifies_fd() {
+        fprs[fields::fd(instr) as usize] = true;
+    }
+    if instr.modifies_fs() {
+        fprs[fields::fs(instr) as usize] = true;
+    }
+    if instr.modifies_ft() {
+        fprs[fields::ft(instr) as usize] = true;
+    }
+}
+
+/// Adds `[start, end)` to a sorted list of ranges, merging overlapping ones
+fn insert_range(ranges: &mut Vec<StackRange>, start: i32, end: i32) {
+    ranges.push(StackRange { start, end });
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<StackRange> = Vec::with_capacity(ranges.len());
+    for range in ranges.drain(..) {
+        match merged.last_mut() {
+            Some(last) if range.start < last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    *ranges = merged;
+}
+
+/// Analyzes the prologue, epilogues and stack accesses of a function
+///
+/// The prologue is taken to end at the first control flow instruction (and
+/// its delay slot). Callee-saved registers stored there before being written
+/// are reported as saved.
+pub(crate) fn analyze(instrs: &[RustInstruction], abi: Abi) -> StackFrame {
+    let mut tracker = RegisterTracker::new();
+    let mut written_gprs = [false; 32];
+    let mut written_fprs = [false; 32];
+    let mut saved_gprs: Vec<SavedRegister> = Vec::new();
+    let mut saved_fprs: Vec<SavedRegister> = Vec::new();
+    let mut size = 0;
+    let mut allocation = None;
+    let mut frame_pointer = None;
+
+    // Offsets are first recorded relative to `$sp` on entry, and rebased onto
+    // the allocated frame once its size is known
+    let mut sp_delta = 0;
+
+    let mut prologue_end = instrs.len();
+    for (i, instr) in instrs.iter().enumerate() {
+        if i > prologue_end {
+            break;
+        }
+        if prologue_end == instrs.len() && instr.has_delay_slot() {
+            prologue_end = i + 1;
+        }
+
+        if let Some(adjustment) = sp_adjustment(instr, &tracker) {
+            if adjustment < 0 && allocation.is_none() {
+                size = (-adjustment) as u32;
+                allocation = Some(instr.vram);
+            }
+            sp_delta += adjustment;
+        } else if let Some(offset) = frame_pointer_setup(instr) {
+            frame_pointer = Some(offset + sp_delta);
+        } else if instr.does_store() && fields::rs(instr) == SP {
+            let offset = instr.processed_immediate() + sp_delta;
+            let slot_size = fields::access_size(instr.access_type());
+            let reg = fields::rt(instr);
+
+            let (saved, written, callee_saved) = if is_fpr_access(instr) {
+                (
+                    &mut saved_fprs,
+                    &written_fprs,
+                    is_callee_saved_fpr(reg, abi),
+                )
+            } else {
+                (
+                    &mut saved_gprs,
+                    &written_gprs,
+                    is_callee_saved_gpr(reg, abi),
+                )
+            };
+            let already_saved = saved.iter().any(|slot| slot.register == reg);
+            if callee_saved && !written[reg as usize] && !already_saved {
+                saved.push(SavedRegister {
+                    register: reg,
+                    offset,
+                    size: slot_size,
+                    vram: instr.vram,
+                });
+            }
+        }
+
+        mark_written(instr, &mut written_gprs, &mut written_fprs);
+        tracker.step(instr);
+    }
+
+    for slot in saved_gprs.iter_mut().chain(saved_fprs.iter_mut()) {
+        slot.offset += size as i32;
+    }
+    let frame_pointer = frame_pointer.map(|offset| offset + size as i32);
+
+    let mut locals = Vec::new();
+    let mut deallocations = Vec::new();
+    let mut tracker = RegisterTracker::new();
+    for instr in instrs {
+        if allocation.is_some()
+            && sp_adjustment(instr, &tracker) == Some(size as i32)
+            && Some(instr.vram) != allocation
+        {
+            deallocations.push(instr.vram);
+        }
+
+        if instr.does_load() || instr.does_store() {
+            let base = fields::rs(instr);
+            let offset = match (base, frame_pointer) {
+                (SP, _) => Some(instr.processed_immediate()),
+                (FP, Some(fp_offset)) => Some(instr.processed_immediate() + fp_offset),
+                _ => None,
+            };
+            let access_size = fields::access_size(instr.access_type()) as i32;
+            if let Some(offset) = offset {
+                let is_save_slot = saved_gprs
+                    .iter()
+                    .chain(saved_fprs.iter())
+                    .any(|slot| slot.offset == offset);
+                if !is_save_slot && offset >= 0 && offset < size as i32 && access_size > 0 {
+                    insert_range(&mut locals, offset, offset + access_size);
+                }
+            }
+        }
+        tracker.step(instr);
+    }
+
+    StackFrame {
+        size,
+        saved_gprs,
+        saved_fprs,
+        locals,
+        uses_frame_pointer: frame_pointer.is_some(),
+        allocation,
+        deallocations,
+    }
+}
@@ -0,0 +1,128 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+use napi_derive::napi;
+
+use crate::enums::{AccessType, InstrId, InstrIdType};
+use crate::section::CodeSection;
+
+/// How often one instruction appears
+#[napi(object)]
+pub struct InstrIdCount {
+    pub id: InstrId,
+    pub name: String,
+    pub count: u32,
+}
+
+/// How often instructions of one `InstrIdType` appear, like `CpuCop1` or `R5900Mmi0`
+#[napi(object)]
+pub struct InstrIdTypeCount {
+    pub id_type: InstrIdType,
+    pub count: u32,
+}
+
+/// How often loads and stores of one size appear
+#[napi(object)]
+pub struct AccessTypeCount {
+    pub access_type: AccessType,
+    pub count: u32,
+}
+
+/// Instruction counts of a section
+#[napi(object)]
+pub struct SectionStatistics {
+    /// Number of words in the section
+    pub words: u32,
+    /// Words that do not decode to a valid instruction
+    pub invalid: u32,
+    /// Valid instructions printed as a pseudo-instruction, like `move` or `b`
+    pub pseudos: u32,
+    /// Counts of valid instructions, most frequent first
+    pub by_id: Vec<InstrIdCount>,
+    pub by_id_type: Vec<InstrIdTypeCount>,
+    pub by_access_type: Vec<AccessTypeCount>,
+}
+
+/// Counts the instructions of a section in one pass
+///
+/// Useful to tell which extensions a binary relies on, like 64-bit
+/// operations, branch likely, MMI or VFPU instructions. Lists are sorted from
+/// the most to the least frequent.
+///
+/// # Arguments
+/// * `section` - The code to count
+#[napi]
+pub fn statistics(section: &CodeSection) -> SectionStatistics {
+    let mut invalid = 0;
+    let mut pseudos = 0;
+    let mut by_id: HashMap<u32, InstrIdCount> = HashMap::new();
+    let mut by_id_type: HashMap<u32, InstrIdTypeCount> = HashMap::new();
+    let mut by_access_type: Vec<AccessTypeCount> = Vec::new();
+
+    for instr in section.instrs() {
+        let id = InstrId::from_rabbitizer(instr.unique_id);
+        let id_type = InstrIdType::from_name(instr.instr_id_type_name());
+        let (true, Some(id), Some(id_type)) = (instr.is_valid(), id, id_type) else {
+            invalid += 1;
+            continue;
+        };
+        if instr.is_pseudo() {
+            pseudos += 1;
+        }
+        by_id
+            .entry(id as u32)
+            .or_insert_with(|| InstrIdCount {
+                id,
+                name: instr.opcode_name().to_string(),
+                count: 0,
+            })
+            .count += 1;
+        by_id_type
+            .entry(id_type as u32)
+            .or_insert(InstrIdTypeCount { id_type, count: 0 })
+            .count += 1;
+
+        let access_type = AccessType::from(instr.access_type());
+        if access_type != AccessType::Invalid {
+            match by_access_type
+                .iter_mut()
+                .find(|count| count.access_type == access_type)
+            {
+                Some(count) => count.count += 1,
+                None => by_access_type.push(AccessTypeCount {
+                    access_type,
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    let mut by_id: Vec<InstrIdCount> = by_id.into_values().collect();
+    by_id.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then((a.id as u32).cmp(&(b.id as u32)))
+    });
+    let mut by_id_type: Vec<InstrIdTypeCount> = by_id_type.into_values().collect();
+    by_id_type.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then((a.id_type as u32).cmp(&(b.id_type as u32)))
+    });
+    by_access_type.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then((a.access_type as u32).cmp(&(b.access_type as u32)))
+    });
+
+    SectionStatistics {
+        words: section.instrs().len() as u32,
+        invalid,
+        pseudos,
+        by_id,
+        by_id_type,
+        by_access_type,
+    }
+}
@@ -0,0 +1,242 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use napi_derive::napi;
+use rabbitizer::AccessType;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::constprop::{ConstantPropagation, Value};
+use crate::dataflow::Dataflow;
+use crate::enums::Abi;
+use crate::fields;
+use crate::registers;
+use crate::stack;
+
+/// What the base pointer of a group of memory accesses is
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BaseKind {
+    /// A pointer passed in an argument register
+    Argument,
+    /// The stack frame of the function
+    Stack,
+    /// A fixed address
+    Global,
+    /// A pointer loaded from memory
+    Loaded,
+}
+
+/// Every access to one offset of a base pointer with the same size and type
+#[napi(object)]
+#[derive(Clone)]
+pub struct FieldAccess {
+    /// Offset from the base pointer
+    pub offset: i32,
+    /// Size in bytes
+    pub size: u32,
+    pub is_float: bool,
+    /// Whether loads zero-extend the value, unknown if the field is only written
+    pub is_unsigned: Option<bool>,
+    pub read: bool,
+    pub written: bool,
+    /// Addresses of the accessing instructions
+    pub vrams: Vec<u32>,
+}
+
+/// The fields accessed through one base pointer
+#[napi(object)]
+pub struct StructAccesses {
+    pub kind: BaseKind,
+    /// Argument register holding the pointer, for `Argument`
+    pub register: Option<String>,
+    /// Address of the base, for `Global`
+    pub address: Option<u32>,
+    /// Address of the instruction loading the pointer, for `Loaded`
+    pub vram: Option<u32>,
+    /// Accessed fields sorted by offset
+    pub fields: Vec<FieldAccess>,
+    /// A starting C struct definition covering the accessed fields
+    pub definition: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Base {
+    Argument(u32),
+    Stack,
+    Global(u32),
+    Loaded(u32),
+}
+
+/// C type of a field
+fn field_type(field: &FieldAccess) -> String {
+    if field.is_float {
+        return if field.size == 8 { "f64" } else { "f32" }.to_string();
+    }
+    let sign = if field.is_unsigned == Some(true) {
+        'u'
+    } else {
+        's'
+    };
+    format!("{}{}", sign, field.size * 8)
+}
+
+/// Builds a struct definition with padding between the accessed fields
+///
+/// When fields overlap, the first one wins and the others are noted in
+/// comments. Fields at negative offsets are left out.
+fn definition(name: &str, fields: &[FieldAccess]) -> String {
+    let mut out = format!("struct {} {{\n", name);
+    let mut cursor = 0;
+    for field in fields {
+        if field.offset < 0 {
+            continue;
+        }
+        let offset = field.offset as u32;
+        if offset < cursor {
+            out.push_str(&format!(
+                "    /* 0x{:02X} */ /* also accessed as {} */\n",
+                offset,
+                field_type(field)
+            ));
+            continue;
+        }
+        if offset > cursor {
+            out.push_str(&format!(
+                "    /* 0x{:02X} */ char pad{:X}[0x{:X}];\n",
+                cursor,
+                cursor,
+                offset - cursor
+            ));
+        }
+        out.push_str(&format!(
+            "    /* 0x{:02X} */ {} unk{:X};\n",
+            offset,
+            field_type(field),
+            offset
+        ));
+        cursor = offset + field.size;
+    }
+    out.push_str(&format!("}}; /* size >= 0x{:X} */\n", cursor));
+    out
+}
+
+/// Groups the loads and stores of a function by their base pointer
+///
+/// Bases are resolved with constant propagation: pointers derived from an
+/// argument register's entry value, `$sp`-relative stack addresses (offsets
+/// are relative to `$sp` after the frame is allocated) and constants. A base
+/// register holding a bare `lui` value is taken as a `%hi`/`%lo` pair, so each
+/// such access becomes a global of its own. Pointers loaded from memory are
+/// grouped by the load instruction.
+pub(crate) fn collect(instrs: &[RustInstruction], abi: Abi) -> Vec<StructAccesses> {
+    let propagation = ConstantPropagation::build(instrs, abi);
+    let dataflow = Dataflow::build(instrs, abi);
+    let frame_size = stack::analyze(instrs, abi).size as i64;
+    let arguments = registers::argument_gprs(abi);
+
+    let mut groups: BTreeMap<Base, BTreeMap<(i32, u32, bool), FieldAccess>> = BTreeMap::new();
+
+    for (index, instr) in instrs.iter().enumerate() {
+        if !instr.does_load() && !instr.does_store() {
+            continue;
+        }
+        let base_reg = fields::rs(instr);
+        let imm = instr.processed_immediate() as i64;
+        let value = propagation
+            .state_before(instrs, index)
+            .map_or(Value::Unknown, |state| state.get(base_reg));
+
+        let (base, offset) = match value {
+            Value::Stack(offset) => (Base::Stack, offset + imm + frame_size),
+            Value::Entry(reg, offset) if arguments.contains(&(reg as usize)) => {
+                (Base::Argument(reg), offset + imm)
+            }
+            Value::Const(address) if address & 0xFFFF == 0 => {
+                (Base::Global((address as u32).wrapping_add(imm as u32)), 0)
+            }
+            Value::Const(address) => (Base::Global(address as u32), imm),
+            _ => {
+                let reaching = dataflow.reaching(index, base_reg as usize);
+                let load = match (reaching.len(), reaching.first()) {
+                    (1, Some(&Some(def))) if instrs[def].does_load() => def,
+                    _ => continue,
+                };
+                (Base::Loaded(instrs[load].vram), imm)
+            }
+        };
+
+        let size = fields::access_size(instr.access_type());
+        let is_float = matches!(
+            instr.access_type(),
+            AccessType::FLOAT | AccessType::DOUBLEFLOAT
+        );
+        let field = groups
+            .entry(base)
+            .or_default()
+            .entry((offset as i32, size, is_float))
+            .or_insert_with(|| FieldAccess {
+                offset: offset as i32,
+                size,
+                is_float,
+                is_unsigned: None,
+                read: false,
+                written: false,
+                vrams: Vec::new(),
+            });
+        if instr.does_load() {
+            field.read = true;
+            if field.is_unsigned.is_none() && !is_float {
+                field.is_unsigned = Some(instr.does_unsigned_memory_access());
+            }
+        } else {
+            field.written = true;
+        }
+        field.vrams.push(instr.vram);
+    }
+
+    let function = instrs.first().map_or(0, |instr| instr.vram);
+    groups
+        .into_iter()
+        .map(|(base, fields)| {
+            let fields: Vec<FieldAccess> = fields.into_values().collect();
+            let (kind, register, address, vram, name) = match base {
+                Base::Argument(reg) => {
+                    let register = registers::name(reg as usize, abi);
+                    let name = format!("Arg_{}", &register[1..]);
+                    (BaseKind::Argument, Some(register), None, None, name)
+                }
+                Base::Stack => (
+                    BaseKind::Stack,
+                    None,
+                    None,
+                    None,
+                    format!("Frame_{:08X}", function),
+                ),
+                Base::Global(address) => (
+                    BaseKind::Global,
+                    None,
+                    Some(address),
+                    None,
+                    format!("D_{:08X}", address),
+                ),
+                Base::Loaded(vram) => (
+                    BaseKind::Loaded,
+                    None,
+                    None,
+                    Some(vram),
+                    format!("Unk_{:08X}", vram),
+                ),
+            };
+            StructAccesses {
+                kind,
+                register,
+                address,
+                vram,
+                definition: definition(&name, &fields),
+                fields,
+            }
+        })
+        .collect()
+}
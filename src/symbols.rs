@@ -0,0 +1,82 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+/// A named address read from a symbol file
+#[derive(Clone)]
+pub(crate) struct Symbol {
+    pub name: String,
+    pub address: u32,
+    /// Size in bytes, from a `size:0x40` attribute
+    pub size: Option<u32>,
+}
+
+pub(crate) fn parse_number(text: &str) -> Option<u32> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let value = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => text.parse().ok()?,
+    };
+    Some(if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    })
+}
+
+/// Parses a symbol file with lines like `name = 0x80001234; // size:0x40`
+///
+/// This is the format of splat's `symbol_addrs.txt` and of linker scripts.
+/// Blank lines and lines holding only a comment are skipped; of the
+/// attributes in the trailing comment only `size:` is used.
+pub(crate) fn parse(text: &str) -> napi::Result<Vec<Symbol>> {
+    let mut symbols = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let (definition, comment) = match line.find("//") {
+            Some(position) => (&line[..position], &line[position + 2..]),
+            None => (line, ""),
+        };
+        let definition = definition.trim();
+        if definition.is_empty() {
+            continue;
+        }
+
+        let invalid = || {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("Invalid symbol on line {}: {}", number + 1, line.trim()),
+            )
+        };
+        let definition = definition.strip_suffix(';').unwrap_or(definition);
+        let (name, value) = definition.split_once('=').ok_or_else(invalid)?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(invalid());
+        }
+        let address = parse_number(value).ok_or_else(invalid)?;
+        let size = comment
+            .split_whitespace()
+            .find_map(|attribute| attribute.strip_prefix("size:"))
+            .map(|size| parse_number(size).ok_or_else(invalid))
+            .transpose()?;
+
+        symbols.push(Symbol {
+            name: name.to_string(),
+            address,
+            size,
+        });
+    }
+    Ok(symbols)
+}
+
+/// Formats `name + 0x8`, or just `name` at offset 0
+pub(crate) fn with_offset(name: &str, offset: u32) -> String {
+    if offset == 0 {
+        name.to_string()
+    } else {
+        format!("{} + 0x{:X}", name, offset)
+    }
+}
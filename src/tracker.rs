@@ -0,0 +1,137 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::constprop::{State, Value};
+use crate::enums::Abi;
+use crate::fields;
+use crate::registers;
+
+/// Tracks GPR values built by `lui`/`addiu`/`ori` sequences along a straight
+/// line of instructions
+///
+/// This is intentionally simple: it does not merge state at branch targets,
+/// callers are expected to reset it at function boundaries. Values come from
+/// the constant propagation `State`; on top of it the tracker remembers the
+/// `%hi` parts that indexed table accesses are based on.
+#[derive(Clone)]
+pub(crate) struct RegisterTracker {
+    state: State,
+    /// `lui` values that survived being added to an unknown index,
+    /// as in `lui $at, %hi(table); addu $at, $at, $t6`
+    hi_bases: [Option<u32>; 32],
+    /// Tables a GPR was loaded from through an unknown index, as in
+    /// `lw $t6, %lo(table)($at)` after the `addu` above
+    tables: [Option<u32>; 32],
+    pending_call: bool,
+}
+
+impl RegisterTracker {
+    pub(crate) fn new() -> Self {
+        RegisterTracker {
+            state: State::entry(),
+            hi_bases: [None; 32],
+            tables: [None; 32],
+            pending_call: false,
+        }
+    }
+
+    /// Returns the known value of a GPR, if any
+    pub(crate) fn get(&self, reg: u32) -> Option<u32> {
+        match self.state.get(reg) {
+            Value::Const(value) => Some(value as u32),
+            _ => None,
+        }
+    }
+
+    /// Returns the `lui` value a GPR was last derived from, if any
+    pub(crate) fn hi_base(&self, reg: u32) -> Option<u32> {
+        self.hi_bases.get(reg as usize).copied().flatten()
+    }
+
+    /// Returns the table a GPR was loaded from with an index, if any
+    ///
+    /// A `jr` through such a register is a jump table.
+    pub(crate) fn table(&self, reg: u32) -> Option<u32> {
+        self.tables.get(reg as usize).copied().flatten()
+    }
+
+    /// Returns the address accessed by a load or store, if its base register is known
+    ///
+    /// Falls back to the `%hi` part of the base for indexed accesses, which
+    /// gives the start of the accessed table.
+    pub(crate) fn memory_address(&self, instr: &RustInstruction) -> Option<u32> {
+        let base = fields::rs(instr);
+        let offset = instr.processed_immediate() as u32;
+        self.get(base)
+            .or_else(|| self.hi_base(base))
+            .map(|base| base.wrapping_add(offset))
+    }
+
+    /// Forgets the `%hi` bases and tables of the registers in `set`
+    fn forget(&mut self, set: registers::RegSet) {
+        for reg in registers::iter(set).filter(|&reg| reg < 32) {
+            self.hi_bases[reg] = None;
+            self.tables[reg] = None;
+        }
+    }
+
+    /// Forgets every register a called function is allowed to overwrite
+    pub(crate) fn clobber_call(&mut self) {
+        self.state.clobber_call(Abi::O32);
+        self.forget(registers::call_clobbers(Abi::O32));
+    }
+
+    /// Applies the effect of `instr` to the tracked registers
+    ///
+    /// A call only clobbers registers once its delay slot has been processed.
+    pub(crate) fn step(&mut self, instr: &RustInstruction) {
+        let (rs, rt, rd) = (fields::rs(instr), fields::rt(instr), fields::rd(instr));
+        let imm = fields::immediate(instr);
+
+        // The register given a `%hi` base or a table, if any
+        let (dest, hi_base, table) = match instr.unique_id {
+            InstrId::cpu_lui | InstrId::rsp_lui => (rt, Some(imm << 16), None),
+            InstrId::cpu_addu
+            | InstrId::cpu_daddu
+            | InstrId::cpu_move
+            | InstrId::rsp_addu
+            | InstrId::rsp_move => {
+                let hi_base = match (self.hi_base(rs), self.hi_base(rt)) {
+                    (Some(hi), None) | (None, Some(hi)) => Some(hi),
+                    _ => None,
+                };
+                (rd, hi_base, None)
+            }
+            InstrId::cpu_lw => {
+                let table = match (self.get(rs), self.hi_base(rs)) {
+                    (None, Some(_)) => self.memory_address(instr),
+                    _ => None,
+                };
+                (rt, None, table)
+            }
+            _ => (0, None, None),
+        };
+
+        self.state.step(instr);
+        self.forget(registers::defs(instr));
+        if dest != 0 {
+            self.hi_bases[dest as usize] = hi_base;
+            self.tables[dest as usize] = table;
+        }
+
+        if self.pending_call {
+            self.pending_call = false;
+            self.clobber_call();
+        }
+        if instr.is_function_call() {
+            if instr.has_delay_slot() {
+                self.pending_call = true;
+            } else {
+                self.clobber_call();
+            }
+        }
+    }
+}
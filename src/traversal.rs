@@ -0,0 +1,231 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeSet;
+
+use napi_derive::napi;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::fields;
+use crate::section::CodeSection;
+use crate::tracker::RegisterTracker;
+
+/// A half-open `[start, end)` range of virtual addresses
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Result of a recursive descent over a CodeSection
+#[napi(object)]
+pub struct TraversalResult {
+    /// Ranges of instructions reachable from the entry points
+    pub code: Vec<AddressRange>,
+    /// Ranges never reached, most likely data embedded in the section
+    pub data: Vec<AddressRange>,
+    /// Entry points and direct call targets inside the section, sorted
+    pub functions: Vec<u32>,
+}
+
+/// Returns the target of a branch or a direct jump, if the instruction has one
+pub(crate) fn branch_target(instr: &RustInstruction) -> Option<u32> {
+    if instr.is_branch() || instr.is_jump_with_address() {
+        Some(instr.branch_vram_generic())
+    } else {
+        None
+    }
+}
+
+/// Checks if the instruction is a direct call (`jal`, `bal`, `bgezal`, ...)
+pub(crate) fn is_direct_call(instr: &RustInstruction) -> bool {
+    instr.does_link() && branch_target(instr).is_some()
+}
+
+/// Checks if execution never continues past this instruction (and its delay slot)
+pub(crate) fn ends_flow(instr: &RustInstruction) -> bool {
+    if instr.is_unconditional_branch() {
+        return true;
+    }
+    if instr.is_jump() && !instr.does_link() {
+        // `j`, `jr $ra` and jumptable jumps
+        return true;
+    }
+    matches!(instr.unique_id, InstrId::cpu_eret)
+}
+
+/// Returns the case addresses of a jump table jump, like the `jr $t6` after
+/// `lw $t6, %lo(table)($at)`
+///
+/// The table is read from the section itself, up to the first word that is
+/// not an address inside the section.
+pub(crate) fn jump_table_targets(
+    section: &CodeSection,
+    instr: &RustInstruction,
+    tracker: &RegisterTracker,
+) -> Vec<u32> {
+    if !instr.is_jumptable_jump() {
+        return Vec::new();
+    }
+    let Some(start) = tracker
+        .table(fields::rs(instr))
+        .and_then(|table| section.index_of(table))
+    else {
+        return Vec::new();
+    };
+    section.instrs()[start..]
+        .iter()
+        .map(|entry| entry.raw())
+        .take_while(|&target| section.index_of(target).is_some())
+        .collect()
+}
+
+/// Which words of a section were reached from a set of entry points
+pub(crate) struct Traversal {
+    pub reached: Vec<bool>,
+    pub functions: BTreeSet<u32>,
+}
+
+impl Traversal {
+    /// Returns the entry point of the function `vram` belongs to
+    ///
+    /// Functions are assumed to extend up to the next known entry point.
+    pub(crate) fn function_containing(&self, vram: u32) -> Option<u32> {
+        self.functions.range(..=vram).next_back().copied()
+    }
+
+    /// Visits every reached instruction in address order, together with the
+    /// function it belongs to and the register state right before it
+    ///
+    /// The register tracker is reset at the start of each function.
+    pub(crate) fn walk_functions<F>(&self, section: &CodeSection, mut visit: F)
+    where
+        F: FnMut(u32, &RustInstruction, &RegisterTracker),
+    {
+        let mut tracker = RegisterTracker::new();
+        let mut current = None;
+
+        for (i, instr) in section.instrs().iter().enumerate() {
+            if !self.reached[i] {
+                current = None;
+                continue;
+            }
+            if self.functions.contains(&instr.vram) || current.is_none() {
+                current = self.function_containing(instr.vram);
+                tracker = RegisterTracker::new();
+            }
+            if let Some(function) = current {
+                visit(function, instr, &tracker);
+                tracker.step(instr);
+            }
+        }
+    }
+
+    /// Returns the `[start, end)` instruction index range of every function
+    ///
+    /// A function extends up to the next known entry point, minus any
+    /// trailing words that were never reached.
+    pub(crate) fn function_ranges(&self, section: &CodeSection) -> Vec<(usize, usize)> {
+        let starts: Vec<usize> = self
+            .functions
+            .iter()
+            .filter_map(|&vram| section.index_of(vram))
+            .collect();
+
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                let mut end = starts.get(i + 1).copied().unwrap_or(self.reached.len());
+                while end > start + 1 && !self.reached[end - 1] {
+                    end -= 1;
+                }
+                (start, end)
+            })
+            .collect()
+    }
+
+    pub(crate) fn to_result(&self, section: &CodeSection) -> TraversalResult {
+        let mut code = Vec::new();
+        let mut data = Vec::new();
+
+        let mut run_start = 0;
+        for i in 1..=self.reached.len() {
+            if i == self.reached.len() || self.reached[i] != self.reached[run_start] {
+                let range = AddressRange {
+                    start: section.start().wrapping_add(run_start as u32 * 4),
+                    end: section.start().wrapping_add(i as u32 * 4),
+                };
+                if self.reached[run_start] {
+                    code.push(range);
+                } else {
+                    data.push(range);
+                }
+                run_start = i;
+            }
+        }
+
+        TraversalResult {
+            code,
+            data,
+            functions: self.functions.iter().copied().collect(),
+        }
+    }
+}
+
+/// Walks the control flow of `section` starting at each of `entry_points`
+///
+/// Invalid instructions stop the walk, so a path that runs into garbage is
+/// not marked as code past that point. Jump tables are followed when the
+/// table address is loaded on the same path as the `jr`.
+pub(crate) fn traverse(section: &CodeSection, entry_points: &[u32]) -> Traversal {
+    let instrs = section.instrs();
+    let mut reached = vec![false; instrs.len()];
+    let mut functions = BTreeSet::new();
+    let mut pending: Vec<usize> = Vec::new();
+
+    for &entry in entry_points {
+        if let Some(index) = section.index_of(entry) {
+            functions.insert(entry);
+            pending.push(index);
+        }
+    }
+
+    while let Some(mut i) = pending.pop() {
+        let mut last = None;
+        let mut tracker = RegisterTracker::new();
+        while i < instrs.len() && !reached[i] && instrs[i].is_valid() {
+            let instr = &instrs[i];
+            reached[i] = true;
+
+            for target in jump_table_targets(section, instr, &tracker) {
+                pending.extend(section.index_of(target));
+            }
+            tracker.step(instr);
+
+            if let Some(target) = branch_target(instr) {
+                if let Some(index) = section.index_of(target) {
+                    if is_direct_call(instr) {
+                        functions.insert(target);
+                    }
+                    pending.push(index);
+                }
+            }
+
+            if last == Some(i) {
+                break;
+            }
+            if ends_flow(instr) {
+                if !instr.has_delay_slot() {
+                    break;
+                }
+                last = Some(i + 1);
+            }
+            i += 1;
+        }
+    }
+
+    Traversal { reached, functions }
+}
@@ -0,0 +1,160 @@
+// SPDX-FileCopyrightText: © 2022-2024 Decompollaborate
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use napi_derive::napi;
+use rabbitizer::InstrId;
+use rabbitizer::Instruction as RustInstruction;
+
+use crate::enums::AccessType;
+use crate::fields;
+use crate::section::CodeSection;
+use crate::tracker::RegisterTracker;
+use crate::traversal::{self, Traversal};
+
+/// How an instruction refers to an address
+#[napi]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum XrefKind {
+    /// Loaded from
+    Read,
+    /// Stored to
+    Write,
+    /// Materialized in a register, usually by a `lui`/`addiu` pair
+    AddressOf,
+    /// Called with `jal`, `bal` or a resolved `jalr`
+    Call,
+    /// Branched or jumped to
+    Branch,
+    /// Jumped to through a jump table, from the `jr`
+    JumpTable,
+}
+
+/// A reference from an instruction to an address
+#[napi(object)]
+#[derive(Clone)]
+pub struct Xref {
+    /// Address of the referencing instruction
+    pub from: u32,
+    /// Referenced address
+    pub target: u32,
+    pub kind: XrefKind,
+    /// Size and type of the access for reads and writes, `Invalid` otherwise
+    pub access_type: AccessType,
+}
+
+fn is_address_of(instr: &RustInstruction, tracker: &RegisterTracker) -> bool {
+    let materializes = matches!(
+        instr.unique_id,
+        InstrId::cpu_addiu
+            | InstrId::cpu_daddiu
+            | InstrId::cpu_ori
+            | InstrId::rsp_addiu
+            | InstrId::rsp_ori
+    );
+    let base = fields::rs(instr);
+    materializes
+        && base != 0
+        && tracker.get(base).is_some()
+        && tracker.get(base) == tracker.hi_base(base)
+}
+
+/// Every address referenced by the reachable code of a CodeSection
+#[napi]
+pub struct XrefIndex {
+    by_target: BTreeMap<u32, Vec<Xref>>,
+}
+
+impl XrefIndex {
+    pub(crate) fn build(section: &CodeSection, traversal: &Traversal) -> Self {
+        let mut by_target: BTreeMap<u32, Vec<Xref>> = BTreeMap::new();
+        let mut add = |from: u32, target: u32, kind: XrefKind, access_type: AccessType| {
+            by_target.entry(target).or_default().push(Xref {
+                from,
+                target,
+                kind,
+                access_type,
+            });
+        };
+
+        traversal.walk_functions(section, |_, instr, tracker| {
+            let from = instr.vram;
+
+            if let Some(target) = traversal::branch_target(instr) {
+                let kind = if instr.does_link() {
+                    XrefKind::Call
+                } else {
+                    XrefKind::Branch
+                };
+                add(from, target, kind, AccessType::Invalid);
+            } else if instr.is_jumptable_jump() {
+                for target in traversal::jump_table_targets(section, instr, tracker) {
+                    add(from, target, XrefKind::JumpTable, AccessType::Invalid);
+                }
+            } else if instr.is_function_call() {
+                if let Some(target) = tracker.get(fields::rs(instr)) {
+                    add(from, target, XrefKind::Call, AccessType::Invalid);
+                }
+            } else if instr.does_load() || instr.does_store() {
+                if let Some(target) = tracker.memory_address(instr) {
+                    let kind = if instr.does_store() {
+                        XrefKind::Write
+                    } else {
+                        XrefKind::Read
+                    };
+                    add(from, target, kind, instr.access_type().into());
+                }
+            } else if is_address_of(instr, tracker) {
+                let mut after = tracker.clone();
+                after.step(instr);
+                if let Some(target) = after.get(fields::rt(instr)) {
+                    add(from, target, XrefKind::AddressOf, AccessType::Invalid);
+                }
+            }
+        });
+
+        XrefIndex { by_target }
+    }
+}
+
+#[napi]
+impl XrefIndex {
+    /// Gets every referenced address, sorted
+    #[napi]
+    pub fn targets(&self) -> Vec<u32> {
+        self.by_target.keys().copied().collect()
+    }
+
+    /// Gets the references to `vram`, in address order
+    #[napi]
+    pub fn references_to(&self, vram: u32) -> Vec<Xref> {
+        self.by_target.get(&vram).cloned().unwrap_or_default()
+    }
+
+    /// Gets the references to any address in `[start, end)`
+    #[napi]
+    pub fn references_in_range(&self, start: u32, end: u32) -> Vec<Xref> {
+        if start >= end {
+            return Vec::new();
+        }
+        let mut refs: Vec<Xref> = self
+            .by_target
+            .range(start..end)
+            .flat_map(|(_, refs)| refs.iter().cloned())
+            .collect();
+        refs.sort_by_key(|xref| xref.from);
+        refs
+    }
+
+    /// Gets the references made by the instruction at `vram`
+    #[napi]
+    pub fn references_from(&self, vram: u32) -> Vec<Xref> {
+        self.by_target
+            .values()
+            .flatten()
+            .filter(|xref| xref.from == vram)
+            .cloned()
+            .collect()
+    }
+}